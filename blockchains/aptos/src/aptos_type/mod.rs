@@ -11,7 +11,7 @@ mod parser;
 mod module;
 
 use serde::{Deserialize, Serialize};
-use account_address::AccountAddress;
+pub use account_address::AccountAddress;
 use chain_id::ChainId;
 use script::Script;
 use module::{Module, ModuleBundle};
@@ -48,6 +48,28 @@ pub struct RawTransaction {
     chain_id: ChainId,
 }
 
+impl RawTransaction {
+    pub fn sender(&self) -> &AccountAddress {
+        &self.sender
+    }
+
+    pub fn payload(&self) -> &TransactionPayload {
+        &self.payload
+    }
+
+    pub fn max_gas_amount(&self) -> u64 {
+        self.max_gas_amount
+    }
+
+    pub fn gas_unit_price(&self) -> u64 {
+        self.gas_unit_price
+    }
+
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+}
+
 /// Different kinds of transactions.
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum TransactionPayload {
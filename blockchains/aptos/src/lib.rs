@@ -2,8 +2,8 @@ mod parser;
 mod error;
 mod aptos_type;
 
-use rcc_trait_chain::Chain;
-use crate::error::AptosError;
+use rcc_trait_chain::{Chain, ParsedTransaction};
+pub use crate::error::AptosError;
 use crate::parser::{AptosTx, Parser};
 
 pub struct Aptos;
@@ -13,6 +13,11 @@ impl Chain<AptosError> for Aptos {
         let tx = Parser::parse(data)?;
         tx.get_result()
     }
+
+    fn parse_transaction(data: &Vec<u8>) -> Result<ParsedTransaction, AptosError> {
+        let tx = Parser::parse(data)?;
+        tx.get_parsed_transaction()
+    }
 }
 
 
@@ -21,6 +26,7 @@ impl Chain<AptosError> for Aptos {
 mod tests {
     use super::*;
     use hex::FromHex;
+    use rcc_trait_chain::Amount;
     use serde_json::Value::String;
 
     #[test]
@@ -32,4 +38,18 @@ mod tests {
         println!("json is {}", parse_result);
         assert_eq!(json, parse_result);
     }
+
+    #[test]
+    fn test_parse_transaction() {
+        let data = "8bbbb70ae8b90a8686b2a27f10e21e44f2fb64ffffcaa4bb0242e9f1ea698659010000000000000002000000000000000000000000000000000000000000000000000000000000000104636f696e087472616e73666572010700000000000000000000000000000000000000000000000000000000000000010a6170746f735f636f696e094170746f73436f696e000220834f4b75dcaacbd7c549a993cdd3140676e172d1fee0609bf6876c74aaa7116008400d0300000000009a0e0000000000006400000000000000b6b747630000000021";
+        let tx = Aptos::parse_transaction(&Vec::from_hex(data).unwrap()).unwrap();
+        assert_eq!(tx.overview.kind, "Transfer");
+        assert_eq!(tx.overview.from.unwrap(), "0x8bbbb70ae8b90a8686b2a27f10e21e44f2fb64ffffcaa4bb0242e9f1ea698659");
+        assert_eq!(tx.overview.to.unwrap(), "0x834f4b75dcaacbd7c549a993cdd3140676e172d1fee0609bf6876c74aaa71160");
+        assert_eq!(tx.overview.amount.unwrap(), Amount::new("200000", "0x1::aptos_coin::AptosCoin"));
+        assert_eq!(tx.overview.fee.unwrap(), Amount::new("373800", "0x1::aptos_coin::AptosCoin"));
+        assert_eq!(tx.overview.network.unwrap(), "33");
+        assert!(tx.warnings.is_empty());
+        assert_eq!(tx.raw, Aptos::parse(&Vec::from_hex(data).unwrap()).unwrap());
+    }
 }
@@ -1,10 +1,14 @@
 
 use crate::error::{AptosError, Result};
-use crate::aptos_type::RawTransaction;
+use crate::aptos_type::{AccountAddress, RawTransaction, TransactionPayload};
 
 use bcs;
+use rcc_trait_chain::{overview_kind, Amount, Detail, Overview, ParsedTransaction};
 use serde_json::{json, Value};
 
+// gas is paid in APT
+const APTOS_COIN: &str = "0x1::aptos_coin::AptosCoin";
+
 pub struct Parser;
 
 impl Parser {
@@ -48,4 +52,68 @@ impl AptosTx {
         });
         Ok(result.to_string())
     }
-}
\ No newline at end of file
+
+    pub fn get_parsed_transaction(&self) -> Result<ParsedTransaction> {
+        let mut warnings = vec![];
+        let mut transfer = None;
+        let detail = match self.tx.payload() {
+            TransactionPayload::EntryFunction(function) => {
+                let id = format!("{}::{}", function.module().short_str_lossless(), function.function());
+                let type_arguments = function.ty_args().iter().map(|ty| ty.to_string()).collect::<Vec<String>>();
+                transfer = Self::decode_transfer(&id, &type_arguments, function.args());
+                match &transfer {
+                    Some((to, amount)) => Detail::new("Transfer")
+                        .with_field("function", id)
+                        .with_field("to", to)
+                        .with_field("amount", &amount.value)
+                        .with_field("coin", &amount.unit),
+                    None => function.args().iter().enumerate().fold(
+                        Detail::new("EntryFunction")
+                            .with_field("function", id)
+                            .with_field("type_arguments", type_arguments.join(", ")),
+                        |detail, (index, arg)| detail.with_field(format!("arg{}", index), hex::encode(arg)),
+                    ),
+                }
+            }
+            TransactionPayload::Script(script) => {
+                warnings.push("the transaction runs a Move script, its effects can not be previewed".to_string());
+                Detail::new("Script")
+                    .with_field("code", hex::encode(script.code()))
+                    .with_field("type_arguments", script.ty_args().iter().map(|ty| ty.to_string()).collect::<Vec<String>>().join(", "))
+            }
+            TransactionPayload::ModuleBundle(bundle) => {
+                warnings.push("the transaction publishes Move modules".to_string());
+                Detail::new("ModuleBundle").with_field("modules", bundle.iter().count().to_string())
+            }
+        };
+        let (to, amount) = transfer.unzip();
+        // the most the sender pays, the fee actually charged depends on the gas used
+        let fee = self.tx.max_gas_amount() as u128 * self.tx.gas_unit_price() as u128;
+        let detail = vec![detail];
+        Ok(ParsedTransaction {
+            overview: Overview {
+                kind: overview_kind(&detail),
+                from: Some(format!("0x{}", self.tx.sender().to_hex())),
+                to,
+                amount,
+                fee: Some(Amount::new(fee.to_string(), APTOS_COIN)),
+                network: Some(self.tx.chain_id().to_string()),
+            },
+            detail,
+            warnings,
+            raw: self.get_result()?,
+        })
+    }
+
+    // 0x1::coin::transfer<CoinType>(to, amount) and 0x1::aptos_account::transfer(to, amount)
+    fn decode_transfer(id: &str, type_arguments: &[String], args: &[Vec<u8>]) -> Option<(String, Amount)> {
+        let coin = match id {
+            "0x1::coin::transfer" => type_arguments.first()?.clone(),
+            "0x1::aptos_account::transfer" => APTOS_COIN.to_string(),
+            _ => return None,
+        };
+        let to = AccountAddress::from_bytes(args.first()?).ok()?;
+        let amount = u64::from_le_bytes(args.get(1)?.as_slice().try_into().ok()?);
+        Some((format!("0x{}", to.to_hex()), Amount::new(amount.to_string(), coin)))
+    }
+}
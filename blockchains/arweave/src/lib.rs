@@ -1,9 +1,12 @@
 mod types;
 
-use rcc_trait_chain::Chain;
+use rcc_trait_chain::{overview_kind, Amount, Chain, Detail, Overview, ParsedTransaction};
+use ring::digest::{digest, SHA256};
 use serde_json::json;
-use crate::types::error::ArweaveError;
-use crate::types::transaction::Transaction;
+pub use crate::types::error::ArweaveError;
+use crate::types::transaction::{Base64, Transaction};
+
+const WINSTON: &str = "winston";
 
 pub struct Arweave {}
 
@@ -34,6 +37,49 @@ impl Chain<ArweaveError> for Arweave {
         };
         Ok(tx.to_string())
     }
+
+    fn parse_transaction(data: &Vec<u8>) -> Result<ParsedTransaction, ArweaveError> {
+        let raw = Arweave::parse(data)?;
+        // like parse, a transaction that does not deserialize is reported rather than failed
+        let tx = match serde_json::from_slice::<Transaction>(data) {
+            Ok(tx) => tx,
+            Err(e) => {
+                return Ok(ParsedTransaction {
+                    overview: Overview { kind: overview_kind(&[]), ..Overview::default() },
+                    warnings: vec![format!("unable to deserialize, reason: {}", e)],
+                    raw,
+                    ..ParsedTransaction::default()
+                })
+            }
+        };
+        let is_transfer = !tx.target.0.is_empty();
+        let detail = tx.tags.iter().fold(
+            Detail::new(if is_transfer { "Transfer" } else { "Data" })
+                .with_field("target", tx.target.to_string())
+                .with_field("quantity", tx.quantity.to_string())
+                .with_field("data_size", tx.data_size.to_string()),
+            |detail, tag| detail.with_field(String::from_utf8_lossy(&tag.name.0), String::from_utf8_lossy(&tag.value.0)),
+        );
+        let detail = vec![detail];
+        Ok(ParsedTransaction {
+            overview: Overview {
+                kind: overview_kind(&detail),
+                from: Some(owner_address(&tx.owner)),
+                to: is_transfer.then(|| tx.target.to_string()),
+                amount: Some(Amount::new(tx.quantity.to_string(), WINSTON)),
+                fee: Some(Amount::new(tx.reward.to_string(), WINSTON)),
+                network: None,
+            },
+            detail,
+            warnings: vec![],
+            raw,
+        })
+    }
+}
+
+// the address of a wallet is the SHA-256 of its RSA modulus
+fn owner_address(owner: &Base64) -> String {
+    Base64(digest(&SHA256, &owner.0).as_ref().to_vec()).to_string()
 }
 
 
@@ -137,4 +183,22 @@ mod tests {
         let deep_hash = deep_hash(DeepHashItem::from_children(deep_hash_item)).unwrap();
         println!("deep_hash {:?}", hex::encode(deep_hash));
     }
+
+    #[test]
+    fn test_parse_transaction() {
+        let tx_bytes = hex::decode("7b22666f726d6174223a322c226964223a227964426e4544586b6c74697248465f38396e446141654a51737968366f6356464836773362476462344551222c226c6173745f7478223a224967497a6b54724e413153472d61594c5647514d316555456d5f622d374a713346385f4243555f6a3655763235727a38307a464767433451645f51617a73792d222c226f776e6572223a2273535757445652386a4262656f47656d79344d31567568554d5f304375735852586454617669343650555663503831344b6d33737a6d566c6b37316c5f2d7242596a584e79724d7232355f4c574d48454f495179667132446e2d323969426259716b36303853476a33594849324c6e52367a3364784c4256787754356174547535695a6e4d665a2d2d4e51696e413634694947725451336e473241333173356c44543538565173583741465f656156437757596f79434b76556a4b6564754e6d5553496a476c37736974722d746f6f54646362416873686b6135354c4371643662724f32345a7a42306969655777727741794e344c5a70636a783336644d61523971314c356e5935642d426e535a68566b322d5f4138532d50375766527275645453555a4d46386656396f3243643535542d6835776c48503558786835424f3435545a656d7746527a464b5833714945774b4e5a785f75434468546c4d6d4b7078744d616a3269302d677849315165587248597637364b66675a79325532584d573248344d7066725f574f324b4d325f623663575155656e50446e717a67525873713647586448554667562d7169314d5f69344d4345364144352d516c50515f516342615f5a666c646f77464c325041435f68796b457944665253374d77785f467734376737307356625745314450394d456678474339766d4f69446564726f4732457176543056704d2d78497a7a6870475057485f7a4e46634e53716a38735f7a537171746e58746c635474626b373649704b38744b6a4466494871314a485a675f5f776f6279664d30666941714836664a617455434c6757504754624a394d3436754a7738624649373270795f324d647278426b572d734e426f6132315f672d3646554e48346448574138674745776b61352d424d6e76614d5970414b417151322d47475977317045222c2274616773223a5b7b226e616d65223a22515842774c553568625755222c2276616c7565223a2251584a44623235755a574e30227d2c7b226e616d65223a22515842774c565a6c636e4e70623234222c2276616c7565223a224d4334304c6a49227d2c7b226e616d65223a225132397564475675644331556558426c222c2276616c7565223a226447563464433977624746706267227d5d2c22746172676574223a2267483034555f4d4476684b64456553564871466e6b78377869346473754b6a39344f3971526835304c5351222c227175616e74697479223a223130303030303030222c2264617461223a22222c22646174615f73697a65223a2230222c22646174615f726f6f74223a22222c22726577617264223a2231343130353037383534227d").unwrap();
+        let tx = Arweave::parse_transaction(&tx_bytes).unwrap();
+        assert_eq!(tx.overview.kind, "Transfer");
+        assert_eq!(tx.overview.from.unwrap(), "t4o7Um8KjJV2OE3RdN-rBkM4NJVJXuFZOHGY1cTHCCY");
+        assert_eq!(tx.overview.to.unwrap(), "gH04U_MDvhKdEeSVHqFnkx7xi4dsuKj94O9qRh50LSQ");
+        assert_eq!(tx.overview.amount.unwrap(), Amount::new("10000000", "winston"));
+        assert_eq!(tx.overview.fee.unwrap(), Amount::new("1410507854", "winston"));
+        assert_eq!(tx.detail[0].fields[3].name, "App-Name");
+        assert_eq!(tx.detail[0].fields[3].value, "ArConnect");
+        assert_eq!(tx.raw, Arweave::parse(&tx_bytes).unwrap());
+
+        let tx = Arweave::parse_transaction(&b"{}".to_vec()).unwrap();
+        assert_eq!(tx.overview.kind, "Unknown");
+        assert_eq!(tx.warnings.len(), 1);
+    }
 }
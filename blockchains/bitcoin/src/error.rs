@@ -1,10 +1,12 @@
-use thiserror_no_std::Error;
+use thiserror::Error;
 use alloc::string::String;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum BitcoinError {
     #[error("bitcoin address derivation failed, reason: `{0}`")]
     AddressDerivationFailed(String),
+    #[error("bitcoin transaction is invalid, reason: `{0}`")]
+    InvalidTransaction(String),
 }
 
 pub type Result<T> = std::result::Result<T, BitcoinError>;
@@ -5,22 +5,39 @@
 extern crate std;
 
 #[cfg(all(not(feature = "std"), not(test)))]
-#[macro_use]
 extern crate core as std;
 extern crate alloc;
 
 use alloc::string::{ToString, String};
-use crate::error::{BitcoinError, Result};
+use crate::error::Result;
+pub use crate::error::BitcoinError;
 use std::str::{FromStr};
 use bitcoin::util::{base58};
 use bitcoin::util::bip32::{ExtendedPubKey, DerivationPath};
 use bitcoin::secp256k1::Secp256k1;
 use bitcoin::{Address};
+use rcc_trait_chain::{Chain, ParsedTransaction};
+use alloc::vec::Vec;
 use xyzpub::{convert_version, Version};
+use crate::transaction::BitcoinTx;
 
 mod error;
+mod transaction;
 mod xyzpub;
 
+pub struct Bitcoin;
+
+// the data is a PSBT, it carries the previous outputs and the derivation paths of the change
+impl Chain<BitcoinError> for Bitcoin {
+    fn parse(data: &Vec<u8>) -> Result<String> {
+        BitcoinTx::from_psbt(data)?.get_result()
+    }
+
+    fn parse_transaction(data: &Vec<u8>) -> Result<ParsedTransaction> {
+        BitcoinTx::from_psbt(data)?.get_parsed_transaction()
+    }
+}
+
 pub fn derive_address(xpub: String, path: String, script_type: String) -> Result<String> {
     let converted_xpub = convert_version(xpub, &Version::Xpub)
         .map_err(|_| BitcoinError::AddressDerivationFailed(String::from("xpub is not valid")))?;
@@ -51,6 +68,7 @@ pub fn derive_address(xpub: String, path: String, script_type: String) -> Result
 mod tests {
     use super::*;
     use crate::std::string::String;
+    use bitcoin::Network;
 
     // they audit color point vague response vital voice slogan coil depth vehicle
     #[test]
@@ -93,4 +111,57 @@ mod tests {
         let expected = BitcoinError::AddressDerivationFailed(String::from("xpub is not valid"));
         assert_eq!(expected, address);
     }
+
+    #[test]
+    fn test_parse_transaction() {
+        use bitcoin::consensus::serialize;
+        use bitcoin::util::bip32::Fingerprint;
+        use bitcoin::util::psbt::PartiallySignedTransaction;
+        use bitcoin::{OutPoint, PackedLockTime, Script, Sequence, Transaction, TxIn, TxOut, Witness};
+        use rcc_trait_chain::Amount;
+
+        let xpub = String::from("zpub6rMTvPvUKBmiHGygNQo2znmhF38gUvcu9WNdARryhdAewa1G2gWJiSvngKd99SpvgiQaiKdo5ymxjtc4HUmaQdDXPMzM2NyohatdNayrVZE");
+        let xpub = base58::from_check(&convert_version(xpub, &Version::Xpub).unwrap()).unwrap();
+        let account = ExtendedPubKey::decode(&xpub).unwrap();
+        let fingerprint = Fingerprint::from(&[0x73, 0xc5, 0xda, 0x0a][..]);
+        let secp = Secp256k1::new();
+        let key = |path: &str| account.derive_pub(&secp, &DerivationPath::from_str(path).unwrap()).unwrap().public_key;
+        let own = |path: &str| Address::p2wpkh(&bitcoin::PublicKey::new(key(path)), Network::Bitcoin).unwrap();
+        let recipient = Address::from_str("1Kw42PtjJV4VWYzDfaPekqy1V4kgXEpqz8").unwrap();
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: PackedLockTime(0),
+            input: vec![TxIn {
+                previous_output: OutPoint::default(),
+                script_sig: Script::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![
+                TxOut { value: 60_000, script_pubkey: recipient.script_pubkey() },
+                TxOut { value: 39_000, script_pubkey: own("m/1/0").script_pubkey() },
+            ],
+        };
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut { value: 100_000, script_pubkey: own("m/0/0").script_pubkey() });
+        psbt.inputs[0].bip32_derivation.insert(key("m/0/0"), (fingerprint, DerivationPath::from_str("m/84'/0'/0'/0/0").unwrap()));
+        psbt.outputs[1].bip32_derivation.insert(key("m/1/0"), (fingerprint, DerivationPath::from_str("m/84'/0'/0'/1/0").unwrap()));
+        let data = serialize(&psbt);
+
+        let tx = Bitcoin::parse_transaction(&data).unwrap();
+        assert_eq!(tx.overview.kind, "Transfer");
+        assert_eq!(tx.overview.from.unwrap(), own("m/0/0").to_string());
+        // the psbt can not prove the change belongs to the wallet, it stays in the amount with a warning
+        assert_eq!(tx.overview.to, None);
+        assert_eq!(tx.overview.amount.unwrap(), Amount::new("99000", "satoshi"));
+        assert_eq!(tx.overview.fee.unwrap(), Amount::new("1000", "satoshi"));
+        assert_eq!(tx.overview.network.unwrap(), "bitcoin");
+        assert_eq!(tx.detail.len(), 3);
+        assert_eq!(tx.warnings, vec![format!(
+            "the output to {} claims to be change at m/84'/0'/0'/1/0, it is not verified",
+            own("m/1/0")
+        )]);
+        assert_eq!(tx.raw, Bitcoin::parse(&data).unwrap());
+    }
 }
@@ -0,0 +1,169 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use bitcoin::consensus::deserialize;
+use bitcoin::util::bip32::{ChildNumber, Fingerprint};
+use bitcoin::util::psbt::PartiallySignedTransaction;
+use bitcoin::{Address, Network, Script};
+use rcc_trait_chain::{Amount, Detail, Overview, ParsedTransaction};
+use serde_json::{json, Value};
+use crate::error::{BitcoinError, Result};
+
+const SATOSHI: &str = "satoshi";
+
+struct ParsedInput {
+    address: Option<String>,
+    value: Option<u64>,
+    path: Option<String>,
+}
+
+struct ParsedOutput {
+    address: String,
+    value: u64,
+    // the path the psbt gives for an output under the fingerprint of the inputs, claimed change
+    path: Option<String>,
+}
+
+pub struct BitcoinTx {
+    network: Network,
+    inputs: Vec<ParsedInput>,
+    outputs: Vec<ParsedOutput>,
+}
+
+impl BitcoinTx {
+    pub fn from_psbt(data: &[u8]) -> Result<Self> {
+        let psbt: PartiallySignedTransaction = deserialize(data)
+            .map_err(|e| BitcoinError::InvalidTransaction(e.to_string()))?;
+        // the coin type of the derivation paths, 1' is testnet
+        let testnet = psbt.inputs.iter()
+            .flat_map(|input| input.bip32_derivation.values())
+            .any(|(_, path)| path.as_ref().get(1) == Some(&ChildNumber::Hardened { index: 1 }));
+        let network = if testnet { Network::Testnet } else { Network::Bitcoin };
+
+        let fingerprints = psbt.inputs.iter()
+            .flat_map(|input| input.bip32_derivation.values())
+            .map(|(fingerprint, _)| *fingerprint)
+            .collect::<Vec<Fingerprint>>();
+        let inputs = psbt.inputs.iter().zip(psbt.unsigned_tx.input.iter()).map(|(input, txin)| {
+            let previous_output = match (&input.witness_utxo, &input.non_witness_utxo) {
+                (Some(utxo), _) => Some(utxo.clone()),
+                (None, Some(tx)) => tx.output.get(txin.previous_output.vout as usize).cloned(),
+                (None, None) => None,
+            };
+            ParsedInput {
+                address: previous_output.as_ref().map(|output| address(&output.script_pubkey, network)),
+                value: previous_output.map(|output| output.value),
+                path: input.bip32_derivation.values().next().map(|(_, path)| path.to_string()),
+            }
+        }).collect::<Vec<ParsedInput>>();
+        let outputs = psbt.outputs.iter().zip(psbt.unsigned_tx.output.iter()).map(|(output, txout)| {
+            ParsedOutput {
+                address: address(&txout.script_pubkey, network),
+                value: txout.value,
+                path: output.bip32_derivation.values()
+                    .find(|(fingerprint, _)| fingerprints.contains(fingerprint))
+                    .map(|(_, path)| path.to_string()),
+            }
+        }).collect::<Vec<ParsedOutput>>();
+        Ok(Self { network, inputs, outputs })
+    }
+
+    // None when the psbt lacks the previous output of an input
+    fn fee(&self) -> Result<Option<u64>> {
+        let input_values = match self.inputs.iter().map(|input| input.value).collect::<Option<Vec<u64>>>() {
+            Some(values) => values,
+            None => return Ok(None),
+        };
+        let total_input = sum(input_values.iter().copied())?;
+        let total_output = sum(self.outputs.iter().map(|output| output.value))?;
+        total_input.checked_sub(total_output)
+            .map(Some)
+            .ok_or(BitcoinError::InvalidTransaction("the outputs spend more than the inputs".to_string()))
+    }
+
+    pub fn get_result(&self) -> Result<String> {
+        let inputs = self.inputs.iter().map(|input| json!({
+            "address": input.address,
+            "value": input.value,
+            "path": input.path,
+        })).collect::<Vec<Value>>();
+        let outputs = self.outputs.iter().map(|output| json!({
+            "address": output.address,
+            "value": output.value,
+            "path": output.path,
+        })).collect::<Vec<Value>>();
+        Ok(json!({
+            "network": self.network.to_string(),
+            "inputs": inputs,
+            "outputs": outputs,
+            "fee": self.fee()?,
+        }).to_string())
+    }
+
+    pub fn get_parsed_transaction(&self) -> Result<ParsedTransaction> {
+        let mut warnings = Vec::new();
+        let mut detail = Vec::new();
+        for input in self.inputs.iter() {
+            let mut input_detail = Detail::new("Input")
+                .with_field("address", input.address.clone().unwrap_or_else(|| "Unknown address".to_string()))
+                .with_field("amount", input.value.map_or("Unknown amount".to_string(), |value| value.to_string()));
+            if let Some(path) = &input.path {
+                input_detail = input_detail.with_field("path", path);
+            }
+            detail.push(input_detail);
+        }
+        for output in self.outputs.iter() {
+            let mut output_detail = Detail::new("Output")
+                .with_field("address", &output.address)
+                .with_field("amount", output.value.to_string());
+            if let Some(path) = &output.path {
+                output_detail = output_detail.with_field("path", path);
+            }
+            detail.push(output_detail);
+        }
+        let fee = self.fee()?;
+        if fee.is_none() {
+            warnings.push("some inputs carry no previous output, their amount and the fee are unknown".to_string());
+        }
+
+        // anyone building the psbt can tag an output with the wallet fingerprint, the claimed change stays in
+        // the amount until the signer has derived its key
+        for output in self.outputs.iter() {
+            if let Some(path) = &output.path {
+                warnings.push(format!("the output to {} claims to be change at {}, it is not verified", output.address, path));
+            }
+        }
+        let amount = sum(self.outputs.iter().map(|output| output.value))?;
+        let from = match self.inputs.first().and_then(|input| input.address.clone()) {
+            Some(first) if self.inputs.iter().all(|input| input.address.as_ref() == Some(&first)) => Some(first),
+            _ => None,
+        };
+        Ok(ParsedTransaction {
+            overview: Overview {
+                kind: "Transfer".to_string(),
+                from,
+                to: match self.outputs.as_slice() {
+                    [only] => Some(only.address.clone()),
+                    _ => None,
+                },
+                amount: Some(Amount::new(amount.to_string(), SATOSHI)),
+                fee: fee.map(|fee| Amount::new(fee.to_string(), SATOSHI)),
+                network: Some(self.network.to_string()),
+            },
+            detail,
+            warnings,
+            raw: self.get_result()?,
+        })
+    }
+}
+
+fn address(script: &Script, network: Network) -> String {
+    Address::from_script(script, network)
+        .map(|address| address.to_string())
+        .unwrap_or_else(|_| script.asm())
+}
+
+fn sum(mut values: impl Iterator<Item = u64>) -> Result<u64> {
+    values.try_fold(0u64, |acc, value| acc.checked_add(value))
+        .ok_or(BitcoinError::InvalidTransaction("the amount overflows".to_string()))
+}
@@ -2,7 +2,11 @@ use crate::errors::{CardanoError, R};
 use crate::structs::{CardanoSignarure, ParseContext, ParsedCardanoTx};
 use cardano_serialization_lib;
 use cardano_serialization_lib::crypto::{Ed25519Signature, PublicKey, Vkey, Vkeywitness};
+use cardano_serialization_lib::utils::from_bignum;
 use crate::traits::ToJSON;
+use rcc_trait_chain::{Amount, Detail, Overview, ParsedTransaction};
+
+const LOVELACE: &str = "lovelace";
 
 pub fn parse_tx(tx: Vec<u8>, context: ParseContext) -> R<ParsedCardanoTx> {
     let cardano_tx = cardano_serialization_lib::Transaction::from_bytes(tx)?;
@@ -14,6 +18,87 @@ pub fn parse_tx_to_json(tx: Vec<u8>, context: ParseContext) -> R<String> {
     Ok(cardano_tx.to_json().to_string())
 }
 
+// cardano needs the utxos of the context to parse, so it has no Chain impl and offers the shared model here
+pub fn parse_tx_to_parsed_transaction(tx: Vec<u8>, context: ParseContext) -> R<ParsedTransaction> {
+    let cardano_tx = cardano_serialization_lib::Transaction::from_bytes(tx)?;
+    // the parsed fee is already normalized to ADA, take lovelace from the body
+    let fee = from_bignum(&cardano_tx.body().fee());
+    let utxos = context.get_utxos();
+    let master_fingerprint = context.get_master_fingerprint();
+    let inputs = cardano_tx.body().inputs();
+    let has_unknown_input = (0..inputs.len()).map(|i| inputs.get(i)).any(|input| {
+        !utxos.iter().any(|utxo| {
+            utxo.get_index() == input.index()
+                && input.transaction_id().to_bytes() == utxo.get_transaction_hash()
+        })
+    });
+    // outputs back to an address of our own utxos are change, they are not part of the amount sent
+    let wallet_addresses = utxos
+        .iter()
+        .filter(|utxo| utxo.get_master_fingerprint() == master_fingerprint)
+        .map(|utxo| utxo.get_address())
+        .collect::<Vec<String>>();
+    let parsed = ParsedCardanoTx::from_cardano_tx(cardano_tx, context)?;
+
+    let from = parsed.get_from();
+    let to = parsed.get_to();
+    let mut warnings = vec![];
+    let mut detail = vec![];
+    for input in from.iter() {
+        let mut input_detail = Detail::new("Input")
+            .with_field("address", input.get_address())
+            .with_field("amount", input.get_value().to_string());
+        if let Some(path) = input.get_path() {
+            input_detail = input_detail.with_field("path", path);
+        }
+        detail.push(input_detail);
+    }
+    for output in to.iter() {
+        let mut output_detail = Detail::new("Output")
+            .with_field("address", output.get_address())
+            .with_field("amount", output.get_value().to_string());
+        if let Some(assets_text) = output.get_assets_text() {
+            output_detail = output_detail.with_field("assets", assets_text);
+        }
+        detail.push(output_detail);
+    }
+    if has_unknown_input {
+        warnings.push("some inputs are not in the context, their address and amount are unknown".to_string());
+    }
+    if let Some(deposit) = parsed.get_detail().get_deposit() {
+        warnings.push(format!("{} is locked as deposit for the stake key registration", deposit));
+    }
+
+    let external_outputs = to
+        .iter()
+        .filter(|output| !wallet_addresses.contains(&output.get_address()))
+        .collect::<Vec<_>>();
+    let amount = external_outputs
+        .iter()
+        .try_fold(0u64, |acc, cur| acc.checked_add(cur.get_value()))
+        .ok_or(CardanoError::InvalidTransaction("output amount overflows".to_string()))?;
+    let overview = Overview {
+        kind: parsed.get_overview().get_header_card().get_type(),
+        from: match from.as_slice() {
+            [only] => Some(only.get_address()),
+            _ => None,
+        },
+        to: match external_outputs.as_slice() {
+            [only] => Some(only.get_address()),
+            _ => None,
+        },
+        amount: Some(Amount::new(amount.to_string(), LOVELACE)),
+        fee: Some(Amount::new(fee.to_string(), LOVELACE)),
+        network: Some(parsed.get_network()),
+    };
+    Ok(ParsedTransaction {
+        overview,
+        detail,
+        warnings,
+        raw: parsed.to_json().to_string(),
+    })
+}
+
 pub fn compose_witness_set(signatures: Vec<CardanoSignarure>) -> R<String> {
     let mut witness_set = cardano_serialization_lib::TransactionWitnessSet::new();
     let mut vkeys = cardano_serialization_lib::crypto::Vkeywitnesses::new();
@@ -33,4 +118,64 @@ pub fn compose_witness_set(signatures: Vec<CardanoSignarure>) -> R<String> {
 
     witness_set.set_vkeys(&vkeys);
     Ok(hex::encode(witness_set.to_bytes()))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::{derive_address, AddressType};
+    use crate::structs::CardanoUtxo;
+    use cardano_serialization_lib::address::Address;
+    use bitcoin::bip32::DerivationPath;
+    use cardano_serialization_lib::crypto::TransactionHash;
+    use cardano_serialization_lib::utils::{to_bignum, Value};
+    use cardano_serialization_lib::{
+        Transaction, TransactionBody, TransactionInput, TransactionInputs, TransactionOutput,
+        TransactionOutputs, TransactionWitnessSet,
+    };
+    use core::str::FromStr;
+    use ed25519_bip32_core::XPrv;
+
+    fn xpub(seed: u8) -> String {
+        hex::encode(XPrv::normalize_bytes_force3rd([seed; 96]).public().as_ref())
+    }
+
+    #[test]
+    fn test_parse_tx_to_parsed_transaction() {
+        let utxo_hash = [1u8; 32];
+        let mine = derive_address(xpub(1), 0, 0, AddressType::Base, 1).unwrap();
+        let other = derive_address(xpub(2), 0, 0, AddressType::Base, 1).unwrap();
+
+        let mut inputs = TransactionInputs::new();
+        inputs.add(&TransactionInput::new(&TransactionHash::from_bytes(utxo_hash.to_vec()).unwrap(), 0));
+        let mut outputs = TransactionOutputs::new();
+        for (address, value) in [(&other, 1_000_000), (&mine, 8_800_000)] {
+            outputs.add(&TransactionOutput::new(
+                &Address::from_bech32(address).unwrap(),
+                &Value::new(&to_bignum(value)),
+            ));
+        }
+        let body = TransactionBody::new_tx_body(&inputs, &outputs, &to_bignum(200_000));
+        let tx = Transaction::new(&body, &TransactionWitnessSet::new(), None).to_bytes();
+
+        let master_fingerprint = hex::decode("73c5da0a").unwrap();
+        let utxo = CardanoUtxo::new(
+            master_fingerprint.clone(),
+            mine.clone(),
+            DerivationPath::from_str("m/1852'/1815'/0'/0/0").unwrap(),
+            10_000_000,
+            utxo_hash.to_vec(),
+            0,
+        );
+        let context = ParseContext::new(vec![utxo], vec![], xpub(1), master_fingerprint);
+
+        let parsed = parse_tx_to_parsed_transaction(tx, context).unwrap();
+        assert_eq!(parsed.overview.kind, "Transfer");
+        assert_eq!(parsed.overview.from, Some(mine));
+        // the change back to our own address is neither the receiver nor part of the amount
+        assert_eq!(parsed.overview.to, Some(other));
+        assert_eq!(parsed.overview.amount, Some(Amount::new("1000000", LOVELACE)));
+        assert_eq!(parsed.overview.fee, Some(Amount::new("200000", LOVELACE)));
+        assert_eq!(parsed.detail.len(), 3);
+        assert!(parsed.warnings.is_empty());
+    }
+}
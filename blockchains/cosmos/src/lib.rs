@@ -1,5 +1,6 @@
-use rcc_trait_chain::Chain;
-use crate::error::{CosmosError, Result};
+use rcc_trait_chain::{Chain, ParsedTransaction};
+use crate::error::Result;
+pub use crate::error::CosmosError;
 use crate::proto_wrapper::sign_doc::SignDoc;
 
 mod proto_wrapper;
@@ -13,13 +14,19 @@ impl Chain<CosmosError> for Cosmos {
             .map(|doc| serde_json::to_string(&doc)
                 .map_err(|err| CosmosError::SerializeFailed(err.to_string())))?
     }
+
+    fn parse_transaction(data: &Vec<u8>) -> Result<ParsedTransaction> {
+        let doc = SignDoc::parse(data)?;
+        let raw = serde_json::to_string(&doc).map_err(|err| CosmosError::SerializeFailed(err.to_string()))?;
+        doc.to_parsed_transaction(raw)
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use hex::FromHex;
-    use rcc_trait_chain::Chain;
+    use rcc_trait_chain::{Amount, Chain};
     use crate::Cosmos;
 
     #[test]
@@ -31,4 +38,19 @@ mod tests {
         assert_eq!(json, parse_result);
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_transaction() {
+        let buf_message = Vec::from_hex("0a8f010a8c010a1c2f636f736d6f732e62616e6b2e763162657461312e4d736753656e64126c0a2d636f736d6f7331786573766b723664306a39366a357a64637735666d717861766a767576717832796779376d70122d636f736d6f7331786573766b723664306a39366a357a64637735666d717861766a767576717832796779376d701a0c0a057374616b65120331303012580a500a460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a21035bc6eee695a089c273b690d7123c84cf6dbcb91e613c8b60b79422a1ee68490612040a0208011803120410c09a0c1a04746573742001").unwrap();
+        let tx = Cosmos::parse_transaction(&buf_message).unwrap();
+        assert_eq!(tx.overview.kind, "MsgSend");
+        assert_eq!(tx.overview.from.unwrap(), "cosmos1xesvkr6d0j96j5zdcw5fmqxavjvuvqx2ygy7mp");
+        assert_eq!(tx.overview.to.unwrap(), "cosmos1xesvkr6d0j96j5zdcw5fmqxavjvuvqx2ygy7mp");
+        assert_eq!(tx.overview.amount.unwrap(), Amount::new("100", "stake"));
+        assert_eq!(tx.overview.fee, None);
+        assert_eq!(tx.overview.network.unwrap(), "test");
+        assert_eq!(tx.detail[0].fields.len(), 3);
+        assert!(tx.warnings.is_empty());
+        assert_eq!(tx.raw, Cosmos::parse(&buf_message).unwrap());
+    }
+}
@@ -1,10 +1,19 @@
 use cosmos_sdk_proto as proto;
 use cosmos_sdk_proto::prost::bytes::Bytes;
 use cosmos_sdk_proto::traits::Message;
+use rcc_trait_chain::{overview_kind, Amount, Detail, Overview, ParsedTransaction};
 use serde::Serialize;
+use serde_json::Value;
 use crate::{CosmosError, Result};
 use crate::proto_wrapper::auth_info::AuthInfo;
 use crate::proto_wrapper::body::Body;
+use crate::proto_wrapper::msg::base::Coin;
+use crate::proto_wrapper::msg::msg::NotSupportMessage;
+
+// the fields of the messages that name who sends, who receives and how much
+const FROM_FIELDS: [&str; 6] = ["from_address", "delegator_address", "sender", "voter", "grantee", "signer"];
+const TO_FIELDS: [&str; 4] = ["to_address", "validator_dst_address", "validator_address", "receiver"];
+const AMOUNT_FIELDS: [&str; 2] = ["amount", "token"];
 
 #[derive(Serialize)]
 pub struct SignDoc {
@@ -35,4 +44,61 @@ impl SignDoc {
         let proto_sign_doc: proto::cosmos::tx::v1beta1::SignDoc = Message::decode(Bytes::from(data.clone())).map_err(|e| CosmosError::ParseFailed(format!("proto SignDoc deserialize failed {}", e.to_string())))?;
         SignDoc::from(proto_sign_doc)
     }
+
+    pub fn to_parsed_transaction(&self, raw: String) -> Result<ParsedTransaction> {
+        let mut warnings = vec![];
+        let messages = self.body.messages.iter().map(|message| message.to_json()).collect::<Result<Vec<Value>>>()?;
+        let detail = messages.iter().map(|message| {
+            let type_url = message["type"].as_str().unwrap_or_default();
+            let value = &message["value"];
+            if type_url == NotSupportMessage::TYPE_URL {
+                let type_url = value["type_url"].as_str().unwrap_or_default();
+                warnings.push(format!("message {} is not supported, its content can not be previewed", type_url));
+                return Detail::new(message_kind(type_url));
+            }
+            if message_kind(type_url) == "MsgExec" {
+                warnings.push("the transaction executes messages on behalf of another account".to_string());
+            }
+            value.as_object().into_iter().flatten().fold(Detail::new(message_kind(type_url)), |detail, (name, value)| {
+                detail.with_field(name, value.as_str().map_or_else(|| value.to_string(), String::from))
+            })
+        }).collect::<Vec<Detail>>();
+        // who and how much are only told for a transaction of a single message
+        let value = match messages.as_slice() {
+            [message] => &message["value"],
+            _ => &Value::Null,
+        };
+        let field = |names: &[&str]| names.iter().find_map(|name| value[*name].as_str().map(String::from));
+        let amount = AMOUNT_FIELDS.iter().find_map(|name| match &value[*name] {
+            Value::Array(coins) if coins.len() == 1 => coin_amount(&coins[0]),
+            coin @ Value::Object(_) => coin_amount(coin),
+            _ => None,
+        });
+        let fee = self.auth_info.fee.as_ref().and_then(|fee| match fee.amount.as_slice() {
+            [Coin { denom, amount }] => Some(Amount::new(amount, denom)),
+            _ => None,
+        });
+        Ok(ParsedTransaction {
+            overview: Overview {
+                kind: overview_kind(&detail),
+                from: field(&FROM_FIELDS),
+                to: field(&TO_FIELDS),
+                amount,
+                fee,
+                network: Some(self.chain_id.clone()),
+            },
+            detail,
+            warnings,
+            raw,
+        })
+    }
+}
+
+// "/cosmos.bank.v1beta1.MsgSend" is a MsgSend
+fn message_kind(type_url: &str) -> String {
+    type_url.rsplit('.').next().unwrap_or(type_url).trim_start_matches('/').to_string()
+}
+
+fn coin_amount(coin: &Value) -> Option<Amount> {
+    Some(Amount::new(coin["amount"].as_str()?, coin["denom"].as_str()?))
 }
@@ -1,5 +1,5 @@
-use rcc_trait_chain::Chain;
-use crate::error::NearError;
+use rcc_trait_chain::{Chain, ParsedTransaction};
+pub use crate::error::NearError;
 
 mod error;
 mod parser;
@@ -14,6 +14,11 @@ impl Chain<NearError> for Near {
         let tx = Parser::parse(data)?;
         tx.get_result()
     }
+
+    fn parse_transaction(data: &Vec<u8>) -> Result<ParsedTransaction, NearError> {
+        let tx = Parser::parse(data)?;
+        tx.get_parsed_transaction()
+    }
 }
 
 
@@ -21,6 +26,7 @@ impl Chain<NearError> for Near {
 mod tests {
     use super::*;
     use hex::FromHex;
+    use rcc_trait_chain::Amount;
 
     #[test]
     fn test() {
@@ -29,5 +35,25 @@ mod tests {
         let mut buf_message = Vec::from_hex(data).unwrap();
         assert_eq!(json, Near::parse(&mut buf_message).unwrap_or_else(|e| e.to_string()));
     }
-}
 
+    #[test]
+    fn test_parse_transaction() {
+        let data = Vec::from_hex("40000000353862633234353938303464326564383736343166626465343062306439363334316362663033313362376466346263346636306661326634326336303263330058bc2459804d2ed87641fbde40b0d96341cbf0313b7df4bc4f60fa2f42c602c389772d10bc5400001000000064656d6f303631372e746573746e65746ce5b0c72ea21d29c9cf8cde859d2ddd466a70e1f8f1069742876e259fb157440100000003000000ed95c28f055a2a000000000000").unwrap();
+        let tx = Near::parse_transaction(&data).unwrap();
+        assert_eq!(tx.overview.kind, "Transfer");
+        assert_eq!(tx.overview.from.unwrap(), "58bc2459804d2ed87641fbde40b0d96341cbf0313b7df4bc4f60fa2f42c602c3");
+        assert_eq!(tx.overview.to.unwrap(), "demo0617.testnet");
+        assert_eq!(tx.overview.amount.unwrap(), Amount::new("200000000000000000000000", "yoctoNEAR"));
+        assert_eq!(tx.overview.fee, None);
+        assert_eq!(tx.detail.len(), 1);
+        assert!(tx.warnings.is_empty());
+        assert_eq!(tx.raw, Near::parse(&data).unwrap());
+    }
+
+    #[test]
+    fn test_parse_transaction_deposit_overflow() {
+        // two transfers of u128::MAX
+        let data = Vec::from_hex("40000000353862633234353938303464326564383736343166626465343062306439363334316362663033313362376466346263346636306661326634326336303263330058bc2459804d2ed87641fbde40b0d96341cbf0313b7df4bc4f60fa2f42c602c389772d10bc5400001000000064656d6f303631372e746573746e65746ce5b0c72ea21d29c9cf8cde859d2ddd466a70e1f8f1069742876e259fb157440200000003ffffffffffffffffffffffffffffffff03ffffffffffffffffffffffffffffffff").unwrap();
+        assert!(Near::parse_transaction(&data).is_err());
+    }
+}
@@ -1,10 +1,12 @@
 use crate::error::Result;
 use crate::primitives::PrimitivesTxParser;
+use rcc_trait_chain::ParsedTransaction;
 
 pub type NearTx = Box<dyn Tx>;
 
 pub trait Tx {
     fn get_result(&self) -> Result<String>;
+    fn get_parsed_transaction(&self) -> Result<ParsedTransaction>;
 }
 
 
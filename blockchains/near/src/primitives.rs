@@ -2,11 +2,16 @@ mod transaction;
 mod types;
 
 use borsh::BorshDeserialize;
+use near_primitives_core::account::AccessKeyPermission;
+use rcc_trait_chain::{overview_kind, Amount, Detail, Overview, ParsedTransaction};
 use serde_json::{json, Value};
 use crate::error::{Result, NearError};
 use crate::parser::{NearTx, Tx};
+use crate::primitives::transaction::Action;
 use hex::ToHex;
 
+const YOCTO_NEAR: &str = "yoctoNEAR";
+
 pub struct PrimitivesTxParser;
 
 
@@ -36,6 +41,71 @@ impl Tx for PrimitivesTx {
         });
         Ok(result.to_string())
     }
+
+    fn get_parsed_transaction(&self) -> Result<ParsedTransaction> {
+        let mut warnings = vec![];
+        let detail = self.tx.actions.iter().map(|action| match action {
+            Action::CreateAccount(_) => Detail::new("CreateAccount"),
+            Action::DeployContract(action) => {
+                warnings.push(format!("the transaction deploys a contract to {}", self.tx.receiver_id));
+                Detail::new("DeployContract").with_field("code_size", action.code.len().to_string())
+            }
+            Action::FunctionCall(action) => Detail::new("FunctionCall")
+                .with_field("method_name", &action.method_name)
+                .with_field("args", String::from_utf8_lossy(&action.args))
+                .with_field("gas", action.gas.to_string())
+                .with_field("deposit", action.deposit.to_string()),
+            Action::Transfer(action) => Detail::new("Transfer").with_field("deposit", action.deposit.to_string()),
+            Action::Stake(action) => Detail::new("Stake")
+                .with_field("stake", action.stake.to_string())
+                .with_field("public_key", action.public_key.to_string()),
+            Action::AddKey(action) => {
+                let permission = match &action.access_key.permission {
+                    AccessKeyPermission::FullAccess => {
+                        warnings.push(format!("the key {} gets full access to {}", action.public_key, self.tx.receiver_id));
+                        "FullAccess"
+                    }
+                    AccessKeyPermission::FunctionCall(_) => "FunctionCall",
+                };
+                Detail::new("AddKey")
+                    .with_field("public_key", action.public_key.to_string())
+                    .with_field("permission", permission)
+            }
+            Action::DeleteKey(action) => Detail::new("DeleteKey").with_field("public_key", action.public_key.to_string()),
+            Action::DeleteAccount(action) => {
+                warnings.push(format!("the transaction deletes the account {}", self.tx.receiver_id));
+                Detail::new("DeleteAccount").with_field("beneficiary_id", action.beneficiary_id.to_string())
+            }
+        }).collect::<Vec<Detail>>();
+        let deposits = self.tx.actions.iter().filter_map(|action| match action {
+            Action::Transfer(action) => Some(action.deposit),
+            Action::FunctionCall(action) => Some(action.deposit),
+            _ => None,
+        }).collect::<Vec<u128>>();
+        let amount = if deposits.is_empty() {
+            None
+        } else {
+            let total = deposits
+                .iter()
+                .try_fold(0u128, |acc, deposit| acc.checked_add(*deposit))
+                .ok_or(NearError::ParseFailed("the deposits overflow".to_string()))?;
+            Some(Amount::new(total.to_string(), YOCTO_NEAR))
+        };
+        Ok(ParsedTransaction {
+            overview: Overview {
+                kind: overview_kind(&detail),
+                from: Some(self.tx.signer_id.to_string()),
+                to: Some(self.tx.receiver_id.to_string()),
+                amount,
+                // the gas is bought at execution, a near transaction carries no fee
+                fee: None,
+                network: None,
+            },
+            detail,
+            warnings,
+            raw: self.get_result()?,
+        })
+    }
 }
 
 impl PrimitivesTx {
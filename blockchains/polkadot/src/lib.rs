@@ -60,7 +60,7 @@ pub fn get_sign_content(db_name: String, checksum: u32) -> String {
 mod tests {
     use std::path::Path;
     use crate::{init_polkadot_db, scanner, handle_stub, import_address, get_sign_content};
-    use crate::transaction_parser::parse_transaction;
+    use crate::transaction_parser::{parse_transaction, parse_transaction_to_parsed_transaction};
     use db_handling;
     use generate_message;
     use generate_message::parser::{Command, Show};
@@ -156,6 +156,23 @@ mod tests {
         remove();
     }
 
+    #[test]
+    fn test_parse_transaction_to_parsed_transaction() {
+        init();
+        add_meta();
+        add_address();
+        let db_path = get_db_path();
+        let tx = fs::read_to_string("./test_data/transactions/transfer").unwrap();
+        let parsed = parse_transaction_to_parsed_transaction(tx, db_path).unwrap();
+        assert_eq!(parsed.overview.kind, "transfer_keep_alive");
+        assert_eq!(parsed.overview.network.unwrap(), "Polkadot");
+        // a transfer back to the author
+        assert!(parsed.overview.from.is_some());
+        assert_eq!(parsed.overview.to, parsed.overview.from);
+        assert_eq!(parsed.detail.len(), 1);
+        remove();
+    }
+
     #[test]
     fn test_get_sign_content() {
         init();
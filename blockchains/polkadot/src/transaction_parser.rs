@@ -1,3 +1,6 @@
+use definitions::navigation::{Card, TransactionCard};
+use rcc_trait_chain::{overview_kind, Detail, Field, Overview, ParsedTransaction};
+use transaction_parsing::TransactionAction;
 use crate::WrappedTransactionAction;
 
 pub fn parse_transaction(transaction: String, db_name: String) -> String {
@@ -6,3 +9,81 @@ pub fn parse_transaction(transaction: String, db_name: String) -> String {
     }.to_json().to_string()
 }
 
+// the parser needs the metadata in the db, so polkadot has no Chain impl and offers the shared model here
+pub fn parse_transaction_to_parsed_transaction(transaction: String, db_name: String) -> Result<ParsedTransaction, String> {
+    let action = WrappedTransactionAction {
+        t: transaction_parsing::produce_output(transaction.as_str(), db_name.as_str())
+    };
+    let raw = action.to_json().to_string();
+    let (content, from, network) = match &action.t {
+        TransactionAction::Sign { content, author_info, network_info, .. } => {
+            (content, Some(author_info.base58.clone()), Some(network_info.title.clone()))
+        }
+        // the author is not in the db, the transaction can be read but not signed
+        TransactionAction::Read { r } => (r, None, None),
+        TransactionAction::Stub { .. } | TransactionAction::Derivations { .. } => {
+            return Err("the payload is not a transaction".to_string());
+        }
+    };
+
+    let mut warnings = vec![];
+    for card in [&content.error, &content.warning].into_iter().flatten().flatten() {
+        match &card.card {
+            Card::ErrorCard { f } | Card::WarningCard { f } => warnings.push(f.to_string()),
+            _ => {}
+        }
+    }
+    if from.is_none() {
+        warnings.push("the author is not imported, the transaction can not be signed".to_string());
+    }
+    let detail = content.method.as_deref().map(calls).unwrap_or_default();
+    let to = detail.iter()
+        .flat_map(|call| call.fields.iter())
+        .find(|field| field.name == "dest")
+        .map(|field| field.value.clone());
+    Ok(ParsedTransaction {
+        overview: Overview {
+            kind: overview_kind(&detail),
+            from,
+            to,
+            // the cards carry amounts already converted to units, e.g. "1.0 DOT", and not in planck
+            amount: None,
+            fee: None,
+            network,
+        },
+        detail,
+        warnings,
+        raw,
+    })
+}
+
+// one detail per call card, with the fields that follow it up to the next call
+fn calls(cards: &[TransactionCard]) -> Vec<Detail> {
+    let mut calls = vec![];
+    let mut pallet = None;
+    let mut field_name = None;
+    for card in cards {
+        match &card.card {
+            Card::PalletCard { f } => pallet = Some(f.to_string()),
+            Card::CallCard { f } => {
+                let mut call = Detail::new(f.method_name.clone());
+                if let Some(pallet) = pallet.take() {
+                    call = call.with_field("pallet", pallet);
+                }
+                calls.push(call);
+            }
+            Card::FieldNameCard { f } => field_name = Some(f.name.clone()),
+            Card::IdCard { f } => push_field(&mut calls, field_name.take(), "id", f.base58.clone()),
+            Card::BalanceCard { f } => push_field(&mut calls, field_name.take(), "balance", format!("{} {}", f.amount, f.units)),
+            Card::DefaultCard { f } | Card::TextCard { f } => push_field(&mut calls, field_name.take(), "value", f.to_string()),
+            _ => {}
+        }
+    }
+    calls
+}
+
+fn push_field(calls: &mut [Detail], name: Option<String>, default_name: &str, value: String) {
+    if let Some(call) = calls.last_mut() {
+        call.fields.push(Field { name: name.unwrap_or_else(|| default_name.to_string()), value });
+    }
+}
@@ -2,14 +2,14 @@
 
 extern crate alloc;
 
-use crate::error::SolanaError;
+pub use crate::error::SolanaError;
 use crate::message::Message;
 use crate::read::Read;
 
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use rcc_trait_chain::Chain;
+use rcc_trait_chain::{Chain, ParsedTransaction};
 
 mod compact;
 mod error;
@@ -38,4 +38,8 @@ impl Chain<SolanaError> for Sol {
     fn parse(data: &Vec<u8>) -> core::result::Result<String, SolanaError> {
         Sol::parse_message(data.clone().to_vec().as_mut()).and_then(|v| v.to_json_str())
     }
+
+    fn parse_transaction(data: &Vec<u8>) -> core::result::Result<ParsedTransaction, SolanaError> {
+        Sol::parse_message(data.clone().as_mut()).and_then(|v| v.to_parsed_transaction())
+    }
 }
@@ -2,6 +2,7 @@ extern crate alloc;
 
 use alloc::format;
 use alloc::string::{String, ToString};
+use alloc::vec;
 use alloc::vec::Vec;
 
 use crate::compact::Compact;
@@ -10,6 +11,7 @@ use crate::instruction::Instruction;
 use crate::read::Read;
 
 use bs58;
+use rcc_trait_chain::{overview_kind, Amount, Detail, Overview, ParsedTransaction};
 use serde_json::{json, Value};
 
 struct Signature {
@@ -81,6 +83,10 @@ impl Read<Message> for Message {
 
 impl Message {
     pub fn to_json_str(&self) -> Result<String> {
+        Ok(self.to_json_value()?.to_string())
+    }
+
+    fn to_json_value(&self) -> Result<Value> {
         let instructions = self
             .instructions
             .iter()
@@ -135,7 +141,58 @@ impl Message {
             "block_hash": bs58::encode(&self.block_hash.value).into_string(),
             "instructions": instructions,
         });
-        Ok(json.to_string())
+        Ok(json)
+    }
+
+    pub fn to_parsed_transaction(&self) -> Result<ParsedTransaction> {
+        let json = self.to_json_value()?;
+        let mut warnings = vec![];
+        let instructions = json["instructions"].as_array().cloned().unwrap_or_default();
+        let detail = instructions
+            .iter()
+            .map(|instruction| match &instruction["readable"] {
+                Value::String(reason) => {
+                    warnings.push(reason.clone());
+                    Detail::new("Unknown")
+                        .with_field("program_account", json_string(&instruction["raw"]["program_account"]))
+                        .with_field("data", json_string(&instruction["raw"]["data"]))
+                }
+                readable => readable["details"].as_object().into_iter().flatten().fold(
+                    Detail::new(json_string(&readable["method_name"]))
+                        .with_field("program", json_string(&readable["program_name"])),
+                    |detail, (name, value)| detail.with_field(name, json_string(value)),
+                ),
+            })
+            .collect::<Vec<Detail>>();
+        // who and how much are only told for a message of a single instruction
+        let overview = match instructions.as_slice() {
+            [instruction] => &instruction["readable"]["overview"],
+            _ => &Value::Null,
+        };
+        // only the amounts of the system program are in a known unit
+        let amount = match instructions.as_slice() {
+            [instruction] if instruction["readable"]["program_name"] == "System" => {
+                overview["amount"].as_str().map(|amount| Amount::new(amount, "lamports"))
+            }
+            _ => None,
+        };
+        Ok(ParsedTransaction {
+            overview: Overview {
+                kind: overview_kind(&detail),
+                // the fee payer signs first
+                from: overview["from"]
+                    .as_str()
+                    .map(String::from)
+                    .or_else(|| json["accounts"][0].as_str().map(String::from)),
+                to: overview["recipient"].as_str().map(String::from),
+                amount,
+                fee: None,
+                network: None,
+            },
+            detail,
+            warnings,
+            raw: json.to_string(),
+        })
     }
 
     pub fn validate(raw: &mut Vec<u8>) -> bool {
@@ -146,6 +203,10 @@ impl Message {
     }
 }
 
+fn json_string(value: &Value) -> String {
+    value.as_str().map_or_else(|| value.to_string(), String::from)
+}
+
 struct MessageHeader {
     num_required_signatures: u8,
     num_readonly_signed_accounts: u8,
@@ -181,7 +242,11 @@ impl Read<u8> for u8 {
 mod tests {
     use crate::message::Message;
     use crate::read::Read;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
     use hex::{self, FromHex};
+    use rcc_trait_chain::Amount;
     use serde_json::json;
 
     #[test]
@@ -228,6 +293,20 @@ mod tests {
         assert_eq!(message.to_json_str().unwrap(), json!({"accounts":["EX1oURpiPWWYUjVSK9KQR2qyqTBaR1EGfRNxkTsNk57Y","23qJPvgvCBGJFhPmemqcksVCtrLDKyXJh5ZstjfCuu9q","11111111111111111111111111111111"],"block_hash":"26673efpV4o6Cv5ZnEfYp3M18nkqhg6tyXF2A2JzeoCd","header":{"num_readonly_signed_accounts":0,"num_readonly_unsigned_accounts":1,"num_required_signatures":1},"instructions":[{"raw":{"account_indexes":[0,1],"accounts":"EX1oURpiPWWYUjVSK9KQR2qyqTBaR1EGfRNxkTsNk57Y,23qJPvgvCBGJFhPmemqcksVCtrLDKyXJh5ZstjfCuu9q","data":"3Bxs411Dtc7pkFQj","program_account":"11111111111111111111111111111111","program_index":2},"readable":{"details":{"amount":"100000000","from":"EX1oURpiPWWYUjVSK9KQR2qyqTBaR1EGfRNxkTsNk57Y","recipient":"23qJPvgvCBGJFhPmemqcksVCtrLDKyXJh5ZstjfCuu9q"},"method_name":"Transfer","overview":{"amount":"100000000","from":"EX1oURpiPWWYUjVSK9KQR2qyqTBaR1EGfRNxkTsNk57Y","recipient":"23qJPvgvCBGJFhPmemqcksVCtrLDKyXJh5ZstjfCuu9q"},"program_name":"System"}}]}).to_string())
     }
 
+    #[test]
+    fn test_parse_transaction() {
+        let mut raw = Vec::from_hex("01000103c8d842a2f17fd7aab608ce2ea535a6e958dffa20caf669b347b911c4171965530f957620b228bae2b94c82ddd4c093983a67365555b737ec7ddc1117e61c72e0000000000000000000000000000000000000000000000000000000000000000010295cc2f1f39f3604718496ea00676d6a72ec66ad09d926e3ece34f565f18d201020200010c0200000000e1f50500000000").unwrap();
+        let message = Message::read(&mut raw).unwrap();
+        let parsed = message.to_parsed_transaction().unwrap();
+        assert_eq!(parsed.overview.kind, "Transfer");
+        assert_eq!(parsed.overview.from.as_deref(), Some("EX1oURpiPWWYUjVSK9KQR2qyqTBaR1EGfRNxkTsNk57Y"));
+        assert_eq!(parsed.overview.to.as_deref(), Some("23qJPvgvCBGJFhPmemqcksVCtrLDKyXJh5ZstjfCuu9q"));
+        assert_eq!(parsed.overview.amount, Some(Amount::new("100000000", "lamports")));
+        assert_eq!(parsed.detail.len(), 1);
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(parsed.raw, message.to_json_str().unwrap());
+    }
+
     #[test]
     fn test_validate_message() {
         let message_invalid = "4c6f72656d20697073756d20646f6c6f722073697420616d65742c20636f6e73656374657475722061646970697363696e6720656c69742c2073656420646f20656975736d6f642074656d706f7220696e6369646964756e74207574206c61626f726520657420646f6c6f7265206d61676e6120616c697175612e";
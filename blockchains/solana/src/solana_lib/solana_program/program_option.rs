@@ -173,7 +173,7 @@ impl<T> COption<T> {
     /// assert_eq!(x.expect("the world is ending"), "value");
     /// ```
     ///
-    /// ```ignore{.should_panic}
+    /// ```ignore,should_panic
     /// let x: COption<&str> = COption::None;
     /// x.expect("the world is ending"); // panics with `the world is ending`
     /// ```
@@ -205,7 +205,7 @@ impl<T> COption<T> {
     /// assert_eq!(x.unwrap(), "air");
     /// ```
     ///
-    /// ```ignore{.should_panic}
+    /// ```ignore,should_panic
     /// let x: COption<&str> = COption::None;
     /// assert_eq!(x.unwrap(), "air"); // fails
     /// ```
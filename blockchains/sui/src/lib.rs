@@ -3,12 +3,15 @@
 extern crate alloc;
 
 pub mod errors;
+mod transaction;
 
 use sui_types::transaction::TransactionData;
 use sui_types::message::PersonalMessage;
+use alloc::string::String;
 use alloc::vec::Vec;
 use bcs;
 use errors::{Result, SuiError};
+use rcc_trait_chain::{Chain, ParsedTransaction};
 
 pub type Bytes = Vec<u8>;
 
@@ -22,12 +25,27 @@ pub fn parse_msg(msg: Bytes) -> Result<PersonalMessage> {
   Ok(msg)
 }
 
+pub struct Sui;
+
+impl Chain<SuiError> for Sui {
+  fn parse(data: &Vec<u8>) -> Result<String> {
+    let tx = parse_tx(data.clone())?;
+    serde_json::to_string(&tx).map_err(|_| SuiError::InvalidTransaction)
+  }
+
+  fn parse_transaction(data: &Vec<u8>) -> Result<ParsedTransaction> {
+    let tx = parse_tx(data.clone())?;
+    transaction::to_parsed_transaction(&tx)
+  }
+}
+
 
 #[cfg(test)]
 mod tests {
   extern crate std;
 
   use alloc::string::ToString;
+  use rcc_trait_chain::Amount;
   use serde_json::json;
   use super::*;
 
@@ -49,6 +67,21 @@ mod tests {
     assert_eq!(json!(tx.unwrap()).to_string(), "{\"V1\":{\"expiration\":\"None\",\"gas_data\":{\"budget\":100,\"owner\":\"0xebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869\",\"payment\":[[\"0xa2e3e42930675d9571a467eb5d4b22553c93ccb84e9097972e02c490b4e7a22a\",12983,\"2aS93HVFS54TNKfAFunntFgoRMbMCzp1bDfqSTRPRYpg\"]],\"price\":1000},\"kind\":{\"ProgrammableTransaction\":{\"commands\":[{\"SplitCoins\":[\"GasCoin\",[{\"Input\":1}]]},{\"TransferObjects\":[[{\"Result\":0}],{\"Input\":0}]}],\"inputs\":[{\"Pure\":[31,249,21,165,233,227,47,219,224,19,85,53,182,198,154,0,169,128,154,175,127,124,2,117,211,35,156,167,157,178,13,100]},{\"Pure\":[16,39,0,0,0,0,0,0]}]}},\"sender\":\"0xebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869\"}}");
   }
 
+  #[test]
+  fn test_parse_transaction() {
+    // SplitCoins from the gas coin, then TransferObjects of the new coin
+    let data = hex::decode("00000200201ff915a5e9e32fdbe0135535b6c69a00a9809aaf7f7c0275d3239ca79db20d6400081027000000000000020200010101000101020000010000ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec3944093886901a2e3e42930675d9571a467eb5d4b22553c93ccb84e9097972e02c490b4e7a22ab73200000000000020176c4727433105da34209f04ac3f22e192a2573d7948cb2fabde7d13a7f4f149ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869e803000000000000640000000000000000").unwrap();
+    let tx = Sui::parse_transaction(&data).unwrap();
+    assert_eq!(tx.overview.kind, "Batch");
+    assert_eq!(tx.overview.from.unwrap(), "0xebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869");
+    assert_eq!(tx.overview.to.unwrap(), "0x1ff915a5e9e32fdbe0135535b6c69a00a9809aaf7f7c0275d3239ca79db20d64");
+    assert_eq!(tx.overview.amount.unwrap(), Amount::new("10000", "MIST"));
+    assert_eq!(tx.overview.fee.unwrap(), Amount::new("100", "MIST"));
+    assert_eq!(tx.detail.len(), 2);
+    assert!(tx.warnings.is_empty());
+    assert_eq!(tx.raw, Sui::parse(&data).unwrap());
+  }
+
   #[test]
   fn test_parse_msg() {
     let msg = parse_msg(hex::decode("0a48656c6c6f2c20537569").unwrap());
@@ -0,0 +1,121 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rcc_trait_chain::{overview_kind, Amount, Detail, Overview, ParsedTransaction};
+use sui_types::base_types::SuiAddress;
+use sui_types::transaction::{Argument, CallArg, Command, TransactionData, TransactionKind};
+
+use crate::errors::{Result, SuiError};
+
+// gas and coin amounts are in MIST, 10^-9 SUI
+const MIST: &str = "MIST";
+
+pub fn to_parsed_transaction(tx: &TransactionData) -> Result<ParsedTransaction> {
+  let TransactionData::V1(data) = tx;
+  let mut warnings = vec![];
+  let mut to = None;
+  let mut amount = None;
+  let detail = match &data.kind {
+    TransactionKind::ProgrammableTransaction(programmable) => {
+      let inputs = &programmable.inputs;
+      let mut recipients = vec![];
+      let mut gas_splits = vec![];
+      let detail = programmable.commands.iter().map(|command| match command {
+        Command::TransferObjects(objects, recipient_argument) => {
+          let recipient = pure_address(inputs, recipient_argument);
+          recipients.push(recipient.clone());
+          Detail::new("TransferObjects")
+            .with_field("objects", objects.iter().map(argument).collect::<Vec<String>>().join(", "))
+            .with_field("recipient", recipient.unwrap_or_else(|| argument(recipient_argument)))
+        }
+        Command::SplitCoins(coin, amounts) => {
+          let amounts = amounts.iter().map(|value| pure_u64(inputs, value)).collect::<Vec<Option<u64>>>();
+          if let Argument::GasCoin = coin {
+            gas_splits.extend(amounts.iter().copied());
+          }
+          Detail::new("SplitCoins")
+            .with_field("coin", argument(coin))
+            .with_field("amounts", amounts.iter().map(|value| value.map_or("Unknown".to_string(), |v| v.to_string())).collect::<Vec<String>>().join(", "))
+        }
+        Command::MergeCoins(coin, coins) => Detail::new("MergeCoins")
+          .with_field("coin", argument(coin))
+          .with_field("coins", coins.iter().map(argument).collect::<Vec<String>>().join(", ")),
+        Command::MoveCall(call) => {
+          warnings.push(format!("the transaction calls {}::{}::{}, its effects can not be previewed", call.package, call.module, call.function));
+          Detail::new("MoveCall")
+            .with_field("function", format!("{}::{}::{}", call.package, call.module, call.function))
+            .with_field("arguments", call.arguments.iter().map(argument).collect::<Vec<String>>().join(", "))
+        }
+        Command::Publish(modules, _) => {
+          warnings.push("the transaction publishes Move modules".to_string());
+          Detail::new("Publish").with_field("modules", modules.len().to_string())
+        }
+        _ => Detail::new("Command"),
+      }).collect::<Vec<Detail>>();
+      if let [Some(recipient)] = recipients.as_slice() {
+        to = Some(recipient.clone());
+      }
+      // only a split of the gas coin tells how much SUI leaves the account
+      if !gas_splits.is_empty() {
+        match gas_splits.iter().copied().collect::<Option<Vec<u64>>>() {
+          Some(values) => {
+            let total = values
+              .iter()
+              .try_fold(0u64, |acc, value| acc.checked_add(*value))
+              .ok_or(SuiError::InvalidTransaction)?;
+            amount = Some(Amount::new(total.to_string(), MIST));
+          }
+          None => warnings.push("some amounts split from the gas coin are not pure inputs, the amount is unknown".to_string()),
+        }
+      }
+      detail
+    }
+    _ => {
+      warnings.push("this is a system transaction, it is not signed by users".to_string());
+      vec![Detail::new("SystemTransaction")]
+    }
+  };
+  Ok(ParsedTransaction {
+    overview: Overview {
+      kind: overview_kind(&detail),
+      from: Some(data.sender.to_string()),
+      to,
+      amount,
+      // the budget is the most the sender pays, the fee actually charged depends on the gas used
+      fee: Some(Amount::new(data.gas_data.budget.to_string(), MIST)),
+      network: None,
+    },
+    detail,
+    warnings,
+    raw: serde_json::to_string(tx).map_err(|_| SuiError::InvalidTransaction)?,
+  })
+}
+
+fn argument(argument: &Argument) -> String {
+  match argument {
+    Argument::GasCoin => "GasCoin".to_string(),
+    Argument::Input(index) => format!("Input({})", index),
+    Argument::Result(index) => format!("Result({})", index),
+    Argument::NestedResult(index, nested) => format!("NestedResult({}, {})", index, nested),
+  }
+}
+
+fn pure<'a>(inputs: &'a [CallArg], argument: &Argument) -> Option<&'a [u8]> {
+  match argument {
+    Argument::Input(index) => match inputs.get(*index as usize) {
+      Some(CallArg::Pure(bytes)) => Some(bytes),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn pure_u64(inputs: &[CallArg], argument: &Argument) -> Option<u64> {
+  bcs::from_bytes::<u64>(pure(inputs, argument)?).ok()
+}
+
+fn pure_address(inputs: &[CallArg], argument: &Argument) -> Option<String> {
+  bcs::from_bytes::<SuiAddress>(pure(inputs, argument)?).ok().map(|address| address.to_string())
+}
@@ -0,0 +1,18 @@
+use std::env;
+use std::path::PathBuf;
+
+// generates rcc_ffi.h from the exported functions into OUT_DIR, tests/c_abi.rs checks include/rcc_ffi.h matches it
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).expect("invalid cbindgen.toml");
+    match cbindgen::generate_with_config(&crate_dir, config) {
+        Ok(bindings) => {
+            bindings.write_to_file(out_dir.join("rcc_ffi.h"));
+        }
+        // a source that does not parse fails the build in rustc with a better message
+        Err(e) => println!("cargo:warning=rcc_ffi.h was not generated: {}", e),
+    }
+}
@@ -0,0 +1,357 @@
+/* the functions of a chain are exported only when rcc-ffi is built with the feature of that chain */
+
+#ifndef RCC_FFI_H
+#define RCC_FFI_H
+
+/* generated by cbindgen from the rcc-ffi sources, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of every call. The codes are stable, each crate has its own range and mirrors its error enum
+// variant by variant, codes of chains left out of the build are never returned.
+typedef enum RccErrorCode {
+  RCC_ERROR_CODE_OK = 0,
+  RCC_ERROR_CODE_NULL_POINTER = 1,
+  RCC_ERROR_CODE_INVALID_UTF8 = 2,
+  RCC_ERROR_CODE_INVALID_ARGUMENT = 3,
+  RCC_ERROR_CODE_PANIC = 4,
+  RCC_ERROR_CODE_SIGNER_SERIAL_MANAGER_ERROR = 100,
+  RCC_ERROR_CODE_SIGNER_SERIAL_TIMEOUT = 101,
+  RCC_ERROR_CODE_SIGNER_TVL_ERROR = 102,
+  RCC_ERROR_CODE_SIGNER_TVL_DESERILIAZE_ERROR = 103,
+  RCC_ERROR_CODE_SIGNER_NONE_SUPPORTED_COMMAND_ERROR = 104,
+  RCC_ERROR_CODE_SIGNER_SE_ERROR = 105,
+  RCC_ERROR_CODE_SIGNER_GENERATE_SIGNING_KEY_ERROR = 106,
+  RCC_ERROR_CODE_SIGNER_RSA_SIGN_ERROR = 107,
+  RCC_ERROR_CODE_SIGNER_RSA_VERIFY_ERROR = 108,
+  RCC_ERROR_CODE_SIGNER_SIGN_DATA_ERROR = 109,
+  RCC_ERROR_CODE_SIGNER_WRITE_SECRET_ERROR = 110,
+  RCC_ERROR_CODE_SIGNER_GET_PUBLIC_KEY_ERROR = 111,
+  RCC_ERROR_CODE_SIGNER_SIGNATURE_MISMATCH = 112,
+  RCC_ERROR_CODE_SIGNER_VERIFY_ERROR = 113,
+  RCC_ERROR_CODE_SIGNER_KEY_STORE_ERROR = 114,
+  RCC_ERROR_CODE_SIGNER_SECURE_CHANNEL_ERROR = 115,
+  RCC_ERROR_CODE_SIGNER_WRONG_PASSWORD = 116,
+  RCC_ERROR_CODE_SIGNER_WALLET_LOCKED = 117,
+  RCC_ERROR_CODE_SIGNER_WALLET_NOT_FOUND = 118,
+  RCC_ERROR_CODE_SIGNER_SECRET_NOT_SET = 119,
+  RCC_ERROR_CODE_SIGNER_INVALID_AUTH_TOKEN = 120,
+  RCC_ERROR_CODE_SIGNER_UNSUPPORTED_COMMAND = 121,
+  RCC_ERROR_CODE_SIGNER_CHIP_BUSY = 122,
+  RCC_ERROR_CODE_SIGNER_SE_STATUS_ERROR = 123,
+  RCC_ERROR_CODE_SIGNER_MNEMONIC_ERROR = 124,
+  RCC_ERROR_CODE_SIGNER_BIP85_ERROR = 125,
+  RCC_ERROR_CODE_SIGNER_POLICY_VIOLATION = 126,
+  RCC_ERROR_CODE_SIGNER_INVALID_POLICY = 127,
+  RCC_ERROR_CODE_SIGNER_AUDIT_LOG_ERROR = 128,
+  RCC_ERROR_CODE_SIGNER_AUDIT_LOG_TAMPERED = 129,
+  RCC_ERROR_CODE_SIGNER_ATTESTATION_ERROR = 130,
+  RCC_ERROR_CODE_APTOS_PARSE_FAILED = 200,
+  RCC_ERROR_CODE_APTOS_SERIALIZE_FAILED = 201,
+  RCC_ERROR_CODE_ARWEAVE_BASE64_DECODE = 300,
+  RCC_ERROR_CODE_ARWEAVE_FROM_UTF8 = 301,
+  RCC_ERROR_CODE_BITCOIN_ADDRESS_DERIVATION_FAILED = 400,
+  RCC_ERROR_CODE_BITCOIN_INVALID_TRANSACTION = 401,
+  RCC_ERROR_CODE_CARDANO_ADDRESS_ENCODING_ERROR = 500,
+  RCC_ERROR_CODE_CARDANO_DERIVATION_ERROR = 501,
+  RCC_ERROR_CODE_CARDANO_INVALID_TRANSACTION = 502,
+  RCC_ERROR_CODE_CARDANO_UNSUPPORTED_TRANSACTION = 503,
+  RCC_ERROR_CODE_CARDANO_SIGNING_FAILED = 504,
+  RCC_ERROR_CODE_COSMOS_PARSE_FAILED = 600,
+  RCC_ERROR_CODE_COSMOS_SERIALIZE_FAILED = 601,
+  RCC_ERROR_CODE_NEAR_PARSE_FAILED = 700,
+  RCC_ERROR_CODE_NEAR_SERIALIZE_FAILED = 701,
+  RCC_ERROR_CODE_SOLANA_UNSUPPORTED_PROGRAM = 800,
+  RCC_ERROR_CODE_SOLANA_INVALID_DATA = 801,
+  RCC_ERROR_CODE_SOLANA_PROGRAM_ERROR = 802,
+  RCC_ERROR_CODE_SOLANA_ACCOUNT_NOT_FOUND = 803,
+  RCC_ERROR_CODE_SUI_BCS_DECODING_ERROR = 900,
+  RCC_ERROR_CODE_SUI_INVALID_TRANSACTION = 901,
+  RCC_ERROR_CODE_SUI_SIGN_FAILURE = 902,
+  RCC_ERROR_CODE_SUI_INVALID_HD_PATH = 903,
+  RCC_ERROR_CODE_SUI_KEYSTORE_ERROR = 904,
+  RCC_ERROR_CODE_SUI_INVALID_ADDRESS_ERROR = 905,
+} RccErrorCode;
+
+// Prehashed signs the data as an already computed 32 bytes digest.
+typedef enum RccHashAlgorithm {
+  RCC_HASH_ALGORITHM_PREHASHED = 0,
+  RCC_HASH_ALGORITHM_KECCAK256 = 1,
+  RCC_HASH_ALGORITHM_SHA256 = 2,
+  RCC_HASH_ALGORITHM_SHA256D = 3,
+  RCC_HASH_ALGORITHM_BLAKE2B256 = 4,
+  RCC_HASH_ALGORITHM_SHA3_256 = 5,
+  RCC_HASH_ALGORITHM_SHA512_HALF = 6,
+} RccHashAlgorithm;
+
+typedef enum RccPublicKeyFormat {
+  RCC_PUBLIC_KEY_FORMAT_COMPRESSED = 0,
+  RCC_PUBLIC_KEY_FORMAT_UNCOMPRESSED = 1,
+  RCC_PUBLIC_KEY_FORMAT_RAW = 2,
+  RCC_PUBLIC_KEY_FORMAT_EXTENDED_PUBLIC_KEY = 3,
+} RccPublicKeyFormat;
+
+typedef enum RccSecp256k1Encoding {
+  RCC_SECP256K1_ENCODING_ETHEREUM = 0,
+  RCC_SECP256K1_ENCODING_BITCOIN = 1,
+  RCC_SECP256K1_ENCODING_COMPACT = 2,
+  RCC_SECP256K1_ENCODING_RECOVERABLE = 3,
+} RccSecp256k1Encoding;
+
+typedef enum RccSignatureEncoding {
+  RCC_SIGNATURE_ENCODING_RAW = 0,
+  RCC_SIGNATURE_ENCODING_DER = 1,
+} RccSignatureEncoding;
+
+typedef enum RccSigningAlgorithm {
+  RCC_SIGNING_ALGORITHM_SECP256K1 = 0,
+  RCC_SIGNING_ALGORITHM_SECP256_R1 = 1,
+  RCC_SIGNING_ALGORITHM_ED25519 = 2,
+  RCC_SIGNING_ALGORITHM_SR25519 = 3,
+  RCC_SIGNING_ALGORITHM_RSA = 4,
+} RccSigningAlgorithm;
+
+typedef enum RccSigningOptionKind {
+  RCC_SIGNING_OPTION_KIND_RSA = 0,
+  RCC_SIGNING_OPTION_KIND_ADA = 1,
+  RCC_SIGNING_OPTION_KIND_SECP256_R1 = 2,
+  RCC_SIGNING_OPTION_KIND_SCHNORR = 3,
+  RCC_SIGNING_OPTION_KIND_SECP256K1 = 4,
+} RccSigningOptionKind;
+
+// Opaque signer handle, release it with rcc_signer_free.
+typedef struct RccSigner RccSigner;
+
+// Bytes returned by the library, release them with rcc_buffer_free.
+typedef struct RccBuffer {
+  uint8_t *data;
+  size_t len;
+} RccBuffer;
+
+// SigningOption of rcc_signer_sign_data and rcc_signer_verify, pass NULL for none.
+// Only the fields prefixed with the name of `kind` are read, the others can be left zeroed.
+typedef struct RccSigningOption {
+  enum RccSigningOptionKind kind;
+  // PSS salt length.
+  int32_t rsa_salt_len;
+  enum RccSignatureEncoding secp256r1_encoding;
+  // BIP341 tweak of the key, by the script tree root when has_merkle_root, key path only otherwise.
+  bool schnorr_tweak;
+  bool schnorr_has_merkle_root;
+  uint8_t schnorr_merkle_root[32];
+  // BIP340 auxiliary randomness, fresh random bytes are used when it is not set.
+  bool schnorr_has_aux_rand;
+  uint8_t schnorr_aux_rand[32];
+  enum RccSecp256k1Encoding secp256k1_encoding;
+  enum RccHashAlgorithm secp256k1_hash;
+  // EIP-155 chain id of the Ethereum encoding, read when secp256k1_has_chain_id.
+  bool secp256k1_has_chain_id;
+  uint64_t secp256k1_chain_id;
+  // Sighash type appended by the Bitcoin encoding.
+  uint8_t secp256k1_sighash_type;
+} RccSigningOption;
+
+// Releases a buffer returned by the library and empties it, freeing an empty buffer does nothing.
+// # Safety
+// `buffer` is NULL or points to a buffer filled by this library that was not freed yet.
+void rcc_buffer_free(struct RccBuffer *buffer);
+
+// Releases a string returned by the library, freeing NULL does nothing. The string is wiped first, it can be a mnemonic.
+// # Safety
+// `string` is NULL or a string returned by this library that was not freed yet.
+void rcc_string_free(char *string);
+
+// Parses a BCS encoded aptos RawTransaction into JSON, free `out` with rcc_string_free.
+// # Safety
+// `data` points to `data_len` bytes, `out` is valid for writes.
+enum RccErrorCode rcc_aptos_parse(const uint8_t *data, size_t data_len, char **out);
+
+// Parses the JSON of an arweave transaction, free `out` with rcc_string_free.
+// # Safety
+// `data` points to `data_len` bytes, `out` is valid for writes.
+enum RccErrorCode rcc_arweave_parse(const uint8_t *data, size_t data_len, char **out);
+
+// Parses a protobuf encoded cosmos SignDoc into JSON, free `out` with rcc_string_free.
+// # Safety
+// `data` points to `data_len` bytes, `out` is valid for writes.
+enum RccErrorCode rcc_cosmos_parse(const uint8_t *data, size_t data_len, char **out);
+
+// Parses a borsh encoded near Transaction into JSON, free `out` with rcc_string_free.
+// # Safety
+// `data` points to `data_len` bytes, `out` is valid for writes.
+enum RccErrorCode rcc_near_parse(const uint8_t *data, size_t data_len, char **out);
+
+// Address at `path` under the x/y/zpub, `script_type` is P2PKH, P2SH-P2WPKH or P2WPKH.
+// Free `out` with rcc_string_free.
+// # Safety
+// Strings are NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_bitcoin_derive_address(const char *xpub,
+                                             const char *path,
+                                             const char *script_type,
+                                             char **out);
+
+// Parses a CBOR transaction into JSON, free `out` with rcc_string_free. `context` is the JSON
+// {"cardano_xpub", "master_fingerprint", "utxos": [{"master_fingerprint", "address", "path", "value",
+// "transaction_hash", "index"}], "cert_keys": [{"master_fingerprint", "key_hash", "path"}]}, bytes in hex.
+// # Safety
+// `tx` points to `tx_len` bytes, `context` is NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_cardano_parse_tx(const uint8_t *tx,
+                                       size_t tx_len,
+                                       const char *context,
+                                       char **out);
+
+// Initializes the metadata database at `db_path`. Free `out` with rcc_string_free.
+// # Safety
+// `db_path` is NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_polkadot_init_db(const char *db_path, char **out);
+
+// Parses a scanned payload against the database. Free `out` with rcc_string_free.
+// # Safety
+// Strings are NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_polkadot_parse_transaction(const char *transaction,
+                                                 const char *db_path,
+                                                 char **out);
+
+// Accepts the metadata update parsed under `checksum`. Free `out` with rcc_string_free.
+// # Safety
+// `db_path` is NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_polkadot_handle_stub(const char *db_path, uint32_t checksum, char **out);
+
+// Registers the address of `public_key` at `path`. Free `out` with rcc_string_free.
+// # Safety
+// Strings are NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_polkadot_import_address(const char *db_path,
+                                              const char *public_key,
+                                              const char *path,
+                                              char **out);
+
+// Hex of the bytes to sign for the transaction parsed under `checksum`. Free `out` with rcc_string_free.
+// # Safety
+// `db_path` is NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_polkadot_get_sign_content(const char *db_path,
+                                                uint32_t checksum,
+                                                char **out);
+
+// Number of frames of an animated QR payload. Free `out` with rcc_string_free.
+// # Safety
+// `payload` is NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_polkadot_get_packets_total(const char *payload, char **out);
+
+// Joins the `count` frames of an animated QR payload. Free `out` with rcc_string_free.
+// # Safety
+// `frames` points to `count` NUL terminated strings, `out` is valid for writes.
+enum RccErrorCode rcc_polkadot_decode_sequence(const char *const *frames, size_t count, char **out);
+
+// Parses a serialized solana message into JSON, free `out` with rcc_string_free.
+// # Safety
+// `message` points to `message_len` bytes, `out` is valid for writes.
+enum RccErrorCode rcc_solana_parse_message(const uint8_t *message, size_t message_len, char **out);
+
+// Whether the bytes are a well formed solana message, false for NULL.
+// # Safety
+// `message` points to `message_len` bytes.
+bool rcc_solana_validate_message(const uint8_t *message, size_t message_len);
+
+// Message of the last failed call on this thread, NULL after a successful one.
+// The string is owned by the library and valid until the next call on the same thread, do not free it.
+const char *rcc_last_error_message(void);
+
+// Signer backed by the secure element on the serial port `port_name`.
+// # Safety
+// `port_name` is a NUL terminated string, `out` is valid for writes.
+enum RccErrorCode rcc_signer_new_with_se(const char *port_name, struct RccSigner **out);
+
+// Signer deriving its keys from the mnemonics imported into `keystore_dir` with rcc_software_import_mnemonic.
+// # Safety
+// `keystore_dir` is a NUL terminated string, `out` is valid for writes.
+enum RccErrorCode rcc_signer_new_with_software(const char *keystore_dir,
+                                               struct RccSigner **out);
+
+// Releases a signer, freeing NULL does nothing.
+// # Safety
+// `signer` is NULL or a handle returned by this library that was not freed yet.
+void rcc_signer_free(struct RccSigner *signer);
+
+// Encrypts the mnemonic with `password` into the software keystore at `keystore_dir`.
+// # Safety
+// All strings are NUL terminated.
+enum RccErrorCode rcc_software_import_mnemonic(const char *keystore_dir,
+                                               uint8_t mnemonic_id,
+                                               const char *mnemonic,
+                                               const char *passphrase,
+                                               const char *password);
+
+// New english mnemonic of `words` words, free `out` with rcc_string_free.
+// # Safety
+// `signer` is a live handle, `out` is valid for writes.
+enum RccErrorCode rcc_signer_generate_mnemonic(const struct RccSigner *signer,
+                                               uint32_t words,
+                                               char **out);
+
+// Signs `data` with the key at `derivation_path`, free `out` with rcc_buffer_free.
+// # Safety
+// `signer` is a live handle, strings are NUL terminated, `data` points to `data_len` bytes, `option` is NULL or
+// points to an option, `out` is valid for writes.
+enum RccErrorCode rcc_signer_sign_data(const struct RccSigner *signer,
+                                       uint8_t mnemonic_id,
+                                       const char *password,
+                                       const uint8_t *data,
+                                       size_t data_len,
+                                       enum RccSigningAlgorithm algo,
+                                       const char *derivation_path,
+                                       const struct RccSigningOption *option,
+                                       struct RccBuffer *out);
+
+// Public key at `derivation_path`, free `out` with rcc_buffer_free.
+// # Safety
+// `signer` is a live handle, strings are NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_signer_get_public_key(const struct RccSigner *signer,
+                                            uint8_t mnemonic_id,
+                                            const char *password,
+                                            enum RccSigningAlgorithm algo,
+                                            const char *derivation_path,
+                                            enum RccPublicKeyFormat format,
+                                            struct RccBuffer *out);
+
+// RSA modulus of the wallet, free `out` with rcc_buffer_free.
+// # Safety
+// `signer` is a live handle, `password` is NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_signer_get_rsa_public_key(const struct RccSigner *signer,
+                                                uint8_t mnemonic_id,
+                                                const char *password,
+                                                struct RccBuffer *out);
+
+// Writes the 4 bytes master fingerprint to `out`.
+// # Safety
+// `signer` is a live handle, `password` is NUL terminated, `out` is valid for 4 bytes of writes.
+enum RccErrorCode rcc_signer_get_master_fingerprint(const struct RccSigner *signer,
+                                                    uint8_t mnemonic_id,
+                                                    const char *password,
+                                                    uint8_t *out);
+
+// Cardano extended public key at `path`, free `out` with rcc_string_free.
+// # Safety
+// `signer` is a live handle, strings are NUL terminated, `out` is valid for writes.
+enum RccErrorCode rcc_signer_get_ada_extended_public_key(const struct RccSigner *signer,
+                                                         uint8_t mnemonic_id,
+                                                         const char *password,
+                                                         const char *path,
+                                                         char **out);
+
+// Ok when `signature` is a valid signature of `data`, SignerSignatureMismatch or another error otherwise.
+// # Safety
+// Every byte pointer points to the number of bytes given next to it, `option` is NULL or points to an option.
+enum RccErrorCode rcc_signer_verify(const uint8_t *public_key,
+                                    size_t public_key_len,
+                                    const uint8_t *data,
+                                    size_t data_len,
+                                    const uint8_t *signature,
+                                    size_t signature_len,
+                                    enum RccSigningAlgorithm algo,
+                                    const struct RccSigningOption *option);
+
+#endif /* RCC_FFI_H */
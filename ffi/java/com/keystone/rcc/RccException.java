@@ -0,0 +1,15 @@
+package com.keystone.rcc;
+
+// thrown by the RccNative methods, code is one of the RccErrorCode values of rcc_ffi.h
+public class RccException extends RuntimeException {
+    private final int code;
+
+    public RccException(int code, String message) {
+        super(message);
+        this.code = code;
+    }
+
+    public int getCode() {
+        return code;
+    }
+}
@@ -0,0 +1,31 @@
+package com.keystone.rcc;
+
+// native methods of librcc_ffi built with the jni feature
+// algo: 0 secp256k1, 1 secp256r1, 2 ed25519, 3 sr25519, 4 rsa
+// format: 0 compressed, 1 uncompressed, 2 raw, 3 extended public key
+public final class RccNative {
+    static {
+        System.loadLibrary("rcc_ffi");
+    }
+
+    private RccNative() {}
+
+    // the handle is released with signerFree
+    public static native long signerNewWithSoftware(String keystoreDir);
+
+    public static native long signerNewWithSe(String portName);
+
+    public static native void signerFree(long signer);
+
+    // option is null for none
+    public static native byte[] signerSignData(long signer, int mnemonicId, String password, byte[] data, int algo, String derivationPath, SigningOption option);
+
+    public static native byte[] signerGetPublicKey(long signer, int mnemonicId, String password, int algo, String derivationPath, int format);
+
+    // chain: aptos, arweave, cosmos, near or solana
+    public static native String parseTransaction(String chain, byte[] data);
+
+    public static native boolean solanaValidateMessage(byte[] message);
+
+    public static native String bitcoinDeriveAddress(String xpub, String path, String scriptType);
+}
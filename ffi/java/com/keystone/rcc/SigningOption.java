@@ -0,0 +1,31 @@
+package com.keystone.rcc;
+
+// signing option of RccNative.signerSignData, mirrors RccSigningOption of rcc_ffi.h, the ints are the values of its enums
+// only the fields prefixed with the name of kind are read
+public final class SigningOption {
+    public static final int KIND_RSA = 0;
+    public static final int KIND_ADA = 1;
+    public static final int KIND_SECP256R1 = 2;
+    public static final int KIND_SCHNORR = 3;
+    public static final int KIND_SECP256K1 = 4;
+
+    public int kind;
+    // PSS salt length
+    public int rsaSaltLen;
+    // 0 raw, 1 der
+    public int secp256r1Encoding;
+    // BIP341 tweak of the key, by the merkle root when it is not null
+    public boolean schnorrTweak;
+    public byte[] schnorrMerkleRoot;
+    // 32 bytes, random when null
+    public byte[] schnorrAuxRand;
+    // 0 ethereum, 1 bitcoin, 2 compact, 3 recoverable
+    public int secp256k1Encoding;
+    // 0 prehashed, 1 keccak256, 2 sha256, 3 sha256d, 4 blake2b256, 5 sha3-256, 6 sha512 half
+    public int secp256k1Hash;
+    // EIP-155 chain id of the ethereum encoding, read when secp256k1HasChainId
+    public boolean secp256k1HasChainId;
+    public long secp256k1ChainId;
+    // sighash type appended by the bitcoin encoding
+    public int secp256k1SighashType;
+}
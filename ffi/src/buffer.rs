@@ -0,0 +1,103 @@
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use rcc_signer::{SecretPassword, Zeroizing};
+
+use crate::error::{FfiError, RccErrorCode};
+
+/// Bytes returned by the library, release them with rcc_buffer_free.
+#[repr(C)]
+pub struct RccBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl RccBuffer {
+    pub(crate) fn empty() -> Self {
+        Self { data: ptr::null_mut(), len: 0 }
+    }
+
+    pub(crate) fn from_vec(bytes: Vec<u8>) -> Self {
+        let bytes = Box::into_raw(bytes.into_boxed_slice());
+        Self { data: bytes as *mut u8, len: bytes.len() }
+    }
+}
+
+/// Releases a buffer returned by the library and empties it, freeing an empty buffer does nothing.
+/// # Safety
+/// `buffer` is NULL or points to a buffer filled by this library that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_buffer_free(buffer: *mut RccBuffer) {
+    if let Some(buffer) = buffer.as_mut() {
+        if !buffer.data.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len)));
+        }
+        *buffer = RccBuffer::empty();
+    }
+}
+
+/// Releases a string returned by the library, freeing NULL does nothing. The string is wiped first, it can be a mnemonic.
+/// # Safety
+/// `string` is NULL or a string returned by this library that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(Zeroizing::new(CString::from_raw(string).into_bytes_with_nul()));
+    }
+}
+
+// the caller keeps ownership of everything passed in, it is only borrowed for the duration of the call
+
+pub(crate) unsafe fn read_str<'a>(string: *const c_char) -> Result<&'a str, FfiError> {
+    if string.is_null() {
+        return Err(FfiError::new(RccErrorCode::NullPointer, "string is NULL"));
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|e| FfiError::new(RccErrorCode::InvalidUtf8, e.to_string()))
+}
+
+pub(crate) unsafe fn read_string(string: *const c_char) -> Result<String, FfiError> {
+    read_str(string).map(str::to_string)
+}
+
+pub(crate) unsafe fn read_password(password: *const c_char) -> Result<SecretPassword, FfiError> {
+    read_str(password).map(SecretPassword::from)
+}
+
+/// NULL is accepted for an empty slice.
+/// # Safety
+/// `data` is NULL or points to `len` bytes that outlive the returned slice.
+pub unsafe fn read_bytes<'a>(data: *const u8, len: usize) -> Result<&'a [u8], FfiError> {
+    match (data.is_null(), len) {
+        (true, 0) => Ok(&[]),
+        (true, _) => Err(FfiError::new(RccErrorCode::NullPointer, "data is NULL")),
+        (false, _) => Ok(std::slice::from_raw_parts(data, len)),
+    }
+}
+
+/// # Safety
+/// `out` is NULL or valid for writes.
+pub unsafe fn write_string(out: *mut *mut c_char, string: String) -> Result<(), FfiError> {
+    let out = out.as_mut().ok_or(FfiError::new(RccErrorCode::NullPointer, "out is NULL"))?;
+    let string = CString::new(string).map_err(|e| FfiError::new(RccErrorCode::InvalidUtf8, e.to_string()))?;
+    *out = string.into_raw();
+    Ok(())
+}
+
+// the buffer is sized for the NUL up front so CString does not leave an unwiped copy behind when it grows
+pub(crate) unsafe fn write_secret_string(out: *mut *mut c_char, secret: &str) -> Result<(), FfiError> {
+    let out = out.as_mut().ok_or(FfiError::new(RccErrorCode::NullPointer, "out is NULL"))?;
+    let mut bytes = Zeroizing::new(Vec::with_capacity(secret.len() + 1));
+    bytes.extend_from_slice(secret.as_bytes());
+    let string = CString::new(std::mem::take(&mut *bytes))
+        .map_err(|e| FfiError::new(RccErrorCode::InvalidUtf8, e.to_string()))?;
+    *out = string.into_raw();
+    Ok(())
+}
+
+pub(crate) unsafe fn write_buffer(out: *mut RccBuffer, bytes: Vec<u8>) -> Result<(), FfiError> {
+    let out = out.as_mut().ok_or(FfiError::new(RccErrorCode::NullPointer, "out is NULL"))?;
+    *out = RccBuffer::from_vec(bytes);
+    Ok(())
+}
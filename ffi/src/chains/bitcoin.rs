@@ -0,0 +1,21 @@
+use std::ffi::c_char;
+
+use crate::buffer::{read_string, write_string};
+use crate::error::{ffi_call, RccErrorCode};
+
+/// Address at `path` under the x/y/zpub, `script_type` is P2PKH, P2SH-P2WPKH or P2WPKH.
+/// Free `out` with rcc_string_free.
+/// # Safety
+/// Strings are NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_bitcoin_derive_address(
+    xpub: *const c_char,
+    path: *const c_char,
+    script_type: *const c_char,
+    out: *mut *mut c_char,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let address = rcc_bitcoin::derive_address(read_string(xpub)?, read_string(path)?, read_string(script_type)?)?;
+        write_string(out, address)
+    })
+}
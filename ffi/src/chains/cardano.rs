@@ -0,0 +1,79 @@
+use std::ffi::c_char;
+use std::str::FromStr;
+
+use bitcoin30::bip32::DerivationPath;
+use rcc_cardano::structs::{CardanoCertKey, CardanoUtxo, ParseContext};
+use serde_json::Value;
+
+use crate::buffer::{read_bytes, read_str, write_string};
+use crate::error::{ffi_call, FfiError, RccErrorCode};
+
+fn invalid_context(reason: &str) -> FfiError {
+    FfiError::new(RccErrorCode::InvalidArgument, format!("invalid parse context: {}", reason))
+}
+
+fn string<'a>(value: &'a Value, field: &str) -> Result<&'a str, FfiError> {
+    value[field].as_str().ok_or_else(|| invalid_context(field))
+}
+
+fn bytes(value: &Value, field: &str) -> Result<Vec<u8>, FfiError> {
+    hex::decode(string(value, field)?).map_err(|_| invalid_context(field))
+}
+
+fn number(value: &Value, field: &str) -> Result<u64, FfiError> {
+    value[field].as_u64().ok_or_else(|| invalid_context(field))
+}
+
+fn path(value: &Value) -> Result<DerivationPath, FfiError> {
+    DerivationPath::from_str(string(value, "path")?).map_err(|_| invalid_context("path"))
+}
+
+fn list<T>(value: &Value, field: &str, item: impl Fn(&Value) -> Result<T, FfiError>) -> Result<Vec<T>, FfiError> {
+    match &value[field] {
+        Value::Null => Ok(vec![]),
+        Value::Array(items) => items.iter().map(item).collect(),
+        _ => Err(invalid_context(field)),
+    }
+}
+
+fn parse_context(context: &str) -> Result<ParseContext, FfiError> {
+    let context: Value = serde_json::from_str(context).map_err(|e| invalid_context(&e.to_string()))?;
+    let utxos = list(&context, "utxos", |utxo| {
+        Ok(CardanoUtxo::new(
+            bytes(utxo, "master_fingerprint")?,
+            string(utxo, "address")?.to_string(),
+            path(utxo)?,
+            number(utxo, "value")?,
+            bytes(utxo, "transaction_hash")?,
+            u32::try_from(number(utxo, "index")?).map_err(|_| invalid_context("index"))?,
+        ))
+    })?;
+    let cert_keys = list(&context, "cert_keys", |key| {
+        Ok(CardanoCertKey::new(bytes(key, "master_fingerprint")?, bytes(key, "key_hash")?, path(key)?))
+    })?;
+    Ok(ParseContext::new(
+        utxos,
+        cert_keys,
+        string(&context, "cardano_xpub")?.to_string(),
+        bytes(&context, "master_fingerprint")?,
+    ))
+}
+
+/// Parses a CBOR transaction into JSON, free `out` with rcc_string_free. `context` is the JSON
+/// {"cardano_xpub", "master_fingerprint", "utxos": [{"master_fingerprint", "address", "path", "value",
+/// "transaction_hash", "index"}], "cert_keys": [{"master_fingerprint", "key_hash", "path"}]}, bytes in hex.
+/// # Safety
+/// `tx` points to `tx_len` bytes, `context` is NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_cardano_parse_tx(
+    tx: *const u8,
+    tx_len: usize,
+    context: *const c_char,
+    out: *mut *mut c_char,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let context = parse_context(read_str(context)?)?;
+        let json = rcc_cardano::transaction::parse_tx_to_json(read_bytes(tx, tx_len)?.to_vec(), context)?;
+        write_string(out, json)
+    })
+}
@@ -0,0 +1,64 @@
+use std::ffi::c_char;
+
+use rcc_trait_chain::Chain;
+
+use crate::buffer::{read_bytes, write_string};
+use crate::error::{ffi_call, FfiError, RccErrorCode};
+
+#[cfg(feature = "bitcoin")]
+mod bitcoin;
+#[cfg(feature = "cardano")]
+mod cardano;
+#[cfg(feature = "polkadot")]
+mod polkadot;
+#[cfg(feature = "solana")]
+mod solana;
+
+// Chain::parse of any chain crate, the raw transaction in and its JSON out
+#[allow(dead_code)]
+unsafe fn parse<C, E>(data: *const u8, data_len: usize, out: *mut *mut c_char) -> RccErrorCode
+where
+    C: Chain<E>,
+    E: std::error::Error + Into<FfiError>,
+{
+    ffi_call(|| {
+        let json = C::parse(&read_bytes(data, data_len)?.to_vec()).map_err(Into::into)?;
+        write_string(out, json)
+    })
+}
+
+/// Parses a BCS encoded aptos RawTransaction into JSON, free `out` with rcc_string_free.
+/// # Safety
+/// `data` points to `data_len` bytes, `out` is valid for writes.
+#[cfg(feature = "aptos")]
+#[no_mangle]
+pub unsafe extern "C" fn rcc_aptos_parse(data: *const u8, data_len: usize, out: *mut *mut c_char) -> RccErrorCode {
+    parse::<rcc_aptos::Aptos, _>(data, data_len, out)
+}
+
+/// Parses the JSON of an arweave transaction, free `out` with rcc_string_free.
+/// # Safety
+/// `data` points to `data_len` bytes, `out` is valid for writes.
+#[cfg(feature = "arweave")]
+#[no_mangle]
+pub unsafe extern "C" fn rcc_arweave_parse(data: *const u8, data_len: usize, out: *mut *mut c_char) -> RccErrorCode {
+    parse::<rcc_arweave::Arweave, _>(data, data_len, out)
+}
+
+/// Parses a protobuf encoded cosmos SignDoc into JSON, free `out` with rcc_string_free.
+/// # Safety
+/// `data` points to `data_len` bytes, `out` is valid for writes.
+#[cfg(feature = "cosmos")]
+#[no_mangle]
+pub unsafe extern "C" fn rcc_cosmos_parse(data: *const u8, data_len: usize, out: *mut *mut c_char) -> RccErrorCode {
+    parse::<rcc_cosmos::Cosmos, _>(data, data_len, out)
+}
+
+/// Parses a borsh encoded near Transaction into JSON, free `out` with rcc_string_free.
+/// # Safety
+/// `data` points to `data_len` bytes, `out` is valid for writes.
+#[cfg(feature = "near")]
+#[no_mangle]
+pub unsafe extern "C" fn rcc_near_parse(data: *const u8, data_len: usize, out: *mut *mut c_char) -> RccErrorCode {
+    parse::<rcc_near::Near, _>(data, data_len, out)
+}
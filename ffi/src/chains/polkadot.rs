@@ -0,0 +1,98 @@
+use std::ffi::c_char;
+
+use crate::buffer::{read_string, write_string};
+use crate::error::{ffi_call, FfiError, RccErrorCode};
+
+// the polkadot functions report their own failures in the returned JSON status, so only argument errors are codes
+
+/// Initializes the metadata database at `db_path`. Free `out` with rcc_string_free.
+/// # Safety
+/// `db_path` is NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_polkadot_init_db(db_path: *const c_char, out: *mut *mut c_char) -> RccErrorCode {
+    ffi_call(|| write_string(out, rcc_polkadot::init_polkadot_db(read_string(db_path)?)))
+}
+
+/// Parses a scanned payload against the database. Free `out` with rcc_string_free.
+/// # Safety
+/// Strings are NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_polkadot_parse_transaction(
+    transaction: *const c_char,
+    db_path: *const c_char,
+    out: *mut *mut c_char,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let json = rcc_polkadot::transaction_parser::parse_transaction(read_string(transaction)?, read_string(db_path)?);
+        write_string(out, json)
+    })
+}
+
+/// Accepts the metadata update parsed under `checksum`. Free `out` with rcc_string_free.
+/// # Safety
+/// `db_path` is NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_polkadot_handle_stub(
+    db_path: *const c_char,
+    checksum: u32,
+    out: *mut *mut c_char,
+) -> RccErrorCode {
+    ffi_call(|| write_string(out, rcc_polkadot::handle_stub(read_string(db_path)?, checksum)))
+}
+
+/// Registers the address of `public_key` at `path`. Free `out` with rcc_string_free.
+/// # Safety
+/// Strings are NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_polkadot_import_address(
+    db_path: *const c_char,
+    public_key: *const c_char,
+    path: *const c_char,
+    out: *mut *mut c_char,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let json = rcc_polkadot::import_address(read_string(db_path)?, read_string(public_key)?, read_string(path)?);
+        write_string(out, json)
+    })
+}
+
+/// Hex of the bytes to sign for the transaction parsed under `checksum`. Free `out` with rcc_string_free.
+/// # Safety
+/// `db_path` is NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_polkadot_get_sign_content(
+    db_path: *const c_char,
+    checksum: u32,
+    out: *mut *mut c_char,
+) -> RccErrorCode {
+    ffi_call(|| write_string(out, rcc_polkadot::get_sign_content(read_string(db_path)?, checksum)))
+}
+
+/// Number of frames of an animated QR payload. Free `out` with rcc_string_free.
+/// # Safety
+/// `payload` is NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_polkadot_get_packets_total(payload: *const c_char, out: *mut *mut c_char) -> RccErrorCode {
+    ffi_call(|| write_string(out, rcc_polkadot::scanner::get_packets_total(read_string(payload)?)))
+}
+
+/// Joins the `count` frames of an animated QR payload. Free `out` with rcc_string_free.
+/// # Safety
+/// `frames` points to `count` NUL terminated strings, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_polkadot_decode_sequence(
+    frames: *const *const c_char,
+    count: usize,
+    out: *mut *mut c_char,
+) -> RccErrorCode {
+    ffi_call(|| {
+        if frames.is_null() {
+            return Err(FfiError::new(RccErrorCode::NullPointer, "frames is NULL"));
+        }
+        let frames = std::slice::from_raw_parts(frames, count)
+            .iter()
+            .map(|frame| read_string(*frame))
+            .collect::<Result<Vec<_>, _>>()?;
+        write_string(out, rcc_polkadot::scanner::decode_sequence(frames))
+    })
+}
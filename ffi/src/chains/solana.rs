@@ -0,0 +1,33 @@
+use std::ffi::c_char;
+
+use crate::buffer::{read_bytes, write_string};
+use crate::error::{ffi_call, RccErrorCode};
+
+/// Parses a serialized solana message into JSON, free `out` with rcc_string_free.
+/// # Safety
+/// `message` points to `message_len` bytes, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_solana_parse_message(
+    message: *const u8,
+    message_len: usize,
+    out: *mut *mut c_char,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let mut message = read_bytes(message, message_len)?.to_vec();
+        write_string(out, rcc_solana::Sol::parse_message_to_json(&mut message)?)
+    })
+}
+
+/// Whether the bytes are a well formed solana message, false for NULL.
+/// # Safety
+/// `message` points to `message_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_solana_validate_message(message: *const u8, message_len: usize) -> bool {
+    let mut valid = false;
+    ffi_call(|| {
+        let mut message = read_bytes(message, message_len)?.to_vec();
+        valid = rcc_solana::Sol::validate_message(&mut message);
+        Ok(())
+    });
+    valid
+}
@@ -0,0 +1,272 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use rcc_signer::KSError;
+
+/// Result of every call. The codes are stable, each crate has its own range and mirrors its error enum
+/// variant by variant, codes of chains left out of the build are never returned.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RccErrorCode {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    InvalidArgument = 3,
+    Panic = 4,
+
+    // rcc_signer KSError
+    SignerSerialManagerError = 100,
+    SignerSerialTimeout = 101,
+    SignerTVLError = 102,
+    SignerTVLDeseriliazeError = 103,
+    SignerNoneSupportedCommandError = 104,
+    SignerSEError = 105,
+    SignerGenerateSigningKeyError = 106,
+    SignerRSASignError = 107,
+    SignerRSAVerifyError = 108,
+    SignerSignDataError = 109,
+    SignerWriteSecretError = 110,
+    SignerGetPublicKeyError = 111,
+    SignerSignatureMismatch = 112,
+    SignerVerifyError = 113,
+    SignerKeyStoreError = 114,
+    SignerSecureChannelError = 115,
+    SignerWrongPassword = 116,
+    SignerWalletLocked = 117,
+    SignerWalletNotFound = 118,
+    SignerSecretNotSet = 119,
+    SignerInvalidAuthToken = 120,
+    SignerUnsupportedCommand = 121,
+    SignerChipBusy = 122,
+    SignerSEStatusError = 123,
+    SignerMnemonicError = 124,
+    SignerBip85Error = 125,
+    SignerPolicyViolation = 126,
+    SignerInvalidPolicy = 127,
+    SignerAuditLogError = 128,
+    SignerAuditLogTampered = 129,
+    SignerAttestationError = 130,
+
+    // rcc-aptos AptosError
+    AptosParseFailed = 200,
+    AptosSerializeFailed = 201,
+
+    // rcc-arweave ArweaveError
+    ArweaveBase64Decode = 300,
+    ArweaveFromUtf8 = 301,
+
+    // rcc-bitcoin BitcoinError
+    BitcoinAddressDerivationFailed = 400,
+    BitcoinInvalidTransaction = 401,
+
+    // rcc_cardano CardanoError
+    CardanoAddressEncodingError = 500,
+    CardanoDerivationError = 501,
+    CardanoInvalidTransaction = 502,
+    CardanoUnsupportedTransaction = 503,
+    CardanoSigningFailed = 504,
+
+    // rcc-cosmos CosmosError
+    CosmosParseFailed = 600,
+    CosmosSerializeFailed = 601,
+
+    // rcc-near NearError
+    NearParseFailed = 700,
+    NearSerializeFailed = 701,
+
+    // rcc-solana SolanaError
+    SolanaUnsupportedProgram = 800,
+    SolanaInvalidData = 801,
+    SolanaProgramError = 802,
+    SolanaAccountNotFound = 803,
+
+    // rcc_sui SuiError, returned by rcc-ffi-sui
+    SuiBcsDecodingError = 900,
+    SuiInvalidTransaction = 901,
+    SuiSignFailure = 902,
+    SuiInvalidHDPath = 903,
+    SuiKeystoreError = 904,
+    SuiInvalidAddressError = 905,
+}
+
+// a failed call, the code and the message kept for rcc_last_error_message
+pub struct FfiError {
+    pub(crate) code: RccErrorCode,
+    pub(crate) message: String,
+}
+
+impl FfiError {
+    pub fn new(code: RccErrorCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl From<KSError> for FfiError {
+    fn from(error: KSError) -> Self {
+        let code = match error {
+            KSError::SerialManagerError(_) => RccErrorCode::SignerSerialManagerError,
+            KSError::SerialTimeout => RccErrorCode::SignerSerialTimeout,
+            KSError::TVLError(_) => RccErrorCode::SignerTVLError,
+            KSError::TVLDeseriliazeError => RccErrorCode::SignerTVLDeseriliazeError,
+            KSError::NoneSupportedCommandError => RccErrorCode::SignerNoneSupportedCommandError,
+            KSError::SEError(_) => RccErrorCode::SignerSEError,
+            KSError::GenerateSigningKeyError(_) => RccErrorCode::SignerGenerateSigningKeyError,
+            KSError::RSASignError => RccErrorCode::SignerRSASignError,
+            KSError::RSAVerifyError => RccErrorCode::SignerRSAVerifyError,
+            KSError::SignDataError(_) => RccErrorCode::SignerSignDataError,
+            KSError::WriteSecretError(_) => RccErrorCode::SignerWriteSecretError,
+            KSError::GetPublicKeyError(_) => RccErrorCode::SignerGetPublicKeyError,
+            KSError::SignatureMismatch => RccErrorCode::SignerSignatureMismatch,
+            KSError::VerifyError(_) => RccErrorCode::SignerVerifyError,
+            KSError::KeyStoreError(_) => RccErrorCode::SignerKeyStoreError,
+            KSError::SecureChannelError(_) => RccErrorCode::SignerSecureChannelError,
+            KSError::WrongPassword { .. } => RccErrorCode::SignerWrongPassword,
+            KSError::WalletLocked => RccErrorCode::SignerWalletLocked,
+            KSError::WalletNotFound => RccErrorCode::SignerWalletNotFound,
+            KSError::SecretNotSet => RccErrorCode::SignerSecretNotSet,
+            KSError::InvalidAuthToken => RccErrorCode::SignerInvalidAuthToken,
+            KSError::UnsupportedCommand(_) => RccErrorCode::SignerUnsupportedCommand,
+            KSError::ChipBusy => RccErrorCode::SignerChipBusy,
+            KSError::SEStatusError(_) => RccErrorCode::SignerSEStatusError,
+            KSError::MnemonicError(_) => RccErrorCode::SignerMnemonicError,
+            KSError::Bip85Error(_) => RccErrorCode::SignerBip85Error,
+            KSError::PolicyViolation(_) => RccErrorCode::SignerPolicyViolation,
+            KSError::InvalidPolicy(_) => RccErrorCode::SignerInvalidPolicy,
+            KSError::AuditLogError(_) => RccErrorCode::SignerAuditLogError,
+            KSError::AuditLogTampered(_) => RccErrorCode::SignerAuditLogTampered,
+            KSError::AttestationError(_) => RccErrorCode::SignerAttestationError,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "aptos")]
+impl From<rcc_aptos::AptosError> for FfiError {
+    fn from(error: rcc_aptos::AptosError) -> Self {
+        use rcc_aptos::AptosError;
+        let code = match error {
+            AptosError::ParseFailed(_) => RccErrorCode::AptosParseFailed,
+            AptosError::SerializeFailed(_) => RccErrorCode::AptosSerializeFailed,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "arweave")]
+impl From<rcc_arweave::ArweaveError> for FfiError {
+    fn from(error: rcc_arweave::ArweaveError) -> Self {
+        use rcc_arweave::ArweaveError;
+        let code = match error {
+            ArweaveError::Base64Decode(_) => RccErrorCode::ArweaveBase64Decode,
+            ArweaveError::FromUtf8(_) => RccErrorCode::ArweaveFromUtf8,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "bitcoin")]
+impl From<rcc_bitcoin::BitcoinError> for FfiError {
+    fn from(error: rcc_bitcoin::BitcoinError) -> Self {
+        use rcc_bitcoin::BitcoinError;
+        let code = match error {
+            BitcoinError::AddressDerivationFailed(_) => RccErrorCode::BitcoinAddressDerivationFailed,
+            BitcoinError::InvalidTransaction(_) => RccErrorCode::BitcoinInvalidTransaction,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "cardano")]
+impl From<rcc_cardano::errors::CardanoError> for FfiError {
+    fn from(error: rcc_cardano::errors::CardanoError) -> Self {
+        use rcc_cardano::errors::CardanoError;
+        let code = match error {
+            CardanoError::AddressEncodingError(_) => RccErrorCode::CardanoAddressEncodingError,
+            CardanoError::DerivationError(_) => RccErrorCode::CardanoDerivationError,
+            CardanoError::InvalidTransaction(_) => RccErrorCode::CardanoInvalidTransaction,
+            CardanoError::UnsupportedTransaction(_) => RccErrorCode::CardanoUnsupportedTransaction,
+            CardanoError::SigningFailed(_) => RccErrorCode::CardanoSigningFailed,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "cosmos")]
+impl From<rcc_cosmos::CosmosError> for FfiError {
+    fn from(error: rcc_cosmos::CosmosError) -> Self {
+        use rcc_cosmos::CosmosError;
+        let code = match error {
+            CosmosError::ParseFailed(_) => RccErrorCode::CosmosParseFailed,
+            CosmosError::SerializeFailed(_) => RccErrorCode::CosmosSerializeFailed,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "near")]
+impl From<rcc_near::NearError> for FfiError {
+    fn from(error: rcc_near::NearError) -> Self {
+        use rcc_near::NearError;
+        let code = match error {
+            NearError::ParseFailed(_) => RccErrorCode::NearParseFailed,
+            NearError::SerializeFailed(_) => RccErrorCode::NearSerializeFailed,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "solana")]
+impl From<rcc_solana::SolanaError> for FfiError {
+    fn from(error: rcc_solana::SolanaError) -> Self {
+        use rcc_solana::SolanaError;
+        let code = match error {
+            SolanaError::UnsupportedProgram(_) => RccErrorCode::SolanaUnsupportedProgram,
+            SolanaError::InvalidData(_) => RccErrorCode::SolanaInvalidData,
+            SolanaError::ProgramError(_) => RccErrorCode::SolanaProgramError,
+            SolanaError::AccountNotFound(_) => RccErrorCode::SolanaAccountNotFound,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: Option<String>) {
+    let message = message.map(|message| CString::new(message.replace('\0', "")).unwrap_or_default());
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+// runs the body of an exported function, panics never cross the C boundary
+pub fn ffi_call<F>(body: F) -> RccErrorCode
+where
+    F: FnOnce() -> Result<(), FfiError>,
+{
+    let result = catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic".to_string());
+        Err(FfiError::new(RccErrorCode::Panic, message))
+    });
+    match result {
+        Ok(()) => {
+            set_last_error(None);
+            RccErrorCode::Ok
+        }
+        Err(error) => {
+            set_last_error(Some(error.message));
+            error.code
+        }
+    }
+}
+
+/// Message of the last failed call on this thread, NULL after a successful one.
+/// The string is owned by the library and valid until the next call on the same thread, do not free it.
+#[no_mangle]
+pub extern "C" fn rcc_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(std::ptr::null(), |message| message.as_ptr()))
+}
@@ -0,0 +1,318 @@
+// entry points of the native methods of com.keystone.rcc.RccNative, failures are thrown as
+// com.keystone.rcc.RccException(int code, String message) with the codes of RccErrorCode
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use jni::objects::{JByteArray, JClass, JObject, JString, JThrowable, JValue};
+use jni::sys::{jboolean, jbyteArray, jint, jlong, jstring, JNI_FALSE};
+use jni::JNIEnv;
+use rcc_signer::{
+    HashAlgorithm, PublicKeyFormat, Secp256k1Encoding, SignatureEncoding, SigningAlgorithm, SigningOption, Signer,
+    TaprootTweak,
+};
+
+use crate::error::{FfiError, RccErrorCode};
+use crate::signer::RccSigner;
+
+const EXCEPTION_CLASS: &str = "com/keystone/rcc/RccException";
+
+impl From<jni::errors::Error> for FfiError {
+    fn from(error: jni::errors::Error) -> Self {
+        FfiError::new(RccErrorCode::InvalidArgument, error.to_string())
+    }
+}
+
+fn throw(env: &mut JNIEnv, error: FfiError) {
+    let thrown = (|| -> jni::errors::Result<()> {
+        let message = env.new_string(&error.message)?;
+        let exception = env.new_object(
+            EXCEPTION_CLASS,
+            "(ILjava/lang/String;)V",
+            &[JValue::Int(error.code as jint), JValue::Object(&message)],
+        )?;
+        env.throw(JThrowable::from(exception))
+    })();
+    // the exception class is missing, a plain RuntimeException still reaches the caller
+    if thrown.is_err() && !env.exception_check().unwrap_or(false) {
+        let _ = env.throw_new("java/lang/RuntimeException", &error.message);
+    }
+}
+
+// on failure an exception is pending and the returned value is ignored by the JVM
+fn jni_call<'local, T>(
+    env: &mut JNIEnv<'local>,
+    failed: T,
+    body: impl FnOnce(&mut JNIEnv<'local>) -> Result<T, FfiError>,
+) -> T {
+    let result = catch_unwind(AssertUnwindSafe(|| body(env)))
+        .unwrap_or_else(|_| Err(FfiError::new(RccErrorCode::Panic, "panic")));
+    match result {
+        Ok(value) => value,
+        Err(error) => {
+            throw(env, error);
+            failed
+        }
+    }
+}
+
+fn string(env: &mut JNIEnv, string: &JString) -> Result<String, FfiError> {
+    Ok(env.get_string(string)?.into())
+}
+
+fn new_string(env: &mut JNIEnv, string: String) -> Result<jstring, FfiError> {
+    Ok(env.new_string(string)?.into_raw())
+}
+
+fn new_bytes(env: &mut JNIEnv, bytes: &[u8]) -> Result<jbyteArray, FfiError> {
+    Ok(env.byte_array_from_slice(bytes)?.into_raw())
+}
+
+fn signer<'a>(handle: jlong) -> Result<&'a Signer, FfiError> {
+    unsafe { (handle as *const RccSigner).as_ref() }
+        .map(|signer| &signer.0)
+        .ok_or(FfiError::new(RccErrorCode::NullPointer, "signer is released"))
+}
+
+fn algorithm(algo: jint) -> Result<SigningAlgorithm, FfiError> {
+    match algo {
+        0 => Ok(SigningAlgorithm::Secp256k1),
+        1 => Ok(SigningAlgorithm::Secp256R1),
+        2 => Ok(SigningAlgorithm::Ed25519),
+        3 => Ok(SigningAlgorithm::SR25519),
+        4 => Ok(SigningAlgorithm::RSA),
+        _ => Err(FfiError::new(RccErrorCode::InvalidArgument, format!("unknown signing algorithm {}", algo))),
+    }
+}
+
+fn public_key_format(format: jint) -> Result<PublicKeyFormat, FfiError> {
+    match format {
+        0 => Ok(PublicKeyFormat::Compressed),
+        1 => Ok(PublicKeyFormat::Uncompressed),
+        2 => Ok(PublicKeyFormat::Raw),
+        3 => Ok(PublicKeyFormat::ExtendedPublicKey),
+        _ => Err(FfiError::new(RccErrorCode::InvalidArgument, format!("unknown public key format {}", format))),
+    }
+}
+
+fn invalid_option(field: &str, value: jint) -> FfiError {
+    FfiError::new(RccErrorCode::InvalidArgument, format!("unknown signing option {} {}", field, value))
+}
+
+fn int_field(env: &mut JNIEnv, object: &JObject, name: &str) -> Result<jint, FfiError> {
+    Ok(env.get_field(object, name, "I")?.i()?)
+}
+
+fn bool_field(env: &mut JNIEnv, object: &JObject, name: &str) -> Result<bool, FfiError> {
+    Ok(env.get_field(object, name, "Z")?.z()?)
+}
+
+// a null array is None, anything else has to be 32 bytes
+fn bytes32_field(env: &mut JNIEnv, object: &JObject, name: &str) -> Result<Option<[u8; 32]>, FfiError> {
+    let array = JByteArray::from(env.get_field(object, name, "[B")?.l()?);
+    if array.is_null() {
+        return Ok(None);
+    }
+    let bytes = env.convert_byte_array(&array)?;
+    let bytes = bytes
+        .try_into()
+        .map_err(|_| FfiError::new(RccErrorCode::InvalidArgument, format!("{} is not 32 bytes", name)))?;
+    Ok(Some(bytes))
+}
+
+// com.keystone.rcc.SigningOption, the same values as RccSigningOption
+fn signing_option(env: &mut JNIEnv, option: &JObject) -> Result<Option<SigningOption>, FfiError> {
+    if option.is_null() {
+        return Ok(None);
+    }
+    let option = match int_field(env, option, "kind")? {
+        0 => SigningOption::RSA { salt_len: int_field(env, option, "rsaSaltLen")? },
+        1 => SigningOption::ADA,
+        2 => SigningOption::Secp256R1 {
+            encoding: match int_field(env, option, "secp256r1Encoding")? {
+                0 => SignatureEncoding::Raw,
+                1 => SignatureEncoding::DER,
+                encoding => return Err(invalid_option("secp256r1Encoding", encoding)),
+            },
+        },
+        3 => SigningOption::Schnorr {
+            tweak: match bool_field(env, option, "schnorrTweak")? {
+                true => Some(TaprootTweak { merkle_root: bytes32_field(env, option, "schnorrMerkleRoot")? }),
+                false => None,
+            },
+            aux_rand: bytes32_field(env, option, "schnorrAuxRand")?,
+        },
+        4 => SigningOption::Secp256k1 {
+            encoding: match int_field(env, option, "secp256k1Encoding")? {
+                0 => Secp256k1Encoding::Ethereum {
+                    chain_id: match bool_field(env, option, "secp256k1HasChainId")? {
+                        true => Some(env.get_field(option, "secp256k1ChainId", "J")?.j()? as u64),
+                        false => None,
+                    },
+                },
+                1 => Secp256k1Encoding::Bitcoin {
+                    sighash_type: int_field(env, option, "secp256k1SighashType")? as u8,
+                },
+                2 => Secp256k1Encoding::Compact,
+                3 => Secp256k1Encoding::Recoverable,
+                encoding => return Err(invalid_option("secp256k1Encoding", encoding)),
+            },
+            hash: match int_field(env, option, "secp256k1Hash")? {
+                0 => None,
+                1 => Some(HashAlgorithm::Keccak256),
+                2 => Some(HashAlgorithm::Sha256),
+                3 => Some(HashAlgorithm::Sha256d),
+                4 => Some(HashAlgorithm::Blake2b256),
+                5 => Some(HashAlgorithm::Sha3_256),
+                6 => Some(HashAlgorithm::Sha512Half),
+                hash => return Err(invalid_option("secp256k1Hash", hash)),
+            },
+        },
+        kind => return Err(invalid_option("kind", kind)),
+    };
+    Ok(Some(option))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_keystone_rcc_RccNative_signerNewWithSoftware<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    keystore_dir: JString<'local>,
+) -> jlong {
+    jni_call(&mut env, 0, |env| {
+        let signer = Signer::new_with_software(string(env, &keystore_dir)?);
+        Ok(Box::into_raw(Box::new(RccSigner(signer))) as jlong)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_keystone_rcc_RccNative_signerNewWithSe<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    port_name: JString<'local>,
+) -> jlong {
+    jni_call(&mut env, 0, |env| {
+        let signer = Signer::new_with_se(string(env, &port_name)?);
+        Ok(Box::into_raw(Box::new(RccSigner(signer))) as jlong)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_keystone_rcc_RccNative_signerFree<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) {
+    unsafe { crate::signer::rcc_signer_free(handle as *mut RccSigner) }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_keystone_rcc_RccNative_signerSignData<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    mnemonic_id: jint,
+    password: JString<'local>,
+    data: JByteArray<'local>,
+    algo: jint,
+    derivation_path: JString<'local>,
+    option: JObject<'local>,
+) -> jbyteArray {
+    jni_call(&mut env, std::ptr::null_mut(), |env| {
+        let password = string(env, &password)?.into();
+        let data = env.convert_byte_array(&data)?;
+        let path = string(env, &derivation_path)?;
+        let option = signing_option(env, &option)?;
+        let signature = signer(handle)?.sign_data(mnemonic_id as u8, &password, data, algorithm(algo)?, path, option)?;
+        new_bytes(env, &signature)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_keystone_rcc_RccNative_signerGetPublicKey<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    mnemonic_id: jint,
+    password: JString<'local>,
+    algo: jint,
+    derivation_path: JString<'local>,
+    format: jint,
+) -> jbyteArray {
+    jni_call(&mut env, std::ptr::null_mut(), |env| {
+        let password = string(env, &password)?.into();
+        let path = string(env, &derivation_path)?;
+        let public_key = signer(handle)?.get_public_key(
+            mnemonic_id as u8,
+            &password,
+            algorithm(algo)?,
+            path,
+            public_key_format(format)?,
+        )?;
+        new_bytes(env, &public_key)
+    })
+}
+
+// chain is the lowercase name of a chain built into the library, e.g. "solana"
+#[no_mangle]
+pub extern "system" fn Java_com_keystone_rcc_RccNative_parseTransaction<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    chain: JString<'local>,
+    data: JByteArray<'local>,
+) -> jstring {
+    jni_call(&mut env, std::ptr::null_mut(), |env| {
+        let chain = string(env, &chain)?;
+        let data = env.convert_byte_array(&data)?;
+        let json = parse_transaction(&chain, data)?;
+        new_string(env, json)
+    })
+}
+
+#[allow(unused_variables)]
+fn parse_transaction(chain: &str, data: Vec<u8>) -> Result<String, FfiError> {
+    #[allow(unused_imports)]
+    use rcc_trait_chain::Chain;
+    match chain {
+        #[cfg(feature = "aptos")]
+        "aptos" => Ok(rcc_aptos::Aptos::parse(&data)?),
+        #[cfg(feature = "arweave")]
+        "arweave" => Ok(rcc_arweave::Arweave::parse(&data)?),
+        #[cfg(feature = "cosmos")]
+        "cosmos" => Ok(rcc_cosmos::Cosmos::parse(&data)?),
+        #[cfg(feature = "near")]
+        "near" => Ok(rcc_near::Near::parse(&data)?),
+        #[cfg(feature = "solana")]
+        "solana" => Ok(rcc_solana::Sol::parse(&data)?),
+        _ => Err(FfiError::new(RccErrorCode::InvalidArgument, format!("chain {} is not built in", chain))),
+    }
+}
+
+#[cfg(feature = "solana")]
+#[no_mangle]
+pub extern "system" fn Java_com_keystone_rcc_RccNative_solanaValidateMessage<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    message: JByteArray<'local>,
+) -> jboolean {
+    jni_call(&mut env, JNI_FALSE, |env| {
+        let mut message = env.convert_byte_array(&message)?;
+        Ok(rcc_solana::Sol::validate_message(&mut message) as jboolean)
+    })
+}
+
+#[cfg(feature = "bitcoin")]
+#[no_mangle]
+pub extern "system" fn Java_com_keystone_rcc_RccNative_bitcoinDeriveAddress<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    xpub: JString<'local>,
+    path: JString<'local>,
+    script_type: JString<'local>,
+) -> jstring {
+    jni_call(&mut env, std::ptr::null_mut(), |env| {
+        let xpub = string(env, &xpub)?;
+        let path = string(env, &path)?;
+        let script_type = string(env, &script_type)?;
+        let address = rcc_bitcoin::derive_address(xpub, path, script_type)?;
+        new_string(env, address)
+    })
+}
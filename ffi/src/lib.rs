@@ -0,0 +1,26 @@
+// C ABI over rcc_signer and the chain parsers, see include/rcc_ffi.h
+//
+// ownership: arguments are borrowed for the duration of the call, everything returned is owned by the caller
+// and released with the matching rcc_*_free function. Every call returns an RccErrorCode, the message of a
+// failure is read with rcc_last_error_message.
+mod buffer;
+mod chains;
+mod error;
+mod signer;
+
+#[cfg(feature = "jni")]
+mod jni;
+
+pub use crate::buffer::{rcc_buffer_free, rcc_string_free, RccBuffer};
+pub use crate::error::{rcc_last_error_message, RccErrorCode};
+pub use crate::signer::{
+    RccHashAlgorithm, RccPublicKeyFormat, RccSecp256k1Encoding, RccSignatureEncoding, RccSigner, RccSigningAlgorithm,
+    RccSigningOption, RccSigningOptionKind,
+};
+
+// for the bindings of chains that cannot be built with this crate, see sui/
+#[doc(hidden)]
+pub mod ext {
+    pub use crate::buffer::{read_bytes, write_string};
+    pub use crate::error::{ffi_call, FfiError};
+}
@@ -0,0 +1,396 @@
+use std::ffi::c_char;
+
+use rcc_signer::{
+    EntropyLength, HashAlgorithm, MnemonicLanguage, PublicKeyFormat, Secp256k1Encoding, SignatureEncoding,
+    SigningAlgorithm, SigningOption, Signer, Software, TaprootTweak, Zeroizing,
+};
+
+use crate::buffer::{
+    read_bytes, read_password, read_string, write_buffer, write_secret_string, write_string, RccBuffer,
+};
+use crate::error::{ffi_call, FfiError, RccErrorCode};
+
+/// Opaque signer handle, release it with rcc_signer_free.
+pub struct RccSigner(pub(crate) Signer);
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum RccSigningAlgorithm {
+    Secp256k1 = 0,
+    Secp256R1 = 1,
+    Ed25519 = 2,
+    SR25519 = 3,
+    RSA = 4,
+}
+
+impl From<RccSigningAlgorithm> for SigningAlgorithm {
+    fn from(algo: RccSigningAlgorithm) -> Self {
+        match algo {
+            RccSigningAlgorithm::Secp256k1 => SigningAlgorithm::Secp256k1,
+            RccSigningAlgorithm::Secp256R1 => SigningAlgorithm::Secp256R1,
+            RccSigningAlgorithm::Ed25519 => SigningAlgorithm::Ed25519,
+            RccSigningAlgorithm::SR25519 => SigningAlgorithm::SR25519,
+            RccSigningAlgorithm::RSA => SigningAlgorithm::RSA,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum RccPublicKeyFormat {
+    Compressed = 0,
+    Uncompressed = 1,
+    Raw = 2,
+    ExtendedPublicKey = 3,
+}
+
+impl From<RccPublicKeyFormat> for PublicKeyFormat {
+    fn from(format: RccPublicKeyFormat) -> Self {
+        match format {
+            RccPublicKeyFormat::Compressed => PublicKeyFormat::Compressed,
+            RccPublicKeyFormat::Uncompressed => PublicKeyFormat::Uncompressed,
+            RccPublicKeyFormat::Raw => PublicKeyFormat::Raw,
+            RccPublicKeyFormat::ExtendedPublicKey => PublicKeyFormat::ExtendedPublicKey,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum RccSigningOptionKind {
+    Rsa = 0,
+    Ada = 1,
+    Secp256R1 = 2,
+    Schnorr = 3,
+    Secp256k1 = 4,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum RccSignatureEncoding {
+    Raw = 0,
+    Der = 1,
+}
+
+impl From<RccSignatureEncoding> for SignatureEncoding {
+    fn from(encoding: RccSignatureEncoding) -> Self {
+        match encoding {
+            RccSignatureEncoding::Raw => SignatureEncoding::Raw,
+            RccSignatureEncoding::Der => SignatureEncoding::DER,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum RccSecp256k1Encoding {
+    Ethereum = 0,
+    Bitcoin = 1,
+    Compact = 2,
+    Recoverable = 3,
+}
+
+/// Prehashed signs the data as an already computed 32 bytes digest.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum RccHashAlgorithm {
+    Prehashed = 0,
+    Keccak256 = 1,
+    Sha256 = 2,
+    Sha256d = 3,
+    Blake2b256 = 4,
+    Sha3_256 = 5,
+    Sha512Half = 6,
+}
+
+impl From<RccHashAlgorithm> for Option<HashAlgorithm> {
+    fn from(hash: RccHashAlgorithm) -> Self {
+        match hash {
+            RccHashAlgorithm::Prehashed => None,
+            RccHashAlgorithm::Keccak256 => Some(HashAlgorithm::Keccak256),
+            RccHashAlgorithm::Sha256 => Some(HashAlgorithm::Sha256),
+            RccHashAlgorithm::Sha256d => Some(HashAlgorithm::Sha256d),
+            RccHashAlgorithm::Blake2b256 => Some(HashAlgorithm::Blake2b256),
+            RccHashAlgorithm::Sha3_256 => Some(HashAlgorithm::Sha3_256),
+            RccHashAlgorithm::Sha512Half => Some(HashAlgorithm::Sha512Half),
+        }
+    }
+}
+
+/// SigningOption of rcc_signer_sign_data and rcc_signer_verify, pass NULL for none.
+/// Only the fields prefixed with the name of `kind` are read, the others can be left zeroed.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct RccSigningOption {
+    pub kind: RccSigningOptionKind,
+    /// PSS salt length.
+    pub rsa_salt_len: i32,
+    pub secp256r1_encoding: RccSignatureEncoding,
+    /// BIP341 tweak of the key, by the script tree root when has_merkle_root, key path only otherwise.
+    pub schnorr_tweak: bool,
+    pub schnorr_has_merkle_root: bool,
+    pub schnorr_merkle_root: [u8; 32],
+    /// BIP340 auxiliary randomness, fresh random bytes are used when it is not set.
+    pub schnorr_has_aux_rand: bool,
+    pub schnorr_aux_rand: [u8; 32],
+    pub secp256k1_encoding: RccSecp256k1Encoding,
+    pub secp256k1_hash: RccHashAlgorithm,
+    /// EIP-155 chain id of the Ethereum encoding, read when secp256k1_has_chain_id.
+    pub secp256k1_has_chain_id: bool,
+    pub secp256k1_chain_id: u64,
+    /// Sighash type appended by the Bitcoin encoding.
+    pub secp256k1_sighash_type: u8,
+}
+
+impl From<&RccSigningOption> for SigningOption {
+    fn from(option: &RccSigningOption) -> Self {
+        let some = |set: bool, value: [u8; 32]| if set { Some(value) } else { None };
+        match option.kind {
+            RccSigningOptionKind::Rsa => SigningOption::RSA { salt_len: option.rsa_salt_len },
+            RccSigningOptionKind::Ada => SigningOption::ADA,
+            RccSigningOptionKind::Secp256R1 => SigningOption::Secp256R1 { encoding: option.secp256r1_encoding.into() },
+            RccSigningOptionKind::Schnorr => SigningOption::Schnorr {
+                tweak: option.schnorr_tweak.then(|| TaprootTweak {
+                    merkle_root: some(option.schnorr_has_merkle_root, option.schnorr_merkle_root),
+                }),
+                aux_rand: some(option.schnorr_has_aux_rand, option.schnorr_aux_rand),
+            },
+            RccSigningOptionKind::Secp256k1 => SigningOption::Secp256k1 {
+                encoding: match option.secp256k1_encoding {
+                    RccSecp256k1Encoding::Ethereum => Secp256k1Encoding::Ethereum {
+                        chain_id: option.secp256k1_has_chain_id.then_some(option.secp256k1_chain_id),
+                    },
+                    RccSecp256k1Encoding::Bitcoin => Secp256k1Encoding::Bitcoin {
+                        sighash_type: option.secp256k1_sighash_type,
+                    },
+                    RccSecp256k1Encoding::Compact => Secp256k1Encoding::Compact,
+                    RccSecp256k1Encoding::Recoverable => Secp256k1Encoding::Recoverable,
+                },
+                hash: option.secp256k1_hash.into(),
+            },
+        }
+    }
+}
+
+unsafe fn read_signing_option(option: *const RccSigningOption) -> Option<SigningOption> {
+    option.as_ref().map(SigningOption::from)
+}
+
+unsafe fn signer<'a>(signer: *const RccSigner) -> Result<&'a Signer, FfiError> {
+    signer
+        .as_ref()
+        .map(|signer| &signer.0)
+        .ok_or(FfiError::new(RccErrorCode::NullPointer, "signer is NULL"))
+}
+
+unsafe fn new_signer(out: *mut *mut RccSigner, build: impl FnOnce() -> Result<Signer, FfiError>) -> RccErrorCode {
+    ffi_call(|| {
+        let out = out.as_mut().ok_or(FfiError::new(RccErrorCode::NullPointer, "out is NULL"))?;
+        *out = Box::into_raw(Box::new(RccSigner(build()?)));
+        Ok(())
+    })
+}
+
+/// Signer backed by the secure element on the serial port `port_name`.
+/// # Safety
+/// `port_name` is a NUL terminated string, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_signer_new_with_se(port_name: *const c_char, out: *mut *mut RccSigner) -> RccErrorCode {
+    new_signer(out, || Ok(Signer::new_with_se(read_string(port_name)?)))
+}
+
+/// Signer deriving its keys from the mnemonics imported into `keystore_dir` with rcc_software_import_mnemonic.
+/// # Safety
+/// `keystore_dir` is a NUL terminated string, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_signer_new_with_software(
+    keystore_dir: *const c_char,
+    out: *mut *mut RccSigner,
+) -> RccErrorCode {
+    new_signer(out, || Ok(Signer::new_with_software(read_string(keystore_dir)?)))
+}
+
+/// Releases a signer, freeing NULL does nothing.
+/// # Safety
+/// `signer` is NULL or a handle returned by this library that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_signer_free(signer: *mut RccSigner) {
+    if !signer.is_null() {
+        drop(Box::from_raw(signer));
+    }
+}
+
+/// Encrypts the mnemonic with `password` into the software keystore at `keystore_dir`.
+/// # Safety
+/// All strings are NUL terminated.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_software_import_mnemonic(
+    keystore_dir: *const c_char,
+    mnemonic_id: u8,
+    mnemonic: *const c_char,
+    passphrase: *const c_char,
+    password: *const c_char,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let software = Software::new(read_string(keystore_dir)?);
+        software.import_mnemonic(
+            mnemonic_id,
+            Zeroizing::new(read_string(mnemonic)?),
+            &read_password(passphrase)?,
+            &read_password(password)?,
+        )?;
+        Ok(())
+    })
+}
+
+/// New english mnemonic of `words` words, free `out` with rcc_string_free.
+/// # Safety
+/// `signer` is a live handle, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_signer_generate_mnemonic(
+    signer: *const RccSigner,
+    words: u32,
+    out: *mut *mut c_char,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let mnemonic = self::signer(signer)?.generate_mnemonic(EntropyLength::Short(words), MnemonicLanguage::English)?;
+        write_secret_string(out, &mnemonic)
+    })
+}
+
+/// Signs `data` with the key at `derivation_path`, free `out` with rcc_buffer_free.
+/// # Safety
+/// `signer` is a live handle, strings are NUL terminated, `data` points to `data_len` bytes, `option` is NULL or
+/// points to an option, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_signer_sign_data(
+    signer: *const RccSigner,
+    mnemonic_id: u8,
+    password: *const c_char,
+    data: *const u8,
+    data_len: usize,
+    algo: RccSigningAlgorithm,
+    derivation_path: *const c_char,
+    option: *const RccSigningOption,
+    out: *mut RccBuffer,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let signature = self::signer(signer)?.sign_data(
+            mnemonic_id,
+            &read_password(password)?,
+            read_bytes(data, data_len)?.to_vec(),
+            algo.into(),
+            read_string(derivation_path)?,
+            read_signing_option(option),
+        )?;
+        write_buffer(out, signature)
+    })
+}
+
+/// Public key at `derivation_path`, free `out` with rcc_buffer_free.
+/// # Safety
+/// `signer` is a live handle, strings are NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_signer_get_public_key(
+    signer: *const RccSigner,
+    mnemonic_id: u8,
+    password: *const c_char,
+    algo: RccSigningAlgorithm,
+    derivation_path: *const c_char,
+    format: RccPublicKeyFormat,
+    out: *mut RccBuffer,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let public_key = self::signer(signer)?.get_public_key(
+            mnemonic_id,
+            &read_password(password)?,
+            algo.into(),
+            read_string(derivation_path)?,
+            format.into(),
+        )?;
+        write_buffer(out, public_key)
+    })
+}
+
+/// RSA modulus of the wallet, free `out` with rcc_buffer_free.
+/// # Safety
+/// `signer` is a live handle, `password` is NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_signer_get_rsa_public_key(
+    signer: *const RccSigner,
+    mnemonic_id: u8,
+    password: *const c_char,
+    out: *mut RccBuffer,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let public_key = self::signer(signer)?.get_rsa_public_key(mnemonic_id, &read_password(password)?)?;
+        write_buffer(out, public_key)
+    })
+}
+
+/// Writes the 4 bytes master fingerprint to `out`.
+/// # Safety
+/// `signer` is a live handle, `password` is NUL terminated, `out` is valid for 4 bytes of writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_signer_get_master_fingerprint(
+    signer: *const RccSigner,
+    mnemonic_id: u8,
+    password: *const c_char,
+    out: *mut u8,
+) -> RccErrorCode {
+    ffi_call(|| {
+        if out.is_null() {
+            return Err(FfiError::new(RccErrorCode::NullPointer, "out is NULL"));
+        }
+        let fingerprint = self::signer(signer)?.get_master_fingerprint(mnemonic_id, &read_password(password)?)?;
+        std::ptr::copy_nonoverlapping(fingerprint.as_ptr(), out, fingerprint.len());
+        Ok(())
+    })
+}
+
+/// Cardano extended public key at `path`, free `out` with rcc_string_free.
+/// # Safety
+/// `signer` is a live handle, strings are NUL terminated, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_signer_get_ada_extended_public_key(
+    signer: *const RccSigner,
+    mnemonic_id: u8,
+    password: *const c_char,
+    path: *const c_char,
+    out: *mut *mut c_char,
+) -> RccErrorCode {
+    ffi_call(|| {
+        let xpub = self::signer(signer)?.get_ada_extended_public_key(
+            mnemonic_id,
+            &read_password(password)?,
+            read_string(path)?,
+        )?;
+        write_string(out, xpub)
+    })
+}
+
+/// Ok when `signature` is a valid signature of `data`, SignerSignatureMismatch or another error otherwise.
+/// # Safety
+/// Every byte pointer points to the number of bytes given next to it, `option` is NULL or points to an option.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_signer_verify(
+    public_key: *const u8,
+    public_key_len: usize,
+    data: *const u8,
+    data_len: usize,
+    signature: *const u8,
+    signature_len: usize,
+    algo: RccSigningAlgorithm,
+    option: *const RccSigningOption,
+) -> RccErrorCode {
+    ffi_call(|| {
+        Signer::verify(
+            read_bytes(public_key, public_key_len)?,
+            read_bytes(data, data_len)?,
+            read_bytes(signature, signature_len)?,
+            algo.into(),
+            read_signing_option(option),
+        )?;
+        Ok(())
+    })
+}
@@ -0,0 +1,25 @@
+/* rcc-ffi with the sui parser, every function of rcc_ffi.h is exported by this library too */
+
+#ifndef RCC_FFI_SUI_H
+#define RCC_FFI_SUI_H
+
+/* generated by cbindgen from the rcc-ffi-sui sources, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+#include "rcc_ffi.h"
+
+// Parses BCS encoded TransactionData into JSON, free `out` with rcc_string_free.
+// # Safety
+// `tx` points to `tx_len` bytes, `out` is valid for writes.
+enum RccErrorCode rcc_sui_parse_tx(const uint8_t *tx, size_t tx_len, char **out);
+
+// Parses a BCS encoded personal message into JSON, free `out` with rcc_string_free.
+// # Safety
+// `msg` points to `msg_len` bytes, `out` is valid for writes.
+enum RccErrorCode rcc_sui_parse_msg(const uint8_t *msg, size_t msg_len, char **out);
+
+#endif /* RCC_FFI_SUI_H */
@@ -0,0 +1,48 @@
+// rcc-ffi plus the sui parser, see include/rcc_ffi_sui.h
+//
+// rcc_sui sets cargo-features in its manifest, so any crate depending on it, even optionally, needs a nightly
+// cargo. This library links rcc-ffi in and exports all of its functions next to the sui ones.
+use std::ffi::c_char;
+
+pub use rcc_ffi::*;
+
+use rcc_ffi::ext::{ffi_call, read_bytes, write_string, FfiError};
+use rcc_sui::errors::SuiError;
+
+fn sui_error(error: SuiError) -> FfiError {
+    let code = match error {
+        SuiError::BcsDecodingError(_) => RccErrorCode::SuiBcsDecodingError,
+        SuiError::InvalidTransaction => RccErrorCode::SuiInvalidTransaction,
+        SuiError::SignFailure(_) => RccErrorCode::SuiSignFailure,
+        SuiError::InvalidHDPath(_) => RccErrorCode::SuiInvalidHDPath,
+        SuiError::KeystoreError(_) => RccErrorCode::SuiKeystoreError,
+        SuiError::InvalidAddressError(_) => RccErrorCode::SuiInvalidAddressError,
+    };
+    FfiError::new(code, error.to_string())
+}
+
+fn serialize_error(error: serde_json::Error) -> FfiError {
+    FfiError::new(RccErrorCode::InvalidArgument, error.to_string())
+}
+
+/// Parses BCS encoded TransactionData into JSON, free `out` with rcc_string_free.
+/// # Safety
+/// `tx` points to `tx_len` bytes, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_sui_parse_tx(tx: *const u8, tx_len: usize, out: *mut *mut c_char) -> RccErrorCode {
+    ffi_call(|| {
+        let tx = rcc_sui::parse_tx(read_bytes(tx, tx_len)?.to_vec()).map_err(sui_error)?;
+        write_string(out, serde_json::to_string(&tx).map_err(serialize_error)?)
+    })
+}
+
+/// Parses a BCS encoded personal message into JSON, free `out` with rcc_string_free.
+/// # Safety
+/// `msg` points to `msg_len` bytes, `out` is valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rcc_sui_parse_msg(msg: *const u8, msg_len: usize, out: *mut *mut c_char) -> RccErrorCode {
+    ffi_call(|| {
+        let msg = rcc_sui::parse_msg(read_bytes(msg, msg_len)?.to_vec()).map_err(sui_error)?;
+        write_string(out, serde_json::to_string(&msg).map_err(serialize_error)?)
+    })
+}
@@ -0,0 +1,153 @@
+// calls the library the way an integrator would, only through rcc_ffi.h
+#include <stdio.h>
+#include <string.h>
+
+#include "rcc_ffi.h"
+
+#define MNEMONIC "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+#define PASSWORD "test_pass"
+#define ETH_PATH "m/44'/60'/0'/0/0"
+
+#define CHECK(condition)                                                        \
+    do {                                                                        \
+        if (!(condition)) {                                                     \
+            const char *message = rcc_last_error_message();                     \
+            fprintf(stderr, "%s:%d: %s failed, last error: %s\n", __FILE__,     \
+                    __LINE__, #condition, message ? message : "none");          \
+            return 1;                                                           \
+        }                                                                       \
+    } while (0)
+
+static int test_signer(const char *keystore_dir) {
+    CHECK(rcc_software_import_mnemonic(keystore_dir, 0, MNEMONIC, "", PASSWORD) == RCC_ERROR_CODE_OK);
+
+    RccSigner *signer = NULL;
+    CHECK(rcc_signer_new_with_software(keystore_dir, &signer) == RCC_ERROR_CODE_OK);
+    CHECK(signer != NULL);
+
+    uint8_t fingerprint[4];
+    const uint8_t expected_fingerprint[4] = {0x73, 0xc5, 0xda, 0x0a};
+    CHECK(rcc_signer_get_master_fingerprint(signer, 0, PASSWORD, fingerprint) == RCC_ERROR_CODE_OK);
+    CHECK(memcmp(fingerprint, expected_fingerprint, 4) == 0);
+
+    RccBuffer public_key = {0};
+    CHECK(rcc_signer_get_public_key(signer, 0, PASSWORD, RCC_SIGNING_ALGORITHM_SECP256K1, ETH_PATH,
+                                    RCC_PUBLIC_KEY_FORMAT_COMPRESSED, &public_key) == RCC_ERROR_CODE_OK);
+    CHECK(public_key.len == 33);
+
+    uint8_t data[32];
+    memset(data, 0x01, sizeof(data));
+    RccBuffer signature = {0};
+    CHECK(rcc_signer_sign_data(signer, 0, PASSWORD, data, sizeof(data), RCC_SIGNING_ALGORITHM_SECP256K1, ETH_PATH,
+                               NULL, &signature) == RCC_ERROR_CODE_OK);
+    CHECK(signature.len > 0);
+    CHECK(rcc_signer_verify(public_key.data, public_key.len, data, sizeof(data), signature.data, signature.len,
+                            RCC_SIGNING_ALGORITHM_SECP256K1, NULL) == RCC_ERROR_CODE_OK);
+    CHECK(rcc_last_error_message() == NULL);
+
+    data[0] ^= 0xff;
+    CHECK(rcc_signer_verify(public_key.data, public_key.len, data, sizeof(data), signature.data, signature.len,
+                            RCC_SIGNING_ALGORITHM_SECP256K1, NULL) != RCC_ERROR_CODE_OK);
+    CHECK(rcc_last_error_message() != NULL);
+
+    RccBuffer wrong = {0};
+    CHECK(rcc_signer_sign_data(signer, 0, "wrong_pass", data, sizeof(data), RCC_SIGNING_ALGORITHM_SECP256K1,
+                               ETH_PATH, NULL, &wrong) != RCC_ERROR_CODE_OK);
+    CHECK(wrong.data == NULL);
+    CHECK(rcc_signer_sign_data(signer, 9, PASSWORD, data, sizeof(data), RCC_SIGNING_ALGORITHM_SECP256K1, ETH_PATH,
+                               NULL, &wrong) != RCC_ERROR_CODE_OK);
+
+    // r || s || v with EIP-155, v of chain 1 is 37 or 38
+    RccSigningOption ethereum = {0};
+    ethereum.kind = RCC_SIGNING_OPTION_KIND_SECP256K1;
+    ethereum.secp256k1_encoding = RCC_SECP256K1_ENCODING_ETHEREUM;
+    ethereum.secp256k1_hash = RCC_HASH_ALGORITHM_KECCAK256;
+    ethereum.secp256k1_has_chain_id = true;
+    ethereum.secp256k1_chain_id = 1;
+    const char *message = "hello";
+    RccBuffer ethereum_signature = {0};
+    CHECK(rcc_signer_sign_data(signer, 0, PASSWORD, (const uint8_t *)message, strlen(message),
+                               RCC_SIGNING_ALGORITHM_SECP256K1, ETH_PATH, &ethereum, &ethereum_signature) ==
+          RCC_ERROR_CODE_OK);
+    CHECK(ethereum_signature.len == 65);
+    CHECK(ethereum_signature.data[64] == 37 || ethereum_signature.data[64] == 38);
+    CHECK(rcc_signer_verify(public_key.data, public_key.len, (const uint8_t *)message, strlen(message),
+                            ethereum_signature.data, ethereum_signature.len, RCC_SIGNING_ALGORITHM_SECP256K1,
+                            &ethereum) == RCC_ERROR_CODE_OK);
+    rcc_buffer_free(&ethereum_signature);
+
+    // an option of another algorithm is refused
+    RccSigningOption ada = {0};
+    ada.kind = RCC_SIGNING_OPTION_KIND_ADA;
+    CHECK(rcc_signer_sign_data(signer, 0, PASSWORD, data, sizeof(data), RCC_SIGNING_ALGORITHM_SECP256K1, ETH_PATH,
+                               &ada, &wrong) != RCC_ERROR_CODE_OK);
+
+    rcc_buffer_free(&signature);
+    CHECK(signature.data == NULL && signature.len == 0);
+    rcc_buffer_free(&signature);
+    rcc_buffer_free(&public_key);
+
+    char *mnemonic = NULL;
+    CHECK(rcc_signer_generate_mnemonic(signer, 24, &mnemonic) == RCC_ERROR_CODE_OK);
+    CHECK(strlen(mnemonic) > 0);
+    rcc_string_free(mnemonic);
+    CHECK(rcc_signer_generate_mnemonic(signer, 13, &mnemonic) == RCC_ERROR_CODE_SIGNER_MNEMONIC_ERROR);
+
+    rcc_signer_free(signer);
+    rcc_signer_free(NULL);
+    return 0;
+}
+
+static int test_arguments(void) {
+    char *out = NULL;
+    CHECK(rcc_signer_new_with_software(NULL, NULL) == RCC_ERROR_CODE_NULL_POINTER);
+    CHECK(rcc_signer_sign_data(NULL, 0, PASSWORD, NULL, 0, RCC_SIGNING_ALGORITHM_SECP256K1, ETH_PATH, NULL, NULL) ==
+          RCC_ERROR_CODE_NULL_POINTER);
+    CHECK(rcc_bitcoin_derive_address("\xff", "m/0/0", "P2PKH", &out) == RCC_ERROR_CODE_INVALID_UTF8);
+    CHECK(out == NULL);
+    rcc_string_free(NULL);
+    return 0;
+}
+
+static int test_chains(void) {
+    char *address = NULL;
+    CHECK(rcc_bitcoin_derive_address(
+              "xpub6CPbc6auq3b8rEkXz6y78esTJ1SXLZwTJezUxoE8B7KZDfKo1qwJkHziR8MMat7P6RNf3aUPrUpMuKFQ8TRbfenCk6UvzCJXs1dHBdz2vcE",
+              "m/0/0", "P2PKH", &address) == RCC_ERROR_CODE_OK);
+    CHECK(strcmp(address, "1Kw42PtjJV4VWYzDfaPekqy1V4kgXEpqz8") == 0);
+    rcc_string_free(address);
+    CHECK(rcc_bitcoin_derive_address("xpub6CPbc6auq3b8rEkXz6y78esTJ1SXLZwTJezUxoE8", "m/0/0", "P2PKH", &address) ==
+          RCC_ERROR_CODE_BITCOIN_ADDRESS_DERIVATION_FAILED);
+    CHECK(strstr(rcc_last_error_message(), "xpub is not valid") != NULL);
+
+    // a MsgSend SignDoc
+    const char *sign_doc_hex =
+        "0a8f010a8c010a1c2f636f736d6f732e62616e6b2e763162657461312e4d736753656e64126c0a2d636f736d6f7331786573766b7236"
+        "64306a39366a357a64637735666d717861766a767576717832796779376d70122d636f736d6f7331786573766b723664306a39366a35"
+        "7a64637735666d717861766a767576717832796779376d701a0c0a057374616b65120331303012580a500a460a1f2f636f736d6f732e"
+        "63727970746f2e736563703235366b312e5075624b657912230a21035bc6eee695a089c273b690d7123c84cf6dbcb91e613c8b60b794"
+        "22a1ee68490612040a0208011803120410c09a0c1a04746573742001";
+    uint8_t sign_doc[512];
+    size_t sign_doc_len = strlen(sign_doc_hex) / 2;
+    for (size_t i = 0; i < sign_doc_len; i++) {
+        sscanf(sign_doc_hex + 2 * i, "%2hhx", &sign_doc[i]);
+    }
+    char *json = NULL;
+    CHECK(rcc_cosmos_parse(sign_doc, sign_doc_len, &json) == RCC_ERROR_CODE_OK);
+    CHECK(strstr(json, "\"to_address\":\"cosmos1xesvkr6d0j96j5zdcw5fmqxavjvuvqx2ygy7mp\"") != NULL);
+    rcc_string_free(json);
+    CHECK(rcc_cosmos_parse(sign_doc, 3, &json) == RCC_ERROR_CODE_COSMOS_PARSE_FAILED);
+    return 0;
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s <keystore dir>\n", argv[0]);
+        return 2;
+    }
+    if (test_signer(argv[1]) || test_arguments() || test_chains()) {
+        return 1;
+    }
+    printf("ok\n");
+    return 0;
+}
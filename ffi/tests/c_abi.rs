@@ -0,0 +1,52 @@
+// builds tests/c/abi_test.c against the cdylib and include/rcc_ffi.h, then runs it
+#![cfg(all(target_os = "linux", feature = "bitcoin", feature = "cosmos"))]
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+// the header is committed for integrators, regenerate it with
+// cbindgen --config cbindgen.toml --output include/rcc_ffi.h
+#[test]
+fn it_should_ship_the_generated_header() {
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let committed = std::fs::read_to_string(crate_dir.join("include/rcc_ffi.h")).unwrap();
+    assert!(
+        committed == include_str!(concat!(env!("OUT_DIR"), "/rcc_ffi.h")),
+        "include/rcc_ffi.h is out of date"
+    );
+}
+
+#[test]
+fn it_should_be_callable_from_c() {
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // cargo test builds the cdylib next to the test binary in target/<profile>/deps, only cargo build copies it up
+    let lib_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    assert!(lib_dir.join("librcc_ffi.so").exists(), "librcc_ffi.so is not built in {}", lib_dir.display());
+
+    let dir = tempfile::tempdir().unwrap();
+    let binary = dir.path().join("abi_test");
+    let compiled = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(crate_dir.join("tests/c/abi_test.c"))
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg(format!("-I{}", crate_dir.join("include").display()))
+        .arg(format!("-L{}", lib_dir.display()))
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lrcc_ffi")
+        .arg("-o")
+        .arg(&binary)
+        .status()
+        .unwrap();
+    assert!(compiled.success());
+
+    let keystore_dir = tempfile::tempdir().unwrap();
+    let output = Command::new(&binary).arg(keystore_dir.path()).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -1,20 +1,80 @@
 use std::str::FromStr;
 use bitcoin::bip32::{ChildNumber, DerivationPath};
 use ed25519_bip32_core::{DerivationScheme, XPrv, XPub};
-use cryptoxide::sha2::Sha512;
+use cryptoxide::hashing::sha256;
+use cryptoxide::sha2::{Sha256, Sha512};
+use cryptoxide::mac::Mac;
 use cryptoxide::{pbkdf2, hmac};
+use bip39::Mnemonic;
+use zeroize::Zeroizing;
+use crate::mnemonic::MnemonicLanguage;
+use crate::secret::SecretPassword;
+
+const LEDGER_HMAC_KEY: &[u8] = b"ed25519 seed";
+
+// how a wallet turns its mnemonic into the cardano master key, see CIP-0003
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdaMasterKeyScheme {
+    Icarus,
+    // trezor keeps the checksum byte in the entropy of 24 words mnemonics, other lengths match icarus
+    IcarusTrezor,
+    // iterated HMAC-SHA512 over the BIP39 seed, as the ledger app does, the seed depends on the mnemonic words
+    Ledger { language: MnemonicLanguage },
+}
+
+pub fn get_master_key(entropy: &[u8], passphrase: &SecretPassword, scheme: AdaMasterKeyScheme) -> Result<XPrv, String> {
+    let passphrase_bytes = passphrase.expose().as_bytes();
+    match scheme {
+        AdaMasterKeyScheme::Icarus => Ok(get_icarus_master_key(entropy, passphrase_bytes)),
+        AdaMasterKeyScheme::IcarusTrezor if entropy.len() == 32 => {
+            let mut entropy = Zeroizing::new(entropy.to_vec());
+            let checksum = sha256(&entropy)[0];
+            entropy.push(checksum);
+            Ok(get_icarus_master_key(&entropy, passphrase_bytes))
+        }
+        AdaMasterKeyScheme::IcarusTrezor => Ok(get_icarus_master_key(entropy, passphrase_bytes)),
+        AdaMasterKeyScheme::Ledger { language } => {
+            let mnemonic = Mnemonic::from_entropy_in(language.into(), entropy).map_err(|e| e.to_string())?;
+            let seed = Zeroizing::new(mnemonic.to_seed(passphrase.expose()));
+            Ok(get_ledger_master_key(seed.as_ref()))
+        }
+    }
+}
+
+pub fn get_ledger_master_key(seed: &[u8]) -> XPrv {
+    let mut message = Zeroizing::new([0u8; 64]);
+    hmac_sha512(seed, &mut message);
+    // retry until the third highest bit of the scalar is clear
+    while message[31] & 0b0010_0000 != 0 {
+        let previous = Zeroizing::new(*message);
+        hmac_sha512(previous.as_ref(), &mut message);
+    }
+    let mut chain_code = hmac::Hmac::new(Sha256::new(), LEDGER_HMAC_KEY);
+    chain_code.input(&[0x01]);
+    chain_code.input(seed);
+    let mut key = Zeroizing::new([0u8; 96]);
+    key[..64].copy_from_slice(message.as_ref());
+    chain_code.raw_result(&mut key[64..]);
+    XPrv::normalize_bytes_ed25519(*key)
+}
+
+fn hmac_sha512(data: &[u8], output: &mut [u8; 64]) {
+    let mut mac = hmac::Hmac::new(Sha512::new(), LEDGER_HMAC_KEY);
+    mac.input(data);
+    mac.raw_result(output);
+}
 
 pub fn get_icarus_master_key(entropy: &[u8], passphrase: &[u8]) -> XPrv {
-    let mut hash = [0u8; 96];
+    let mut hash = Zeroizing::new([0u8; 96]);
     let digest = Sha512::new();
     let iter_count = 4096;
     pbkdf2::pbkdf2(
         &mut hmac::Hmac::new(digest, passphrase),
         entropy,
         iter_count,
-        &mut hash,
+        hash.as_mut(),
     );
-    XPrv::normalize_bytes_force3rd(hash)
+    XPrv::normalize_bytes_force3rd(*hash)
 }
 
 pub fn get_extended_private_key(path: String, icarus_master_key: XPrv) -> Result<XPrv, String> {
@@ -54,3 +114,60 @@ pub fn normalize_path(path: &String) -> String {
     }
     p
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn master_key(mnemonic: &str, passphrase: &str, scheme: AdaMasterKeyScheme) -> String {
+        let entropy = Mnemonic::parse(mnemonic).unwrap().to_entropy();
+        let key = get_master_key(&entropy, &passphrase.into(), scheme).unwrap();
+        hex::encode(key.as_ref())
+    }
+
+    // CIP-0003 test vectors
+    #[test]
+    fn test_icarus_master_key() {
+        let mnemonic = "eight country switch draw meat scout mystery blade tip drift useless good keep usage title";
+        assert_eq!(master_key(mnemonic, "", AdaMasterKeyScheme::Icarus), "c065afd2832cd8b087c4d9ab7011f481ee1e0721e78ea5dd609f3ab3f156d245d176bd8fd4ec60b4731c3918a2a72a0226c0cd119ec35b47e4d55884667f552a23f7fdcd4a10c6cd2c7393ac61d877873e248f417634aa3d812af327ffe9d620");
+        assert_eq!(master_key(mnemonic, "foo", AdaMasterKeyScheme::Icarus), "70531039904019351e1afb361cd1b312a4d0565d4ff9f8062d38acf4b15cce41d7b5738d9c893feea55512a3004acb0d222c35d3e3d5cde943a15a9824cbac59443cf67e589614076ba01e354b1a432e0e6db3b59e37fc56b5fb0222970a010e");
+    }
+
+    #[test]
+    fn test_ledger_master_key() {
+        let mnemonic = "recall grace sport punch exhibit mad harbor stand obey short width stem awkward used stairs wool ugly trap season stove worth toward congress jaguar";
+        assert_eq!(master_key(mnemonic, "", AdaMasterKeyScheme::Ledger { language: MnemonicLanguage::English }), "a08cf85b564ecf3b947d8d4321fb96d70ee7bb760877e371899b14e2ccf88658104b884682b57efd97decbb318a45c05a527b9cc5c2f64f7352935a049ceea60680d52308194ccef2a18e6812b452a5815fbd7f5babc083856919aaf668fe7e4");
+        let english = AdaMasterKeyScheme::Ledger { language: MnemonicLanguage::English };
+        assert_ne!(master_key(mnemonic, "foo", english), master_key(mnemonic, "", english));
+        // the same entropy in japanese words gives another seed
+        let entropy = Mnemonic::parse(mnemonic).unwrap().to_entropy();
+        let japanese = Mnemonic::from_entropy_in(bip39::Language::Japanese, &entropy).unwrap();
+        let expected = get_ledger_master_key(&japanese.to_seed(""));
+        let key = get_master_key(&entropy, &"".into(), AdaMasterKeyScheme::Ledger { language: MnemonicLanguage::Japanese }).unwrap();
+        assert_eq!(hex::encode(key.as_ref()), hex::encode(expected.as_ref()));
+        assert_ne!(hex::encode(key.as_ref()), master_key(mnemonic, "", english));
+    }
+
+    // test_bip32_cardano_hdnode_vector_8 and _9 of trezor-crypto, m/0'/1'/2'/2'/1000000000' as key, extension, chain code
+    #[test]
+    fn test_icarus_trezor_master_key() {
+        let derive = |mnemonic: &str, scheme| {
+            let entropy = Mnemonic::parse(mnemonic).unwrap().to_entropy();
+            let master_key = get_master_key(&entropy, &"".into(), scheme).unwrap();
+            let key = get_extended_private_key("m/0'/1'/2'/2'/1000000000'".to_string(), master_key).unwrap();
+            hex::encode(key.as_ref())
+        };
+        // only 24 words mnemonics take the checksum byte along
+        let mnemonic = "found differ bulb shadow wrist blue bind vessel deposit tip pelican action surprise weapon check fiction muscle this";
+        assert_eq!(derive(mnemonic, AdaMasterKeyScheme::IcarusTrezor), "a0baa34e4e24f0500ed6e5e90ab41984b965b7464b0b28640528778dd8a6b854170e0d3b65ba8d71f27a6db60d0ac26dcb16e52e08cc259db72066f206b258d56fb22a4531ad79e828c4907c5fff3ecf686c16cb195f81243f1f0330173380e4");
+        assert_eq!(derive(mnemonic, AdaMasterKeyScheme::Icarus), derive(mnemonic, AdaMasterKeyScheme::IcarusTrezor));
+        let mnemonic = "balance exotic ranch knife glory slow tape favorite yard gym awake ill exist useless parent aim pig stay effort into square gasp credit butter";
+        assert_eq!(derive(mnemonic, AdaMasterKeyScheme::IcarusTrezor), "38eb2a79486e516cb6658700503a3e2c870c03e9d1aec731f780aa6fb7f7de4480d2c677638e5dbd4395cdec279bf2a42077f2797c9e887949d37cdb317fce6a9b226add79f90086ea18b260da633089fe121db758aa31284ad1affaf3c9bb68");
+        assert_ne!(derive(mnemonic, AdaMasterKeyScheme::Icarus), derive(mnemonic, AdaMasterKeyScheme::IcarusTrezor));
+    }
+
+    #[test]
+    fn test_ledger_master_key_rejects_bad_entropy() {
+        assert!(get_master_key(&[0u8; 15], &"".into(), AdaMasterKeyScheme::Ledger { language: MnemonicLanguage::English }).is_err());
+    }
+}
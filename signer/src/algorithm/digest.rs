@@ -0,0 +1,58 @@
+use cryptoxide::hashing;
+
+use crate::keymaster::HashAlgorithm;
+use crate::KSError;
+
+fn sha256d(data: &[u8]) -> [u8; 32] {
+    hashing::sha256(&hashing::sha256(data))
+}
+
+fn sha512_half(data: &[u8]) -> [u8; 32] {
+    let mut half = [0u8; 32];
+    half.copy_from_slice(&hashing::sha512(data)[..32]);
+    half
+}
+
+// every supported pre-hash, each producing the 32 bytes an ecdsa signature covers
+fn hash_data(hash: HashAlgorithm, data: &[u8]) -> [u8; 32] {
+    match hash {
+        HashAlgorithm::Keccak256 => hashing::keccak256(data),
+        HashAlgorithm::Sha256 => hashing::sha256(data),
+        HashAlgorithm::Sha256d => sha256d(data),
+        HashAlgorithm::Blake2b256 => hashing::blake2b_256(data),
+        HashAlgorithm::Sha3_256 => hashing::sha3_256(data),
+        HashAlgorithm::Sha512Half => sha512_half(data),
+    }
+}
+
+// the 32 bytes digest to sign, data is taken as the digest itself when no hash is given
+pub(crate) fn digest(hash: Option<HashAlgorithm>, data: &[u8]) -> Result<[u8; 32], KSError> {
+    match hash {
+        None => data
+            .try_into()
+            .map_err(|_| KSError::SignDataError("prehashed data should be 32 bytes digest".to_string())),
+        Some(hash) => Ok(hash_data(hash, data)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_digest_messages() {
+        let cases = [
+            (HashAlgorithm::Keccak256, "1c8aff950685c2ed4bc3174f3472287b56d9517b9c948127319a09a7a36deac8"),
+            (HashAlgorithm::Sha256, "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"),
+            (HashAlgorithm::Sha256d, "9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50"),
+            (HashAlgorithm::Blake2b256, "324dcf027dd4a30a932c441f365a25e86b173defa4b8e58948253471b81b72cf"),
+            (HashAlgorithm::Sha3_256, "3338be694f50c5f338814986cdf0686453a888b84f424d792af4b9202398f392"),
+            (HashAlgorithm::Sha512Half, "9b71d224bd62f3785d96d46ad3ea3d73319bfbc2890caadae2dff72519673ca7"),
+        ];
+        for (hash, expected) in cases {
+            assert_eq!(hex::encode(digest(Some(hash), b"hello").unwrap()), expected);
+        }
+        assert_eq!(digest(None, &[0x01; 32]).unwrap(), [0x01; 32]);
+        assert!(digest(None, b"hello").is_err());
+    }
+}
@@ -1,10 +1,82 @@
-use crate::{KSError, SigningOption};
+use bitcoin::base58;
+use bitcoin::bip32::ExtendedPubKey;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use zeroize::Zeroizing;
+use crate::keymaster::PublicKeyFormat;
+use crate::{KSError, SigningAlgorithm, SigningOption};
 
 pub mod rsa;
 pub mod secp256k1;
+pub mod schnorr;
+pub mod secp256r1;
 pub mod bip32_ed25519;
+pub mod slip10_ed25519;
+pub mod sr25519;
+pub mod verifier;
+pub mod digest;
+pub mod bip85;
 
 pub trait SecretKey {
     fn from_secret(secret: &[u8]) -> Result<Self, KSError> where Self: Sized;
     fn sign(&self, data: Vec<u8>, signing_option: Option<SigningOption>) -> Result<Vec<u8>, KSError>;
-}
\ No newline at end of file
+}
+
+// the SE returns the base58 encoded extended private key, the last 32 bytes before the checksum is the private key
+pub(crate) fn decode_extended_private_key(secret: &[u8]) -> Result<Zeroizing<Vec<u8>>, KSError> {
+    let base58_key = Zeroizing::new(String::from_utf8(secret.to_vec()).map_err(|_e| KSError::SEError("decode bs58 key error".to_string()))?);
+    let key = Zeroizing::new(bs58::decode(base58_key.as_str())
+        .into_vec()
+        .map_err(|_| KSError::SEError("decode bs58 key error".to_string()))?);
+    if key.len() < 32 + 4 {
+        return Err(KSError::SEError("decode bs58 key error".to_string()));
+    }
+    let start = key.len() - (32 + 4);
+    let end = key.len() - 4;
+    Ok(Zeroizing::new(key[start..end].to_vec()))
+}
+
+// the xpub is the base58 encoded BIP32 serialization, the last 33 bytes is the compressed public key
+pub(crate) fn format_extended_public_key(
+    xpub: &[u8],
+    algo: SigningAlgorithm,
+    format: PublicKeyFormat,
+) -> Result<Vec<u8>, KSError> {
+    let base58_key = String::from_utf8(xpub.to_vec())
+        .map_err(|_e| KSError::GetPublicKeyError("decode bs58 key error".to_string()))?;
+    let key = base58::decode_check(&base58_key)
+        .map_err(|_e| KSError::GetPublicKeyError("decode bs58 key error".to_string()))?;
+    if key.len() != 78 {
+        return Err(KSError::GetPublicKeyError("invalid extended public key length".to_string()));
+    }
+    let compressed = &key[45..];
+    match (algo, format) {
+        (_, PublicKeyFormat::ExtendedPublicKey) => Ok(base58_key.into_bytes()),
+        (_, PublicKeyFormat::Compressed) => Ok(compressed.to_vec()),
+        (SigningAlgorithm::Secp256k1, PublicKeyFormat::Uncompressed) => {
+            let public_key = bitcoin::secp256k1::PublicKey::from_slice(compressed)
+                .map_err(|e| KSError::GetPublicKeyError(e.to_string()))?;
+            Ok(public_key.serialize_uncompressed().to_vec())
+        }
+        (SigningAlgorithm::Secp256R1, PublicKeyFormat::Uncompressed) => {
+            let public_key = p256::PublicKey::from_sec1_bytes(compressed)
+                .map_err(|e| KSError::GetPublicKeyError(e.to_string()))?;
+            Ok(public_key.to_encoded_point(false).as_bytes().to_vec())
+        }
+        _ => Err(KSError::GetPublicKeyError(
+            "public key format is not supported".to_string(),
+        )),
+    }
+}
+
+// BIP32 fingerprint, the first 4 bytes of hash160 of the master public key
+pub(crate) fn master_fingerprint(master_xpub: &[u8]) -> Result<[u8; 4], KSError> {
+    let base58_key = String::from_utf8(master_xpub.to_vec())
+        .map_err(|_e| KSError::GetPublicKeyError("decode bs58 key error".to_string()))?;
+    let key = base58::decode_check(&base58_key)
+        .map_err(|_e| KSError::GetPublicKeyError("decode bs58 key error".to_string()))?;
+    let xpub = ExtendedPubKey::decode(&key).map_err(|e| KSError::GetPublicKeyError(e.to_string()))?;
+    if xpub.depth != 0 {
+        return Err(KSError::GetPublicKeyError("not a master public key".to_string()));
+    }
+    Ok(xpub.fingerprint().to_bytes())
+}
@@ -1,6 +1,7 @@
-use bytes::BytesMut;
 use openssl::hash::MessageDigest;
-use openssl::pkey::PKey;
+use openssl::bn::BigNum;
+use openssl::pkey::{HasPublic, PKey, PKeyRef};
+use openssl::rsa::Rsa;
 use rsa::{BigUint, pkcs8::{EncodePrivateKey}, PublicKeyParts, rand_core, RsaPrivateKey};
 use sha2::{Digest, Sha256};
 use rand_core::SeedableRng;
@@ -8,6 +9,8 @@ use rand_chacha::ChaCha20Rng;
 use crate::algorithm::SecretKey;
 use openssl::sign::{RsaPssSaltlen, Signer as OpensslSigner, Verifier};
 use crate::{KSError, SigningOption};
+use crate::secret::SecretBytes;
+use zeroize::Zeroizing;
 
 pub const MODULUS_LENGTH: usize = 4096;
 // secret = p || q || d || n
@@ -25,20 +28,16 @@ impl SecretKey for RSA {
         if secret.len() != SECRET_LENGTH_IN_BYTE {
             return Err(KSError::GenerateSigningKeyError("invalid secret length".to_string()));
         }
-        let mut p = BytesMut::with_capacity(PRIME_LENGTH_IN_BYTE);
-        p.extend_from_slice(&secret[0..PRIME_LENGTH_IN_BYTE]);
-        let mut q = BytesMut::with_capacity(PRIME_LENGTH_IN_BYTE);
-        q.extend_from_slice(&secret[PRIME_LENGTH_IN_BYTE..PRIME_LENGTH_IN_BYTE * 2]);
-        let mut d = BytesMut::with_capacity(MODULUS_LENGTH_IN_BYTE);
-        d.extend_from_slice(&secret[PRIME_LENGTH_IN_BYTE * 2..PRIME_LENGTH_IN_BYTE * 2 + MODULUS_LENGTH_IN_BYTE]);
-        let mut n = BytesMut::with_capacity(MODULUS_LENGTH_IN_BYTE);
-        n.extend_from_slice(&secret[PRIME_LENGTH_IN_BYTE * 2 + MODULUS_LENGTH_IN_BYTE..PRIME_LENGTH_IN_BYTE * 2 + MODULUS_LENGTH_IN_BYTE * 2]);
+        // the components are read in place, no copy of the secret is left behind
+        let (p, rest) = secret.split_at(PRIME_LENGTH_IN_BYTE);
+        let (q, rest) = rest.split_at(PRIME_LENGTH_IN_BYTE);
+        let (d, n) = rest.split_at(MODULUS_LENGTH_IN_BYTE);
         let e = vec![01, 00, 01];
         let private_key = RsaPrivateKey::from_components(
-            BigUint::from_bytes_be(&n),
+            BigUint::from_bytes_be(n),
             BigUint::from_bytes_be(&e),
-            BigUint::from_bytes_be(&d),
-            [BigUint::from_bytes_be(&p), BigUint::from_bytes_be(&q)].to_vec(),
+            BigUint::from_bytes_be(d),
+            [BigUint::from_bytes_be(p), BigUint::from_bytes_be(q)].to_vec(),
         ).map_err(|_| KSError::GenerateSigningKeyError("failed to compose rsa signing key".to_string()))?;
         Ok(Self {
             private_key
@@ -67,23 +66,23 @@ impl SecretKey for RSA {
 }
 
 impl RSA {
-    pub fn from_seed(seed: &[u8]) -> Result<Vec<u8>, KSError> {
+    pub fn from_seed(seed: &[u8]) -> Result<SecretBytes, KSError> {
         let mut intermediate;
         let mut hash = &seed[..];
         for _ in 0..2 {
             intermediate = Sha256::digest(&hash);
             hash = &intermediate[..];
         }
-        let rng_seed: [u8; 32] = hash.try_into().map_err(|_| KSError::GenerateSigningKeyError("rsa generate chacha20 rng_seed failed".to_string()))?;
-        let mut rng = ChaCha20Rng::from_seed(rng_seed);
+        let rng_seed: Zeroizing<[u8; 32]> = Zeroizing::new(hash.try_into().map_err(|_| KSError::GenerateSigningKeyError("rsa generate chacha20 rng_seed failed".to_string()))?);
+        let mut rng = ChaCha20Rng::from_seed(*rng_seed);
         let private_key =
             RsaPrivateKey::new(&mut rng, MODULUS_LENGTH).map_err(|_| KSError::GenerateSigningKeyError("generate rsa private key failed".to_string()))?;
-        let mut secret = BytesMut::with_capacity(PRIME_LENGTH_IN_BYTE * 2 + MODULUS_LENGTH_IN_BYTE * 2);
-        secret.extend_from_slice(&private_key.primes()[0].to_bytes_be());
-        secret.extend_from_slice(&private_key.primes()[1].to_bytes_be());
-        secret.extend_from_slice(&private_key.d().to_bytes_be());
+        let mut secret = Zeroizing::new(Vec::with_capacity(PRIME_LENGTH_IN_BYTE * 2 + MODULUS_LENGTH_IN_BYTE * 2));
+        secret.extend_from_slice(&Zeroizing::new(private_key.primes()[0].to_bytes_be()));
+        secret.extend_from_slice(&Zeroizing::new(private_key.primes()[1].to_bytes_be()));
+        secret.extend_from_slice(&Zeroizing::new(private_key.d().to_bytes_be()));
         secret.extend_from_slice(&private_key.n().to_bytes_be());
-        Ok(secret.to_vec())
+        Ok(SecretBytes::from(secret))
     }
 
     pub fn keypair_modulus(&self) -> Vec<u8> {
@@ -93,21 +92,32 @@ impl RSA {
     pub fn verify(&self, signature: &[u8], message: &[u8], signing_option: SigningOption) -> Result<(), KSError> {
         let private_key_der = self.private_key.to_pkcs8_der().map_err(|_| KSError::RSAVerifyError)?;
         let pkey = PKey::private_key_from_der(private_key_der.as_bytes()).map_err(|_| KSError::RSAVerifyError)?;
-        let mut verifier = Verifier::new(MessageDigest::sha256(), &pkey).map_err(|_| KSError::RSAVerifyError)?;
-        verifier.set_rsa_padding(openssl::rsa::Padding::PKCS1_PSS).map_err(|_| KSError::RSAVerifyError)?;
         match signing_option {
-            SigningOption::RSA {salt_len}=>{
-                let parsed_salt_len: i32 = salt_len.try_into().map_err(|_|KSError::RSASignError)?;
-                verifier
-                    .set_rsa_pss_saltlen(RsaPssSaltlen::custom(parsed_salt_len))
-                    .map_err(|_| KSError::RSAVerifyError)?;
-                verifier.update(&message).map_err(|_| KSError::RSAVerifyError)?;
-                verifier.verify(&signature).map_err(|_| KSError::RSAVerifyError)?;
-                Ok(())
-            },
+            SigningOption::RSA { salt_len } => verify_with_pkey(&pkey, signature, message, salt_len),
             _ => Err(KSError::RSAVerifyError)
         }
+    }
+}
+
+// the public exponent is fixed to 65537, only the modulus is needed to rebuild the public key
+pub fn verify_with_modulus(modulus: &[u8], signature: &[u8], message: &[u8], salt_len: i32) -> Result<(), KSError> {
+    let n = BigNum::from_slice(modulus).map_err(|e| KSError::VerifyError(e.to_string()))?;
+    let e = BigNum::from_u32(65537).map_err(|e| KSError::VerifyError(e.to_string()))?;
+    let rsa = Rsa::from_public_components(n, e).map_err(|e| KSError::VerifyError(e.to_string()))?;
+    let pkey = PKey::from_rsa(rsa).map_err(|e| KSError::VerifyError(e.to_string()))?;
+    verify_with_pkey(&pkey, signature, message, salt_len)
+}
 
+fn verify_with_pkey<T: HasPublic>(pkey: &PKeyRef<T>, signature: &[u8], message: &[u8], salt_len: i32) -> Result<(), KSError> {
+    let mut verifier = Verifier::new(MessageDigest::sha256(), pkey).map_err(|_| KSError::RSAVerifyError)?;
+    verifier.set_rsa_padding(openssl::rsa::Padding::PKCS1_PSS).map_err(|_| KSError::RSAVerifyError)?;
+    verifier
+        .set_rsa_pss_saltlen(RsaPssSaltlen::custom(salt_len))
+        .map_err(|_| KSError::RSAVerifyError)?;
+    verifier.update(message).map_err(|_| KSError::RSAVerifyError)?;
+    match verifier.verify(signature).map_err(|_| KSError::RSAVerifyError)? {
+        true => Ok(()),
+        false => Err(KSError::SignatureMismatch),
     }
 }
 
@@ -120,7 +130,7 @@ mod tests {
     fn test_sign_verify_salt_zero() {
         let seed_bytes = hex::decode("5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4").unwrap();
         let secret = RSA::from_seed(&seed_bytes).unwrap();
-        let rsa = RSA::from_secret(&secret).unwrap();
+        let rsa = RSA::from_secret(secret.expose()).unwrap();
         let message = hex::decode("00f41cfa7bfad3d7b097fcc28ed08cb4ca7d0c544ec760cc6cc5c4f3780d0ec43cc011eaaab0868393c3c813ab8c04df").unwrap();
         let signing_option = SigningOption::RSA {salt_len: 0};
         let signature = rsa.sign(message.clone(), Some(signing_option)).unwrap();
@@ -133,7 +143,7 @@ mod tests {
     fn test_sign_verify_salt_digest() {
         let seed_bytes = hex::decode("5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4").unwrap();
         let secret = RSA::from_seed(&seed_bytes).unwrap();
-        let rsa = RSA::from_secret(&secret).unwrap();
+        let rsa = RSA::from_secret(secret.expose()).unwrap();
         let message = hex::decode("00f41cfa7bfad3d7b097fcc28ed08cb4ca7d0c544ec760cc6cc5c4f3780d0ec43cc011eaaab0868393c3c813ab8c04df").unwrap();
         let signing_option = SigningOption::RSA {salt_len: 32};
         let signature = rsa.sign(message.clone(), Some(signing_option)).unwrap();
@@ -0,0 +1,190 @@
+use bitcoin::hashes::Hash;
+use bitcoin::key::{TapTweak, UntweakedKeyPair};
+use bitcoin::secp256k1::{schnorr, KeyPair, Message, Secp256k1, XOnlyPublicKey};
+use bitcoin::taproot::TapNodeHash;
+use crate::algorithm::{decode_extended_private_key, SecretKey};
+use crate::keymaster::TaprootTweak;
+use crate::{KSError, SigningOption};
+
+// BIP340 schnorr signing over secp256k1
+pub struct Schnorr {
+    keypair: KeyPair,
+}
+
+impl SecretKey for Schnorr {
+    fn from_secret(secret: &[u8]) -> Result<Schnorr, KSError> {
+        let private_key = decode_extended_private_key(secret)?;
+        Self::from_private_key(private_key.as_slice())
+    }
+
+    // data is the 32 bytes message to sign, e.g. the taproot sighash
+    fn sign(&self, data: Vec<u8>, signing_option: Option<SigningOption>) -> Result<Vec<u8>, KSError> {
+        match signing_option {
+            Some(SigningOption::Schnorr { tweak, aux_rand }) => {
+                let secp = Secp256k1::new();
+                let message = Message::from_slice(&data)
+                    .map_err(|e| KSError::SignDataError(e.to_string()))?;
+                let keypair = match tweak {
+                    Some(TaprootTweak { merkle_root }) => {
+                        let merkle_root = merkle_root.map(TapNodeHash::from_byte_array);
+                        UntweakedKeyPair::from(self.keypair)
+                            .tap_tweak(&secp, merkle_root)
+                            .to_inner()
+                    }
+                    None => self.keypair,
+                };
+                let signature = match aux_rand {
+                    Some(aux_rand) => secp.sign_schnorr_with_aux_rand(&message, &keypair, &aux_rand),
+                    None => secp.sign_schnorr(&message, &keypair),
+                };
+                Ok(signature.as_ref().to_vec())
+            }
+            _ => Err(KSError::SignDataError(
+                "signing option is not supported by schnorr".to_string(),
+            )),
+        }
+    }
+}
+
+impl Schnorr {
+    pub fn from_private_key(private_key: &[u8]) -> Result<Schnorr, KSError> {
+        let secp = Secp256k1::new();
+        let keypair = KeyPair::from_seckey_slice(&secp, private_key)
+            .map_err(|_e| KSError::GenerateSigningKeyError("schnorr".to_string()))?;
+        Ok(Self { keypair })
+    }
+}
+
+// BIP341 output key, Q = P + H_TapTweak(P || merkle_root)G
+pub fn tweak_public_key(internal_key: &[u8], merkle_root: Option<[u8; 32]>) -> Result<[u8; 32], KSError> {
+    let secp = Secp256k1::verification_only();
+    let internal_key = bitcoin::key::UntweakedPublicKey::from_slice(internal_key)
+        .map_err(|e| KSError::GetPublicKeyError(e.to_string()))?;
+    let (output_key, _parity) =
+        internal_key.tap_tweak(&secp, merkle_root.map(TapNodeHash::from_byte_array));
+    Ok(output_key.to_inner().serialize())
+}
+
+pub fn verify(message: &[u8], public_key: &[u8], signature: &[u8]) -> Result<(), KSError> {
+    let secp = Secp256k1::verification_only();
+    let message = Message::from_slice(message).map_err(|e| KSError::VerifyError(e.to_string()))?;
+    let public_key = XOnlyPublicKey::from_slice(public_key)
+        .map_err(|e| KSError::VerifyError(e.to_string()))?;
+    let signature = schnorr::Signature::from_slice(signature)
+        .map_err(|e| KSError::VerifyError(e.to_string()))?;
+    secp.verify_schnorr(&signature, &message, &public_key)
+        .map_err(|_| KSError::SignatureMismatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x_only_public_key(schnorr: &Schnorr) -> [u8; 32] {
+        schnorr.keypair.x_only_public_key().0.serialize()
+    }
+
+    fn schnorr_option(tweak: Option<TaprootTweak>, aux_rand: Option<[u8; 32]>) -> Option<SigningOption> {
+        Some(SigningOption::Schnorr { tweak, aux_rand })
+    }
+
+    // https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv
+    #[test]
+    fn test_bip340_vectors() {
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0",
+            ),
+            (
+                "b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef",
+                "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+                "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a",
+            ),
+            (
+                "c90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b14e5c9",
+                "dd308afec5777e13121fa72b9cc1b7cc0139715309b086c960e18fd969774eb8",
+                "c87aa53824b4d7ae2eb035a2b5bbbccc080e76cdc6d1692c4b0b62d798e6d906",
+                "7e2d58d8b3bcdf1abadec7829054f90dda9805aab56c77333024b9d0a508b75c",
+                "5831aaeed7b44bb74e5eab94ba9d4294c49bcf2a60728d8b4c200f50dd313c1bab745879a5ad954a72c45a91c3a51d3c7adea98d82f8481e0e1e03674a6f3fb7",
+            ),
+            (
+                "0b432b2677937381aef05bb02a66ecd012773062cf3fa2549e44f58ed2401710",
+                "25d1dff95105f5253c4022f628a996ad3a0d95fbf21d468a1b33f8c160d8f517",
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "7eb0509757e246f19449885651611cb965ecc1a187dd51b64fda1edc9637d5ec97582b9cb13db3933705b32ba982af5af25fd78881ebb32771fc5922efc66ea3",
+            ),
+        ];
+        for (secret_key, public_key, aux_rand, message, signature) in vectors {
+            let schnorr = Schnorr::from_private_key(&hex::decode(secret_key).unwrap()).unwrap();
+            assert_eq!(hex::encode(x_only_public_key(&schnorr)), public_key);
+            let aux_rand: [u8; 32] = hex::decode(aux_rand).unwrap().try_into().unwrap();
+            let message = hex::decode(message).unwrap();
+            let result = schnorr.sign(message.clone(), schnorr_option(None, Some(aux_rand))).unwrap();
+            assert_eq!(hex::encode(&result), signature);
+            assert!(verify(&message, &x_only_public_key(&schnorr), &result).is_ok());
+        }
+    }
+
+    // https://github.com/bitcoin/bips/blob/master/bip-0341/wallet-test-vectors.json
+    #[test]
+    fn test_bip341_output_key_vectors() {
+        let output_key = tweak_public_key(
+            &hex::decode("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d").unwrap(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(output_key),
+            "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343"
+        );
+        let merkle_root: [u8; 32] =
+            hex::decode("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let output_key = tweak_public_key(
+            &hex::decode("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27").unwrap(),
+            Some(merkle_root),
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(output_key),
+            "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3"
+        );
+    }
+
+    #[test]
+    fn test_sign_with_taproot_tweak() {
+        let schnorr = Schnorr::from_private_key(
+            &hex::decode("b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef").unwrap(),
+        )
+        .unwrap();
+        let message =
+            hex::decode("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89").unwrap();
+        for merkle_root in [None, Some([0x11u8; 32])] {
+            let signature = schnorr
+                .sign(message.clone(), schnorr_option(Some(TaprootTweak { merkle_root }), None))
+                .unwrap();
+            let output_key = tweak_public_key(&x_only_public_key(&schnorr), merkle_root).unwrap();
+            assert!(verify(&message, &output_key, &signature).is_ok());
+            assert!(verify(&message, &x_only_public_key(&schnorr), &signature).is_err());
+        }
+    }
+
+    #[test]
+    fn test_reject_invalid_input() {
+        let schnorr = Schnorr::from_private_key(
+            &hex::decode("b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef").unwrap(),
+        )
+        .unwrap();
+        assert!(schnorr.sign(vec![0u8; 31], schnorr_option(None, None)).is_err());
+        assert!(schnorr.sign(vec![0u8; 32], None).is_err());
+    }
+}
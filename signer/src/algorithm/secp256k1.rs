@@ -1,28 +1,249 @@
-use zeroize::Zeroizing;
-use crate::algorithm::SecretKey;
+use crate::algorithm::digest::digest;
+use crate::algorithm::{decode_extended_private_key, SecretKey};
+use crate::keymaster::{HashAlgorithm, Secp256k1Encoding};
 use crate::{KSError, SigningOption};
-use k256::ecdsa::SigningKey;
+use k256::ecdsa::{SigningKey, VerifyingKey};
 use crate::keymaster::hash_wraper::ShaWrapper;
-use k256::ecdsa::{recoverable::Signature, signature::DigestSigner,digest::Digest};
+use k256::ecdsa::{recoverable, recoverable::Signature, signature::DigestSigner, signature::DigestVerifier, digest::Digest};
 
 impl SecretKey for SigningKey {
     fn from_secret(secret: &[u8]) -> Result<SigningKey, KSError> {
-        let base58_key = String::from_utf8(secret.to_vec()).map_err(|_e| KSError::SEError("decode bs58 key error".to_string()))?;
-        let key = bs58::decode(base58_key)
-            .into_vec()
-            .map_err(|_| KSError::SEError("decode bs58 key error".to_string()))?;
-        let start = key.len() - (32 + 4);
-        let end = key.len() - 4;
-        let zeroize_private_key = Zeroizing::new(key[start..end].to_vec());
+        let zeroize_private_key = decode_extended_private_key(secret)?;
         let signing_key = SigningKey::from_bytes(zeroize_private_key.as_slice())
             .map_err(|_e| KSError::GenerateSigningKeyError("secp256k1".to_string()))?;
         Ok(signing_key)
     }
 
     fn sign(&self, data: Vec<u8>, signing_option: Option<SigningOption>) -> Result<Vec<u8>, KSError> {
-        let mut hash_wrapper = ShaWrapper::new();
-        hash_wrapper.update(data);
-        let signature: Signature = self.try_sign_digest(hash_wrapper).map_err(|e| KSError::SignDataError(e.to_string()))?;
-        Ok(signature.as_ref().to_vec())
+        match signing_option {
+            // data is the 32 bytes digest, signed as a recoverable signature
+            None => {
+                let (signature, recovery_id) = sign_digest(self, &digest(None, &data)?)?;
+                encode(&signature, recovery_id, Secp256k1Encoding::Recoverable)
+            }
+            Some(SigningOption::Secp256k1 { encoding, hash }) => {
+                let (signature, recovery_id) = sign_digest(self, &digest(hash, &data)?)?;
+                encode(&signature, recovery_id, encoding)
+            }
+            _ => Err(KSError::SignDataError(
+                "signing option is not supported by secp256k1".to_string(),
+            )),
+        }
     }
-}
\ No newline at end of file
+}
+
+// low-S signature of the digest and its recovery id, the wrapper hands the digest to k256 unchanged
+fn sign_digest(key: &SigningKey, digest: &[u8; 32]) -> Result<(k256::ecdsa::Signature, u8), KSError> {
+    let mut hash_wrapper = ShaWrapper::new();
+    hash_wrapper.update(digest);
+    let recoverable: Signature = key.try_sign_digest(hash_wrapper).map_err(|e| KSError::SignDataError(e.to_string()))?;
+    let recovery_id = u8::from(recoverable.recovery_id());
+    let signature = k256::ecdsa::Signature::from(recoverable);
+    // negating s mirrors R, so the recovery id flips with it
+    match signature.normalize_s() {
+        Some(normalized) => Ok((normalized, recovery_id ^ 1)),
+        None => Ok((signature, recovery_id)),
+    }
+}
+
+fn encode(signature: &k256::ecdsa::Signature, recovery_id: u8, encoding: Secp256k1Encoding) -> Result<Vec<u8>, KSError> {
+    let compact = signature.as_ref();
+    match encoding {
+        Secp256k1Encoding::Ethereum { chain_id } => {
+            let v = ethereum_v(chain_id, recovery_id)
+                .ok_or(KSError::SignDataError("chain id is too large".to_string()))?;
+            let v = v.to_be_bytes();
+            let start = v.iter().position(|byte| *byte != 0).unwrap_or(v.len() - 1);
+            Ok([compact, &v[start..]].concat())
+        }
+        Secp256k1Encoding::Bitcoin { sighash_type } => {
+            Ok([signature.to_der().as_bytes(), &[sighash_type]].concat())
+        }
+        Secp256k1Encoding::Compact => Ok(compact.to_vec()),
+        Secp256k1Encoding::Recoverable => Ok([compact, &[recovery_id]].concat()),
+    }
+}
+
+fn ethereum_v(chain_id: Option<u64>, recovery_id: u8) -> Option<u64> {
+    match chain_id {
+        None => Some(27 + recovery_id as u64),
+        Some(chain_id) => chain_id.checked_mul(2)?.checked_add(35 + recovery_id as u64),
+    }
+}
+
+fn decode(signature: &[u8], encoding: Secp256k1Encoding) -> Result<(k256::ecdsa::Signature, Option<u8>), KSError> {
+    let parse = |bytes: &[u8]| k256::ecdsa::Signature::try_from(bytes).map_err(|e| KSError::VerifyError(e.to_string()));
+    match encoding {
+        Secp256k1Encoding::Ethereum { chain_id } => {
+            if signature.len() <= 64 || signature.len() > 64 + 8 {
+                return Err(KSError::VerifyError("invalid ethereum signature length".to_string()));
+            }
+            let v = signature[64..].iter().fold(0u64, |v, byte| v << 8 | *byte as u64);
+            let recovery_id = (0..=1u8)
+                .find(|recovery_id| ethereum_v(chain_id, *recovery_id) == Some(v))
+                .ok_or(KSError::SignatureMismatch)?;
+            Ok((parse(&signature[..64])?, Some(recovery_id)))
+        }
+        Secp256k1Encoding::Bitcoin { sighash_type } => match signature.split_last() {
+            Some((last, der)) if *last == sighash_type => {
+                let signature = k256::ecdsa::Signature::from_der(der).map_err(|e| KSError::VerifyError(e.to_string()))?;
+                Ok((signature, None))
+            }
+            _ => Err(KSError::SignatureMismatch),
+        },
+        Secp256k1Encoding::Compact => Ok((parse(signature)?, None)),
+        Secp256k1Encoding::Recoverable => match signature.len() {
+            65 => Ok((parse(&signature[..64])?, Some(signature[64]))),
+            _ => Err(KSError::VerifyError("invalid recoverable signature length".to_string())),
+        },
+    }
+}
+
+// high-S signatures are rejected, a carried recovery id must recover the public key
+pub fn verify(
+    public_key: &[u8],
+    data: &[u8],
+    signature: &[u8],
+    encoding: Secp256k1Encoding,
+    hash: Option<HashAlgorithm>,
+) -> Result<(), KSError> {
+    let verifying_key = VerifyingKey::from_sec1_bytes(public_key).map_err(|e| KSError::VerifyError(e.to_string()))?;
+    let digest = digest(hash, data).map_err(|e| KSError::VerifyError(e.to_string()))?;
+    let (signature, recovery_id) = decode(signature, encoding)?;
+    if signature.normalize_s().is_some() {
+        return Err(KSError::SignatureMismatch);
+    }
+    let mut hash_wrapper = ShaWrapper::new();
+    hash_wrapper.update(digest);
+    verifying_key
+        .verify_digest(hash_wrapper, &signature)
+        .map_err(|_| KSError::SignatureMismatch)?;
+    if let Some(recovery_id) = recovery_id {
+        let recovery_id = recoverable::Id::new(recovery_id).map_err(|_| KSError::SignatureMismatch)?;
+        let recovered = Signature::new(&signature, recovery_id)
+            .and_then(|signature| signature.recover_verifying_key_from_digest_bytes(&digest.into()))
+            .map_err(|_| KSError::SignatureMismatch)?;
+        if recovered != verifying_key {
+            return Err(KSError::SignatureMismatch);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd";
+
+    fn key() -> SigningKey {
+        SigningKey::from_bytes(&[0x11u8; 32]).unwrap()
+    }
+
+    fn sign(data: &[u8], encoding: Secp256k1Encoding, hash: Option<HashAlgorithm>) -> Vec<u8> {
+        key().sign(data.to_vec(), Some(SigningOption::Secp256k1 { encoding, hash })).unwrap()
+    }
+
+    #[test]
+    fn it_should_encode_ethereum_signatures() {
+        let data = hex::decode(DIGEST).unwrap();
+        let recoverable = sign(&data, Secp256k1Encoding::Recoverable, None);
+        let recovery_id = recoverable[64];
+        assert!(recovery_id <= 1);
+
+        let signature = sign(&data, Secp256k1Encoding::Ethereum { chain_id: None }, None);
+        assert_eq!(signature[..64], recoverable[..64]);
+        assert_eq!(signature[64..], [27 + recovery_id]);
+        let signature = sign(&data, Secp256k1Encoding::Ethereum { chain_id: Some(1) }, None);
+        assert_eq!(signature[64..], [37 + recovery_id]);
+        // v takes as many bytes as the chain id needs
+        let signature = sign(&data, Secp256k1Encoding::Ethereum { chain_id: Some(0x1_0000) }, None);
+        assert_eq!(signature[64..], [0x02, 0x00, 0x23 + recovery_id]);
+        let result = key().sign(
+            data,
+            Some(SigningOption::Secp256k1 { encoding: Secp256k1Encoding::Ethereum { chain_id: Some(u64::MAX) }, hash: None }),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_encode_bitcoin_and_compact_signatures() {
+        let data = hex::decode(DIGEST).unwrap();
+        let compact = sign(&data, Secp256k1Encoding::Compact, None);
+        assert_eq!(compact.len(), 64);
+        let signature = sign(&data, Secp256k1Encoding::Bitcoin { sighash_type: 0x01 }, None);
+        assert_eq!(signature.last(), Some(&0x01));
+        let der = k256::ecdsa::Signature::from_der(&signature[..signature.len() - 1]).unwrap();
+        assert_eq!(der.as_ref(), compact.as_slice());
+        // the default signature is unchanged
+        assert_eq!(key().sign(data.clone(), None).unwrap(), sign(&data, Secp256k1Encoding::Recoverable, None));
+    }
+
+    #[test]
+    fn it_should_sign_low_s() {
+        for i in 0..16u8 {
+            let signature = sign(&[i; 32], Secp256k1Encoding::Compact, None);
+            let signature = k256::ecdsa::Signature::try_from(signature.as_slice()).unwrap();
+            assert!(signature.normalize_s().is_none());
+        }
+    }
+
+    #[test]
+    fn it_should_hash_messages_before_signing() {
+        let message = b"hello".to_vec();
+        let public_key = key().verifying_key().to_bytes();
+        let hashes = [
+            HashAlgorithm::Keccak256,
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Sha256d,
+            HashAlgorithm::Blake2b256,
+            HashAlgorithm::Sha3_256,
+            HashAlgorithm::Sha512Half,
+        ];
+        for hash in hashes {
+            let signature = sign(&message, Secp256k1Encoding::Compact, Some(hash));
+            let prehashed = sign(&digest(Some(hash), &message).unwrap(), Secp256k1Encoding::Compact, None);
+            assert_eq!(signature, prehashed);
+            assert!(verify(&public_key, &message, &signature, Secp256k1Encoding::Compact, Some(hash)).is_ok());
+        }
+        let option = Some(SigningOption::Secp256k1 { encoding: Secp256k1Encoding::Compact, hash: None });
+        assert!(key().sign(message, option).is_err());
+    }
+
+    #[test]
+    fn it_should_verify_encoded_signatures() {
+        let data = hex::decode(DIGEST).unwrap();
+        let public_key = key().verifying_key().to_bytes();
+        let encodings = [
+            Secp256k1Encoding::Ethereum { chain_id: None },
+            Secp256k1Encoding::Ethereum { chain_id: Some(137) },
+            Secp256k1Encoding::Bitcoin { sighash_type: 0x81 },
+            Secp256k1Encoding::Compact,
+            Secp256k1Encoding::Recoverable,
+        ];
+        for encoding in encodings {
+            let signature = sign(&data, encoding, None);
+            assert!(verify(&public_key, &data, &signature, encoding, None).is_ok());
+            assert!(matches!(
+                verify(&public_key, &[0u8; 32], &signature, encoding, None),
+                Err(KSError::SignatureMismatch)
+            ));
+        }
+
+        // wrong chain id and flipped recovery id
+        let signature = sign(&data, Secp256k1Encoding::Ethereum { chain_id: Some(1) }, None);
+        let result = verify(&public_key, &data, &signature, Secp256k1Encoding::Ethereum { chain_id: Some(5) }, None);
+        assert!(matches!(result, Err(KSError::SignatureMismatch)));
+        let mut signature = sign(&data, Secp256k1Encoding::Recoverable, None);
+        signature[64] ^= 1;
+        let result = verify(&public_key, &data, &signature, Secp256k1Encoding::Recoverable, None);
+        assert!(matches!(result, Err(KSError::SignatureMismatch)));
+
+        // the high-S twin of a valid signature
+        let compact = sign(&data, Secp256k1Encoding::Compact, None);
+        let low = k256::ecdsa::Signature::try_from(compact.as_slice()).unwrap();
+        let high = k256::ecdsa::Signature::from_scalars(low.r().to_bytes(), (-*low.s()).to_bytes()).unwrap();
+        let result = verify(&public_key, &data, high.as_ref(), Secp256k1Encoding::Compact, None);
+        assert!(matches!(result, Err(KSError::SignatureMismatch)));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::algorithm::{decode_extended_private_key, SecretKey};
+use crate::keymaster::SignatureEncoding;
+use crate::{KSError, SigningOption};
+use bitcoin::bip32::{ChildNumber, DerivationPath};
+use cryptoxide::hmac::Hmac;
+use cryptoxide::mac::Mac;
+use cryptoxide::sha2::Sha512;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use p256::elliptic_curve::{Field, PrimeField};
+use p256::{FieldBytes, Scalar};
+use std::str::FromStr;
+use zeroize::Zeroizing;
+use crate::algorithm::bip32_ed25519::normalize_path;
+
+const NIST256P1_CURVE_SEED: &[u8] = b"Nist256p1 seed";
+
+impl SecretKey for SigningKey {
+    fn from_secret(secret: &[u8]) -> Result<SigningKey, KSError> {
+        let private_key = decode_extended_private_key(secret)?;
+        let signing_key = SigningKey::from_bytes(private_key.as_slice())
+            .map_err(|_e| KSError::GenerateSigningKeyError("secp256r1".to_string()))?;
+        Ok(signing_key)
+    }
+
+    // data is hashed with sha256 before signing, the s value is always normalized to low-S
+    fn sign(&self, data: Vec<u8>, signing_option: Option<SigningOption>) -> Result<Vec<u8>, KSError> {
+        let signature: Signature = self
+            .try_sign(&data)
+            .map_err(|e| KSError::SignDataError(e.to_string()))?;
+        let signature = signature.normalize_s().unwrap_or(signature);
+        match signing_option {
+            None | Some(SigningOption::Secp256R1 { encoding: SignatureEncoding::Raw }) => {
+                Ok(signature.as_ref().to_vec())
+            }
+            Some(SigningOption::Secp256R1 { encoding: SignatureEncoding::DER }) => {
+                Ok(signature.to_der().as_bytes().to_vec())
+            }
+            _ => Err(KSError::SignDataError(
+                "signing option is not supported by secp256r1".to_string(),
+            )),
+        }
+    }
+}
+
+// SLIP-0010 nist256p1 extended private key, key || chain_code
+pub struct ExtendedPrivateKey {
+    key: Zeroizing<[u8; 32]>,
+    chain_code: [u8; 32],
+}
+
+impl ExtendedPrivateKey {
+    // the hmac output is hashed again until it yields a valid private key
+    pub fn new_master(seed: &[u8]) -> Self {
+        let mut output = hmac_sha512(NIST256P1_CURVE_SEED, &[seed]);
+        while to_scalar(&output[..32]).is_none_or(|k| bool::from(k.is_zero())) {
+            output = hmac_sha512(NIST256P1_CURVE_SEED, &[output.as_ref()]);
+        }
+        Self::from_output(&output, None)
+    }
+
+    pub fn derive_child(&self, child: ChildNumber) -> Self {
+        let index = u32::from(child).to_be_bytes();
+        let mut output = match child {
+            ChildNumber::Hardened { .. } => {
+                hmac_sha512(&self.chain_code, &[&[0u8], self.key.as_ref(), &index])
+            }
+            ChildNumber::Normal { .. } => {
+                hmac_sha512(&self.chain_code, &[&self.public_key(), &index])
+            }
+        };
+        // retry with 0x01 || IR || index when IL is not below the curve order or the child key is zero
+        loop {
+            let parent = to_scalar(self.key.as_ref());
+            let child_key = to_scalar(&output[..32])
+                .zip(parent)
+                .map(|(tweak, parent)| tweak + parent)
+                .filter(|k| !bool::from(k.is_zero()));
+            if let Some(child_key) = child_key {
+                return Self::from_output(&output, Some(child_key));
+            }
+            output = hmac_sha512(&self.chain_code, &[&[1u8], &output[32..], &index]);
+        }
+    }
+
+    #[cfg(any(test, feature = "simulator"))]
+    pub fn private_key(&self) -> &[u8; 32] {
+        &self.key
+    }
+
+    #[cfg(any(test, feature = "simulator"))]
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    pub fn signing_key(&self) -> Result<SigningKey, KSError> {
+        SigningKey::from_bytes(self.key.as_ref())
+            .map_err(|_e| KSError::GenerateSigningKeyError("secp256r1".to_string()))
+    }
+
+    pub fn public_key(&self) -> [u8; 33] {
+        let signing_key = SigningKey::from_bytes(self.key.as_ref()).expect("valid secp256r1 key");
+        let mut public_key = [0u8; 33];
+        public_key.copy_from_slice(signing_key.verifying_key().to_encoded_point(true).as_bytes());
+        public_key
+    }
+
+    fn from_output(output: &[u8; 64], key: Option<Scalar>) -> Self {
+        let mut private_key = Zeroizing::new([0u8; 32]);
+        match key {
+            Some(key) => private_key.copy_from_slice(&key.to_bytes()),
+            None => private_key.copy_from_slice(&output[..32]),
+        }
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&output[32..]);
+        Self { key: private_key, chain_code }
+    }
+}
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> Zeroizing<[u8; 64]> {
+    let mut hmac = Hmac::new(Sha512::new(), key);
+    data.iter().for_each(|d| hmac.input(d));
+    let mut output = Zeroizing::new([0u8; 64]);
+    hmac.raw_result(output.as_mut());
+    output
+}
+
+// None when the bytes are not below the curve order
+fn to_scalar(bytes: &[u8]) -> Option<Scalar> {
+    Scalar::from_repr(FieldBytes::clone_from_slice(bytes)).into()
+}
+
+pub fn get_extended_private_key(path: String, seed: &[u8]) -> Result<ExtendedPrivateKey, String> {
+    let path = normalize_path(&path);
+    let derivation_path = DerivationPath::from_str(path.as_str())
+        .map_err(|e| format!("{}", e))?;
+    let childrens: Vec<ChildNumber> = derivation_path.into();
+    Ok(childrens
+        .iter()
+        .fold(ExtendedPrivateKey::new_master(seed), |acc, cur| acc.derive_child(*cur)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_extended_private_key, SecretKey, Signature, SignatureEncoding, SigningKey, SigningOption};
+    use p256::ecdsa::{signature::Verifier, VerifyingKey};
+
+    const PRIVATE_KEY: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+
+    // https://www.rfc-editor.org/rfc/rfc6979#appendix-A.2.5
+    #[test]
+    fn test_sign_rfc6979_vector() {
+        let key = SigningKey::from_bytes(&hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+        let signature = key.sign(b"test".to_vec(), None).unwrap();
+        assert_eq!(
+            hex::encode(signature),
+            "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083"
+        );
+    }
+
+    #[test]
+    fn test_sign_normalize_high_s() {
+        // the rfc6979 signature of "sample" has s = f7cb1c94...43acda8, which is larger than n/2
+        let key = SigningKey::from_bytes(&hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+        let signature = key
+            .sign(
+                b"sample".to_vec(),
+                Some(SigningOption::Secp256R1 { encoding: SignatureEncoding::Raw }),
+            )
+            .unwrap();
+        assert_eq!(
+            hex::encode(signature),
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf37160834e36ad29a83bf2bc9385e491d6099c8fdf9d1ed67aa7ea5f51f93782857a9"
+        );
+    }
+
+    #[test]
+    fn test_sign_der_encoding() {
+        let key = SigningKey::from_bytes(&hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+        let signature = key
+            .sign(
+                b"test".to_vec(),
+                Some(SigningOption::Secp256R1 { encoding: SignatureEncoding::DER }),
+            )
+            .unwrap();
+        assert_eq!(
+            hex::encode(&signature),
+            "3045022100f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d383670220019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083"
+        );
+        let verifying_key = VerifyingKey::from(&key);
+        let signature = Signature::from_der(&signature).unwrap();
+        assert!(verifying_key.verify(b"test", &signature).is_ok());
+    }
+
+    #[test]
+    fn test_reject_other_signing_option() {
+        let key = SigningKey::from_bytes(&hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+        let result = key.sign(b"test".to_vec(), Some(SigningOption::RSA { salt_len: 0 }));
+        assert!(result.is_err());
+    }
+
+    // https://github.com/satoshilabs/slips/blob/master/slip-0010.md#test-vector-1-for-nist256p1
+    #[test]
+    fn test_slip10_vector_1() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let vectors = [
+            (
+                "m",
+                "beeb672fe4621673f722f38529c07392fecaa61015c80c34f29ce8b41b3cb6ea",
+                "612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2",
+                "0266874dc6ade47b3ecd096745ca09bcd29638dd52c2c12117b11ed3e458cfa9e8",
+            ),
+            (
+                "m/0'",
+                "3460cea53e6a6bb5fb391eeef3237ffd8724bf0a40e94943c98b83825342ee11",
+                "6939694369114c67917a182c59ddb8cafc3004e63ca5d3b84403ba8613debc0c",
+                "0384610f5ecffe8fda089363a41f56a5c7ffc1d81b59a612d0d649b2d22355590c",
+            ),
+            (
+                "m/0'/1",
+                "4187afff1aafa8445010097fb99d23aee9f599450c7bd140b6826ac22ba21d0c",
+                "284e9d38d07d21e4e281b645089a94f4cf5a5a81369acf151a1c3a57f18b2129",
+                "03526c63f8d0b4bbbf9c80df553fe66742df4676b241dabefdef67733e070f6844",
+            ),
+        ];
+        for (path, chain_code, private_key, public_key) in vectors {
+            let xprv = get_extended_private_key(path.to_string(), &seed).unwrap();
+            assert_eq!(hex::encode(xprv.chain_code()), chain_code, "{}", path);
+            assert_eq!(hex::encode(xprv.private_key()), private_key, "{}", path);
+            assert_eq!(hex::encode(xprv.public_key()), public_key, "{}", path);
+        }
+    }
+}
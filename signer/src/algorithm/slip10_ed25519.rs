@@ -0,0 +1,179 @@
+use std::str::FromStr;
+use bitcoin::bip32::{ChildNumber, DerivationPath};
+use cryptoxide::ed25519;
+use cryptoxide::hmac::Hmac;
+use cryptoxide::mac::Mac;
+use cryptoxide::sha2::Sha512;
+use zeroize::Zeroizing;
+use crate::algorithm::bip32_ed25519::normalize_path;
+
+const ED25519_CURVE_SEED: &[u8] = b"ed25519 seed";
+
+// SLIP-0010 extended private key, key || chain_code
+pub struct ExtendedPrivateKey {
+    key: Zeroizing<[u8; 32]>,
+    chain_code: [u8; 32],
+}
+
+impl ExtendedPrivateKey {
+    pub fn new_master(seed: &[u8]) -> Self {
+        Self::from_hmac(ED25519_CURVE_SEED, &[seed])
+    }
+
+    // ed25519 only supports hardened child derivation
+    pub fn derive_hardened(&self, index: u32) -> Self {
+        let index_bytes = (index | 0x80000000).to_be_bytes();
+        Self::from_hmac(&self.chain_code, &[&[0u8], self.key.as_ref(), &index_bytes])
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        let (_, public_key) = ed25519::keypair(&self.key);
+        public_key
+    }
+
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        let (keypair, _) = ed25519::keypair(&self.key);
+        let keypair = Zeroizing::new(keypair);
+        ed25519::signature(message, &keypair)
+    }
+
+    fn from_hmac(key: &[u8], data: &[&[u8]]) -> Self {
+        let mut hmac = Hmac::new(Sha512::new(), key);
+        data.iter().for_each(|d| hmac.input(d));
+        let mut output = Zeroizing::new([0u8; 64]);
+        hmac.raw_result(output.as_mut());
+        let mut key = Zeroizing::new([0u8; 32]);
+        let mut chain_code = [0u8; 32];
+        key.copy_from_slice(&output[..32]);
+        chain_code.copy_from_slice(&output[32..]);
+        Self { key, chain_code }
+    }
+}
+
+pub fn get_extended_private_key(path: String, seed: &[u8]) -> Result<ExtendedPrivateKey, String> {
+    let path = normalize_path(&path);
+    let derivation_path = DerivationPath::from_str(path.as_str())
+        .map_err(|e| format!("{}", e))?;
+    let childrens: Vec<ChildNumber> = derivation_path.into();
+    childrens
+        .iter()
+        .try_fold(ExtendedPrivateKey::new_master(seed), |acc, cur| match cur {
+            ChildNumber::Hardened { index } => Ok(acc.derive_hardened(*index)),
+            ChildNumber::Normal { index } => Err(format!(
+                "non-hardened index {} is not supported by ed25519",
+                index
+            )),
+        })
+}
+
+pub fn get_public_key(path: String, seed: &[u8]) -> Result<[u8; 32], String> {
+    let xprv = get_extended_private_key(path, seed)?;
+    Ok(xprv.public_key())
+}
+
+pub fn sign_message(message: &[u8], path: String, seed: &[u8]) -> Result<[u8; 64], String> {
+    let xprv = get_extended_private_key(path, seed)?;
+    Ok(xprv.sign(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // https://github.com/satoshilabs/slips/blob/master/slip-0010.md#test-vector-1-for-ed25519
+    #[test]
+    fn test_slip10_vector_1() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let vectors = [
+            (
+                "m",
+                "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+                "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+                "a4b2856bfec510abab89753fac1ac0e1112364e7d250545963f135f2a33188ed",
+            ),
+            (
+                "m/0'",
+                "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69",
+                "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+                "8c8a13df77a28f3445213a0f432fde644acaa215fc72dcdf300d5efaa85d350c",
+            ),
+            (
+                "m/0'/1'",
+                "a320425f77d1b5c2505a6b1b27382b37368ee640e3557c315416801243552f14",
+                "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2",
+                "1932a5270f335bed617d5b935c80aedb1a35bd9fc1e31acafd5372c30f5c1187",
+            ),
+            (
+                "m/0'/1'/2'",
+                "2e69929e00b5ab250f49c3fb1c12f252de4fed2c1db88387094a0f8c4c9ccd6c",
+                "92a5b23c0b8a99e37d07df3fb9966917f5d06e02ddbd909c7e184371463e9fc9",
+                "ae98736566d30ed0e9d2f4486a64bc95740d89c7db33f52121f8ea8f76ff0fc1",
+            ),
+            (
+                "m/0'/1'/2'/2'",
+                "8f6d87f93d750e0efccda017d662a1b31a266e4a6f5993b15f5c1f07f74dd5cc",
+                "30d1dc7e5fc04c31219ab25a27ae00b50f6fd66622f6e9c913253d6511d1e662",
+                "8abae2d66361c879b900d204ad2cc4984fa2aa344dd7ddc46007329ac76c429c",
+            ),
+            (
+                "m/0'/1'/2'/2'/1000000000'",
+                "68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230",
+                "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+                "3c24da049451555d51a7014a37337aa4e12d41e485abccfa46b47dfb2af54b7a",
+            ),
+        ];
+        for (path, chain_code, private_key, public_key) in vectors {
+            let xprv = get_extended_private_key(path.to_string(), &seed).unwrap();
+            assert_eq!(hex::encode(xprv.chain_code), chain_code, "{}", path);
+            assert_eq!(hex::encode(*xprv.key), private_key, "{}", path);
+            assert_eq!(hex::encode(xprv.public_key()), public_key, "{}", path);
+        }
+    }
+
+    // https://github.com/satoshilabs/slips/blob/master/slip-0010.md#test-vector-2-for-ed25519
+    #[test]
+    fn test_slip10_vector_2() {
+        let seed = hex::decode("fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542").unwrap();
+        let vectors = [
+            (
+                "m",
+                "ef70a74db9c3a5af931b5fe73ed8e1a53464133654fd55e7a66f8570b8e33c3b",
+                "171cb88b1b3c1db25add599712e36245d75bc65a1a5c9e18d76f9f2b1eab4012",
+                "8fe9693f8fa62a4305a140b9764c5ee01e455963744fe18204b4fb948249308a",
+            ),
+            (
+                "m/0'/2147483647'/1'/2147483646'/2'",
+                "5d70af781f3a37b829f0d060924d5e960bdc02e85423494afc0b1a41bbe196d4",
+                "551d333177df541ad876a60ea71f00447931c0a9da16f227c11ea080d7391b8d",
+                "47150c75db263559a70d5778bf36abbab30fb061ad69f69ece61a72b0cfa4fc0",
+            ),
+        ];
+        for (path, chain_code, private_key, public_key) in vectors {
+            let xprv = get_extended_private_key(path.to_string(), &seed).unwrap();
+            assert_eq!(hex::encode(xprv.chain_code), chain_code, "{}", path);
+            assert_eq!(hex::encode(*xprv.key), private_key, "{}", path);
+            assert_eq!(hex::encode(xprv.public_key()), public_key, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_reject_non_hardened_path() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let result = get_extended_private_key("m/44'/501'/0".to_string(), &seed);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let seed = hex::decode("5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4").unwrap();
+        let path = "m/44'/501'/0'/0'".to_string();
+        let message = b"hello solana";
+        let signature = sign_message(message, path.clone(), &seed).unwrap();
+        let public_key = get_public_key(path, &seed).unwrap();
+        assert_eq!(
+            bs58::encode(public_key).into_string(),
+            "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk"
+        );
+        assert!(ed25519::verify(message, &public_key, &signature));
+    }
+}
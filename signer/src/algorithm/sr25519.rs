@@ -0,0 +1,215 @@
+use cryptoxide::blake2b::Blake2b;
+use cryptoxide::hmac::Hmac;
+use cryptoxide::pbkdf2;
+use cryptoxide::sha2::Sha512;
+use schnorrkel::derive::{ChainCode, Derivation};
+use schnorrkel::{ExpansionMode, Keypair, MiniSecretKey, PublicKey, SecretKey, Signature};
+use zeroize::Zeroizing;
+use crate::KSError;
+
+const SIGNING_CTX: &[u8] = b"substrate";
+const JUNCTION_ID_LEN: usize = 32;
+
+pub enum DeriveJunction {
+    Soft([u8; JUNCTION_ID_LEN]),
+    Hard([u8; JUNCTION_ID_LEN]),
+}
+
+impl DeriveJunction {
+    // numeric junctions are scale encoded as u64, others as scale encoded strings,
+    // ids longer than 32 bytes are hashed with blake2b-256
+    fn new(code: &str, hard: bool) -> Self {
+        let encoded = match code.parse::<u64>() {
+            Ok(n) => n.to_le_bytes().to_vec(),
+            Err(_) => {
+                let mut encoded = compact_encode_len(code.len());
+                encoded.extend_from_slice(code.as_bytes());
+                encoded
+            }
+        };
+        let mut id = [0u8; JUNCTION_ID_LEN];
+        if encoded.len() > JUNCTION_ID_LEN {
+            Blake2b::blake2b(&mut id, &encoded, &[]);
+        } else {
+            id[..encoded.len()].copy_from_slice(&encoded);
+        }
+        if hard {
+            DeriveJunction::Hard(id)
+        } else {
+            DeriveJunction::Soft(id)
+        }
+    }
+}
+
+// substrate style derivation path, e.g. "//polkadot//0/1///password"
+pub struct DerivationPath {
+    pub junctions: Vec<DeriveJunction>,
+    pub password: Option<Zeroizing<String>>,
+}
+
+impl DerivationPath {
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let (path, password) = match path.find("///") {
+            Some(index) => (
+                &path[..index],
+                Some(Zeroizing::new(path[index + 3..].to_string())),
+            ),
+            None => (path, None),
+        };
+        if !path.is_empty() && !path.starts_with('/') {
+            return Err(format!("invalid sr25519 derivation path {}", path));
+        }
+        let mut junctions = vec![];
+        let mut rest = path;
+        while !rest.is_empty() {
+            let hard = rest.starts_with("//");
+            rest = if hard { &rest[2..] } else { &rest[1..] };
+            let end = rest.find('/').unwrap_or(rest.len());
+            let code = &rest[..end];
+            if code.is_empty() {
+                return Err(format!("invalid sr25519 derivation path {}", path));
+            }
+            junctions.push(DeriveJunction::new(code, hard));
+            rest = &rest[end..];
+        }
+        Ok(Self { junctions, password })
+    }
+}
+
+// substrate derives the mini secret key from the bip39 entropy rather than the bip39 seed
+pub fn get_mini_secret_key(entropy: &[u8], password: &str) -> Result<MiniSecretKey, String> {
+    let salt = Zeroizing::new(format!("mnemonic{}", password));
+    let mut seed = Zeroizing::new([0u8; 64]);
+    pbkdf2::pbkdf2(
+        &mut Hmac::new(Sha512::new(), entropy),
+        salt.as_bytes(),
+        2048,
+        seed.as_mut(),
+    );
+    MiniSecretKey::from_bytes(&seed[..32]).map_err(|e| e.to_string())
+}
+
+pub fn get_keypair(path: String, entropy: &[u8]) -> Result<Keypair, String> {
+    let path = DerivationPath::parse(&path)?;
+    let password = path.password.as_ref().map(|p| p.as_str()).unwrap_or("");
+    let mini_secret_key = get_mini_secret_key(entropy, password)?;
+    let secret_key = path.junctions.iter().fold(
+        mini_secret_key.expand(ExpansionMode::Ed25519),
+        |acc: SecretKey, junction| match junction {
+            DeriveJunction::Soft(cc) => acc.derived_key_simple(ChainCode(*cc), []).0,
+            DeriveJunction::Hard(cc) => acc
+                .hard_derive_mini_secret_key(Some(ChainCode(*cc)), b"")
+                .0
+                .expand(ExpansionMode::Ed25519),
+        },
+    );
+    Ok(secret_key.to_keypair())
+}
+
+pub fn get_public_key(path: String, entropy: &[u8]) -> Result<[u8; 32], String> {
+    let keypair = get_keypair(path, entropy)?;
+    Ok(keypair.public.to_bytes())
+}
+
+pub fn sign_message(message: &[u8], path: String, entropy: &[u8]) -> Result<[u8; 64], String> {
+    let keypair = get_keypair(path, entropy)?;
+    Ok(keypair.sign_simple(SIGNING_CTX, message).to_bytes())
+}
+
+pub fn verify(message: &[u8], public_key: &[u8], signature: &[u8]) -> Result<(), KSError> {
+    let public_key = PublicKey::from_bytes(public_key).map_err(|e| KSError::VerifyError(e.to_string()))?;
+    let signature = Signature::from_bytes(signature).map_err(|e| KSError::VerifyError(e.to_string()))?;
+    public_key
+        .verify_simple(SIGNING_CTX, message, &signature)
+        .map_err(|_| KSError::SignatureMismatch)
+}
+
+fn compact_encode_len(len: usize) -> Vec<u8> {
+    match len {
+        0..=0x3f => vec![(len as u8) << 2],
+        0x40..=0x3fff => (((len as u16) << 2) | 0b01).to_le_bytes().to_vec(),
+        _ => (((len as u32) << 2) | 0b10).to_le_bytes().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bip39::{Language, Mnemonic};
+
+    const DEV_PHRASE: &str = "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+
+    fn dev_entropy() -> Vec<u8> {
+        Mnemonic::parse_in(Language::English, DEV_PHRASE)
+            .unwrap()
+            .to_entropy()
+    }
+
+    // https://github.com/paritytech/substrate/blob/master/primitives/core/src/sr25519.rs
+    #[test]
+    fn test_derive_hard_known_pair() {
+        let public_key = get_public_key("//Alice".to_string(), &dev_entropy()).unwrap();
+        assert_eq!(
+            hex::encode(public_key),
+            "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+        );
+        let public_key = get_public_key("//Bob".to_string(), &dev_entropy()).unwrap();
+        assert_eq!(
+            hex::encode(public_key),
+            "8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48"
+        );
+    }
+
+    #[test]
+    fn test_derive_soft_known_pair() {
+        let public_key = get_public_key("/Alice".to_string(), &dev_entropy()).unwrap();
+        assert_eq!(
+            hex::encode(public_key),
+            "d6c71059dbbe9ad2b0ed3f289738b800836eb425544ce694825285b958ca755e"
+        );
+    }
+
+    #[test]
+    fn test_derive_multiple_hard_junctions() {
+        let public_key = get_public_key("//Alice//stash".to_string(), &dev_entropy()).unwrap();
+        assert_eq!(
+            hex::encode(public_key),
+            "be5ddb1579b72e84524fc29e78609e3caf42e85aa118ebfe0b0ad404b5bdd25f"
+        );
+    }
+
+    #[test]
+    fn test_derive_with_password() {
+        let entropy = dev_entropy();
+        let with_password = get_public_key("//Alice///password".to_string(), &entropy).unwrap();
+        assert_eq!(
+            hex::encode(with_password),
+            "32fc18294f88e02ec071e59bb3996aa21f4519d92593dc6e01fda2921d459b23"
+        );
+        let without_password = get_public_key("//Alice".to_string(), &entropy).unwrap();
+        assert_ne!(with_password, without_password);
+    }
+
+    #[test]
+    fn test_parse_path() {
+        let path = DerivationPath::parse("//polkadot//0/1///secret").unwrap();
+        assert_eq!(path.junctions.len(), 3);
+        assert!(matches!(path.junctions[0], DeriveJunction::Hard(_)));
+        assert!(matches!(path.junctions[1], DeriveJunction::Hard(id) if id[..8] == [0u8; 8]));
+        assert!(matches!(path.junctions[2], DeriveJunction::Soft(id) if id[0] == 1));
+        assert_eq!(path.password.unwrap().as_str(), "secret");
+        assert!(DerivationPath::parse("m/44'/354'").is_err());
+        assert!(DerivationPath::parse("//polkadot//").is_err());
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let entropy = dev_entropy();
+        let path = "//polkadot//0/1".to_string();
+        let message = b"hello polkadot";
+        let signature = sign_message(message, path.clone(), &entropy).unwrap();
+        let public_key = get_public_key(path, &entropy).unwrap();
+        assert!(verify(message, &public_key, &signature).is_ok());
+        assert!(verify(b"another message", &public_key, &signature).is_err());
+    }
+}
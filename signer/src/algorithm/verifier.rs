@@ -0,0 +1,259 @@
+use crate::algorithm;
+use crate::keymaster::hash_wraper::ShaWrapper;
+use crate::keymaster::{SignatureEncoding, TaprootTweak};
+use crate::{KSError, SigningAlgorithm, SigningOption};
+use ed25519_bip32_core::{Signature as XPubSignature, XPub};
+use k256::ecdsa::digest::Digest;
+use k256::ecdsa::signature::{DigestVerifier, Verifier};
+
+pub fn verify(
+    public_key: &[u8],
+    data: &[u8],
+    signature: &[u8],
+    algo: SigningAlgorithm,
+    signing_option: Option<SigningOption>,
+) -> Result<(), KSError> {
+    match (algo, signing_option) {
+        (SigningAlgorithm::Secp256k1, Some(SigningOption::Schnorr { tweak, .. })) => {
+            // x-only public key, a compressed key is accepted as well
+            let public_key = match public_key.len() {
+                33 => &public_key[1..],
+                _ => public_key,
+            };
+            match tweak {
+                // a BIP341 key path signature is made by the output key of the internal key
+                Some(TaprootTweak { merkle_root }) => {
+                    let output_key = algorithm::schnorr::tweak_public_key(public_key, merkle_root)
+                        .map_err(|e| KSError::VerifyError(e.to_string()))?;
+                    algorithm::schnorr::verify(data, &output_key, signature)
+                }
+                None => algorithm::schnorr::verify(data, public_key, signature),
+            }
+        }
+        (SigningAlgorithm::Secp256k1, Some(SigningOption::Secp256k1 { encoding, hash })) => {
+            algorithm::secp256k1::verify(public_key, data, signature, encoding, hash)
+        }
+        (SigningAlgorithm::Secp256k1, _) => verify_secp256k1(public_key, data, signature),
+        (SigningAlgorithm::Secp256R1, _) => {
+            verify_secp256r1(public_key, data, signature, signing_option)
+        }
+        (SigningAlgorithm::Ed25519, Some(SigningOption::ADA)) => {
+            // 64 bytes bip32-ed25519 extended public key or the 32 bytes public key
+            let xpub = match public_key.len() {
+                32 => [public_key, &[0u8; 32]].concat(),
+                _ => public_key.to_vec(),
+            };
+            let xpub = XPub::from_slice(&xpub).map_err(|e| KSError::VerifyError(e.to_string()))?;
+            let signature = XPubSignature::<Vec<u8>>::from_slice(signature)
+                .map_err(|e| KSError::VerifyError(e.to_string()))?;
+            match xpub.verify(data, &signature) {
+                true => Ok(()),
+                false => Err(KSError::SignatureMismatch),
+            }
+        }
+        (SigningAlgorithm::Ed25519, _) => {
+            let public_key: [u8; 32] = public_key
+                .try_into()
+                .map_err(|_| KSError::VerifyError("invalid ed25519 public key length".to_string()))?;
+            let signature: [u8; 64] = signature
+                .try_into()
+                .map_err(|_| KSError::VerifyError("invalid ed25519 signature length".to_string()))?;
+            match cryptoxide::ed25519::verify(data, &public_key, &signature) {
+                true => Ok(()),
+                false => Err(KSError::SignatureMismatch),
+            }
+        }
+        (SigningAlgorithm::SR25519, _) => algorithm::sr25519::verify(data, public_key, signature),
+        (SigningAlgorithm::RSA, Some(SigningOption::RSA { salt_len })) => {
+            // public key is the modulus returned by get_rsa_public_key
+            algorithm::rsa::verify_with_modulus(public_key, signature, data, salt_len)
+        }
+        (SigningAlgorithm::RSA, _) => Err(KSError::VerifyError(
+            "rsa verification requires the salt length".to_string(),
+        )),
+    }
+}
+
+// data is the 32 bytes digest, the signature is either the 65 bytes recoverable signature or the 64 bytes compact r || s
+fn verify_secp256k1(public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<(), KSError> {
+    let verifying_key = k256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+        .map_err(|e| KSError::VerifyError(e.to_string()))?;
+    if data.len() != 32 {
+        return Err(KSError::VerifyError("secp256k1 data should be 32 bytes digest".to_string()));
+    }
+    let mut digest = ShaWrapper::new();
+    digest.update(data);
+    match signature.len() {
+        65 => {
+            let signature = k256::ecdsa::recoverable::Signature::try_from(signature)
+                .map_err(|e| KSError::VerifyError(e.to_string()))?;
+            let recovered = signature
+                .recover_verifying_key_from_digest(digest)
+                .map_err(|_| KSError::SignatureMismatch)?;
+            match recovered == verifying_key {
+                true => Ok(()),
+                false => Err(KSError::SignatureMismatch),
+            }
+        }
+        64 => {
+            let signature = k256::ecdsa::Signature::try_from(signature)
+                .map_err(|e| KSError::VerifyError(e.to_string()))?;
+            verifying_key
+                .verify_digest(digest, &signature)
+                .map_err(|_| KSError::SignatureMismatch)
+        }
+        _ => Err(KSError::VerifyError("invalid secp256k1 signature length".to_string())),
+    }
+}
+
+fn verify_secp256r1(
+    public_key: &[u8],
+    data: &[u8],
+    signature: &[u8],
+    signing_option: Option<SigningOption>,
+) -> Result<(), KSError> {
+    let verifying_key = p256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+        .map_err(|e| KSError::VerifyError(e.to_string()))?;
+    let signature = match signing_option {
+        Some(SigningOption::Secp256R1 { encoding: SignatureEncoding::DER }) => {
+            p256::ecdsa::Signature::from_der(signature)
+        }
+        _ => p256::ecdsa::Signature::try_from(signature),
+    }
+    .map_err(|e| KSError::VerifyError(e.to_string()))?;
+    verifying_key
+        .verify(data, &signature)
+        .map_err(|_| KSError::SignatureMismatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::SecretKey;
+    use crate::keymaster::{HashAlgorithm, Secp256k1Encoding};
+
+    const SEED: &str = "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4";
+    const DIGEST: &str = "af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd";
+
+    fn assert_mismatch(result: Result<(), KSError>) {
+        assert!(matches!(result, Err(KSError::SignatureMismatch)));
+    }
+
+    #[test]
+    fn test_verify_secp256k1() {
+        let data = hex::decode(DIGEST).unwrap();
+        let key = k256::ecdsa::SigningKey::from_bytes(&[0x11u8; 32]).unwrap();
+        let public_key = key.verifying_key().to_bytes();
+        let signature = key.sign(data.clone(), None).unwrap();
+        assert!(verify(&public_key, &data, &signature, SigningAlgorithm::Secp256k1, None).is_ok());
+        // compact signature without the recovery id
+        assert!(verify(&public_key, &data, &signature[..64], SigningAlgorithm::Secp256k1, None).is_ok());
+
+        let other = k256::ecdsa::SigningKey::from_bytes(&[0x22u8; 32]).unwrap();
+        let other_public_key = other.verifying_key().to_bytes();
+        assert_mismatch(verify(&other_public_key, &data, &signature, SigningAlgorithm::Secp256k1, None));
+        assert_mismatch(verify(&other_public_key, &data, &signature[..64], SigningAlgorithm::Secp256k1, None));
+
+        let option = Some(SigningOption::Secp256k1 {
+            encoding: Secp256k1Encoding::Ethereum { chain_id: Some(1) },
+            hash: Some(HashAlgorithm::Keccak256),
+        });
+        let signature = key.sign(b"hello".to_vec(), option).unwrap();
+        assert!(verify(&public_key, b"hello", &signature, SigningAlgorithm::Secp256k1, option).is_ok());
+        assert_mismatch(verify(&other_public_key, b"hello", &signature, SigningAlgorithm::Secp256k1, option));
+    }
+
+    #[test]
+    fn test_verify_schnorr() {
+        let data = hex::decode(DIGEST).unwrap();
+        let schnorr = algorithm::schnorr::Schnorr::from_private_key(&[0x11u8; 32]).unwrap();
+        let public_key = k256::ecdsa::SigningKey::from_bytes(&[0x11u8; 32]).unwrap().verifying_key().to_bytes();
+        for merkle_root in [None, Some([0x22u8; 32])] {
+            let option = Some(SigningOption::Schnorr { tweak: Some(TaprootTweak { merkle_root }), aux_rand: None });
+            let signature = schnorr.sign(data.clone(), option).unwrap();
+            // the internal key is tweaked by the verifier, compressed or x-only
+            assert!(verify(&public_key, &data, &signature, SigningAlgorithm::Secp256k1, option).is_ok());
+            assert!(verify(&public_key[1..], &data, &signature, SigningAlgorithm::Secp256k1, option).is_ok());
+            let untweaked = Some(SigningOption::Schnorr { tweak: None, aux_rand: None });
+            assert_mismatch(verify(&public_key, &data, &signature, SigningAlgorithm::Secp256k1, untweaked));
+        }
+        let option = Some(SigningOption::Schnorr { tweak: None, aux_rand: None });
+        let signature = schnorr.sign(data.clone(), option).unwrap();
+        assert!(verify(&public_key, &data, &signature, SigningAlgorithm::Secp256k1, option).is_ok());
+    }
+
+    #[test]
+    fn test_verify_secp256r1() {
+        let data = hex::decode(DIGEST).unwrap();
+        let key = p256::ecdsa::SigningKey::from_bytes(&[0x11u8; 32]).unwrap();
+        let public_key = p256::ecdsa::VerifyingKey::from(&key).to_encoded_point(true);
+        for encoding in [SignatureEncoding::Raw, SignatureEncoding::DER] {
+            let option = Some(SigningOption::Secp256R1 { encoding });
+            let signature = key.sign(data.clone(), option).unwrap();
+            assert!(verify(public_key.as_bytes(), &data, &signature, SigningAlgorithm::Secp256R1, option).is_ok());
+            assert_mismatch(verify(public_key.as_bytes(), b"another message", &signature, SigningAlgorithm::Secp256R1, option));
+        }
+    }
+
+    #[test]
+    fn test_verify_ed25519() {
+        let seed = hex::decode(SEED).unwrap();
+        let path = "m/44'/501'/0'/0'".to_string();
+        let data = hex::decode(DIGEST).unwrap();
+        let signature = algorithm::slip10_ed25519::sign_message(&data, path.clone(), &seed).unwrap();
+        let public_key = algorithm::slip10_ed25519::get_public_key(path, &seed).unwrap();
+        assert!(verify(&public_key, &data, &signature, SigningAlgorithm::Ed25519, None).is_ok());
+        assert_mismatch(verify(&public_key, b"another message", &signature, SigningAlgorithm::Ed25519, None));
+    }
+
+    #[test]
+    fn test_verify_bip32_ed25519() {
+        let entropy = hex::decode("00000000000000000000000000000000").unwrap();
+        let root_key = algorithm::bip32_ed25519::get_icarus_master_key(&entropy, b"");
+        let path = "m/1852'/1815'/0'/0/0".to_string();
+        let data = hex::decode(DIGEST).unwrap();
+        let signature = algorithm::bip32_ed25519::sign_message(&data, path.clone(), root_key.as_ref()).unwrap();
+        let xpub = algorithm::bip32_ed25519::get_extended_public_key(path, root_key).unwrap();
+        let option = Some(SigningOption::ADA);
+        assert!(verify(xpub.as_ref(), &data, &signature, SigningAlgorithm::Ed25519, option).is_ok());
+        assert!(verify(&xpub.as_ref()[..32], &data, &signature, SigningAlgorithm::Ed25519, option).is_ok());
+        assert_mismatch(verify(xpub.as_ref(), b"another message", &signature, SigningAlgorithm::Ed25519, option));
+    }
+
+    #[test]
+    fn test_verify_sr25519() {
+        let entropy = hex::decode("00000000000000000000000000000000").unwrap();
+        let path = "//polkadot".to_string();
+        let data = hex::decode(DIGEST).unwrap();
+        let signature = algorithm::sr25519::sign_message(&data, path.clone(), &entropy).unwrap();
+        let public_key = algorithm::sr25519::get_public_key(path, &entropy).unwrap();
+        assert!(verify(&public_key, &data, &signature, SigningAlgorithm::SR25519, None).is_ok());
+        assert_mismatch(verify(&public_key, b"another message", &signature, SigningAlgorithm::SR25519, None));
+    }
+
+    #[test]
+    fn test_verify_rsa() {
+        let seed = hex::decode(SEED).unwrap();
+        let secret = algorithm::rsa::RSA::from_seed(&seed).unwrap();
+        let rsa = algorithm::rsa::RSA::from_secret(secret.expose()).unwrap();
+        let data = hex::decode(DIGEST).unwrap();
+        let option = Some(SigningOption::RSA { salt_len: 32 });
+        let signature = rsa.sign(data.clone(), option).unwrap();
+        let modulus = rsa.keypair_modulus();
+        assert!(verify(&modulus, &data, &signature, SigningAlgorithm::RSA, option).is_ok());
+        assert_mismatch(verify(&modulus, b"another message", &signature, SigningAlgorithm::RSA, option));
+        assert!(matches!(
+            verify(&modulus, &data, &signature, SigningAlgorithm::RSA, None),
+            Err(KSError::VerifyError(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_malformed_input() {
+        let data = hex::decode(DIGEST).unwrap();
+        let result = verify(&[0u8; 33], &data, &[0u8; 65], SigningAlgorithm::Secp256k1, None);
+        assert!(matches!(result, Err(KSError::VerifyError(_))));
+        let result = verify(&[0u8; 31], &data, &[0u8; 64], SigningAlgorithm::Ed25519, None);
+        assert!(matches!(result, Err(KSError::VerifyError(_))));
+    }
+}
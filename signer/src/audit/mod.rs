@@ -0,0 +1,302 @@
+pub mod storage;
+
+use crate::error::KSError;
+use crate::keymaster::SigningAlgorithm;
+use cryptoxide::hashing::sha256;
+use cryptoxide::hmac::Hmac;
+use cryptoxide::mac::{Mac, MacResult};
+use cryptoxide::sha2::Sha256;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use storage::AuditStorage;
+
+// bip85 hex child entropy index of the audit MAC key, "AUDI" in ascii
+pub(crate) const AUDIT_KEY_INDEX: u32 = 0x41554449;
+pub(crate) const AUDIT_KEY_LENGTH: u32 = 32;
+
+const MAC_LENGTH: usize = 32;
+
+#[derive(Clone)]
+pub struct AuditRecord {
+    pub sequence: u64,
+    // unix time in seconds
+    pub timestamp: u64,
+    pub mnemonic_id: u8,
+    pub algorithm: SigningAlgorithm,
+    pub path: String,
+    pub payload_hash: [u8; 32],
+    pub signature_hash: [u8; 32],
+    // hash of the previous record, zeros for the first one
+    pub previous_hash: [u8; 32],
+    pub mac: [u8; MAC_LENGTH],
+}
+
+fn algorithm_code(algo: SigningAlgorithm) -> u8 {
+    match algo {
+        SigningAlgorithm::Secp256k1 => 0,
+        SigningAlgorithm::Secp256R1 => 1,
+        SigningAlgorithm::Ed25519 => 2,
+        SigningAlgorithm::SR25519 => 3,
+        SigningAlgorithm::RSA => 4,
+    }
+}
+
+fn algorithm_from_code(code: u8) -> Option<SigningAlgorithm> {
+    match code {
+        0 => Some(SigningAlgorithm::Secp256k1),
+        1 => Some(SigningAlgorithm::Secp256R1),
+        2 => Some(SigningAlgorithm::Ed25519),
+        3 => Some(SigningAlgorithm::SR25519),
+        4 => Some(SigningAlgorithm::RSA),
+        _ => None,
+    }
+}
+
+impl AuditRecord {
+    // sequence || timestamp || mnemonic_id || algorithm || path length u16 || path || the three hashes, all big endian
+    fn content(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&self.sequence.to_be_bytes());
+        bytes.extend_from_slice(&self.timestamp.to_be_bytes());
+        bytes.push(self.mnemonic_id);
+        bytes.push(algorithm_code(self.algorithm));
+        bytes.extend_from_slice(&(self.path.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(self.path.as_bytes());
+        bytes.extend_from_slice(&self.payload_hash);
+        bytes.extend_from_slice(&self.signature_hash);
+        bytes.extend_from_slice(&self.previous_hash);
+        bytes
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.content();
+        bytes.extend_from_slice(&self.mac);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = bytes;
+        let mut take = |length: usize| {
+            if reader.len() < length {
+                return None;
+            }
+            let (value, rest) = reader.split_at(length);
+            reader = rest;
+            Some(value)
+        };
+        let sequence = u64::from_be_bytes(take(8)?.try_into().ok()?);
+        let timestamp = u64::from_be_bytes(take(8)?.try_into().ok()?);
+        let mnemonic_id = take(1)?[0];
+        let algorithm = algorithm_from_code(take(1)?[0])?;
+        let path_length = u16::from_be_bytes(take(2)?.try_into().ok()?) as usize;
+        let path = String::from_utf8(take(path_length)?.to_vec()).ok()?;
+        let payload_hash = take(32)?.try_into().ok()?;
+        let signature_hash = take(32)?.try_into().ok()?;
+        let previous_hash = take(32)?.try_into().ok()?;
+        let mac = take(MAC_LENGTH)?.try_into().ok()?;
+        if !reader.is_empty() {
+            return None;
+        }
+        Some(Self { sequence, timestamp, mnemonic_id, algorithm, path, payload_hash, signature_hash, previous_hash, mac })
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        sha256(&self.to_bytes())
+    }
+
+    fn compute_mac(&self, key: &[u8]) -> [u8; MAC_LENGTH] {
+        let mut hmac = Hmac::new(Sha256::new(), key);
+        hmac.input(&self.content());
+        let mut mac = [0u8; MAC_LENGTH];
+        hmac.raw_result(&mut mac);
+        mac
+    }
+}
+
+struct Head {
+    hash: [u8; 32],
+    count: u64,
+}
+
+// append only log of what was signed, every record is chained to the previous one by hash and
+// MACed with a key derived from the signing wallet
+pub struct AuditLog {
+    storage: Box<dyn AuditStorage>,
+    head: Mutex<Head>,
+}
+
+impl AuditLog {
+    // continues the records already in the storage
+    pub fn new(storage: Box<dyn AuditStorage>) -> Result<Self, KSError> {
+        let records = storage.load()?;
+        let hash = records.last().map(|record| sha256(record)).unwrap_or_default();
+        let head = Head { hash, count: records.len() as u64 };
+        Ok(Self { storage, head: Mutex::new(head) })
+    }
+
+    // continues from a head kept outside of the storage, so records cut off the end are detected
+    pub fn resume(storage: Box<dyn AuditStorage>, head: [u8; 32]) -> Result<Self, KSError> {
+        let log = Self::new(storage)?;
+        log.lock()?.hash = head;
+        Ok(log)
+    }
+
+    // hash of the last record, keep it somewhere else to detect truncation across restarts
+    pub fn head(&self) -> Result<[u8; 32], KSError> {
+        Ok(self.lock()?.hash)
+    }
+
+    pub fn records(&self) -> Result<Vec<AuditRecord>, KSError> {
+        self.storage
+            .load()?
+            .iter()
+            .enumerate()
+            .map(|(index, bytes)| AuditRecord::from_bytes(bytes).ok_or_else(|| tampered(index, "malformed")))
+            .collect()
+    }
+
+    pub(crate) fn append(
+        &self,
+        key: &[u8],
+        mnemonic_id: u8,
+        algorithm: SigningAlgorithm,
+        path: &str,
+        payload: &[u8],
+        signature: &[u8],
+    ) -> Result<(), KSError> {
+        if path.len() > u16::MAX as usize {
+            return Err(KSError::AuditLogError("derivation path is too long".to_string()));
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| KSError::AuditLogError(e.to_string()))?
+            .as_secs();
+        let mut head = self.lock()?;
+        let mut record = AuditRecord {
+            sequence: head.count,
+            timestamp,
+            mnemonic_id,
+            algorithm,
+            path: path.to_string(),
+            payload_hash: sha256(payload),
+            signature_hash: sha256(signature),
+            previous_hash: head.hash,
+            mac: [0u8; MAC_LENGTH],
+        };
+        record.mac = record.compute_mac(key);
+        self.storage.append(&record.to_bytes())?;
+        head.hash = record.hash();
+        head.count += 1;
+        Ok(())
+    }
+
+    // checks the whole chain and the MACs of the records of one wallet, returns how many of them were verified
+    pub(crate) fn verify(&self, key: &[u8], mnemonic_id: u8) -> Result<usize, KSError> {
+        let head = self.lock()?;
+        let mut previous_hash = [0u8; 32];
+        let mut verified = 0;
+        for (index, record) in self.records()?.iter().enumerate() {
+            if record.sequence != index as u64 {
+                return Err(tampered(index, "out of sequence"));
+            }
+            if record.previous_hash != previous_hash {
+                return Err(tampered(index, "not chained to the previous record"));
+            }
+            if record.mnemonic_id == mnemonic_id {
+                // MacResult compares in constant time
+                if MacResult::new(&record.mac) != MacResult::new(&record.compute_mac(key)) {
+                    return Err(tampered(index, "bad mac"));
+                }
+                verified += 1;
+            }
+            previous_hash = record.hash();
+        }
+        if previous_hash != head.hash {
+            return Err(KSError::AuditLogTampered("the log does not end at the expected head".to_string()));
+        }
+        Ok(verified)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Head>, KSError> {
+        self.head.lock().map_err(|e| KSError::AuditLogError(e.to_string()))
+    }
+}
+
+fn tampered(index: usize, reason: &str) -> KSError {
+    KSError::AuditLogTampered(format!("record {}: {}", index, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::storage::{FileAuditStorage, MemoryAuditStorage};
+    use super::*;
+    use std::fs;
+
+    const KEY: [u8; 32] = [0x01; 32];
+
+    fn append(log: &AuditLog, mnemonic_id: u8, path: &str) {
+        log.append(&KEY, mnemonic_id, SigningAlgorithm::Secp256k1, path, b"payload", b"signature").unwrap();
+    }
+
+    #[test]
+    fn test_record_encoding() {
+        let log = AuditLog::new(Box::new(MemoryAuditStorage::new())).unwrap();
+        append(&log, 0, "m/44'/60'/0'/0/0");
+        let record = log.records().unwrap().remove(0);
+        assert_eq!(record.sequence, 0);
+        assert_eq!(record.path, "m/44'/60'/0'/0/0");
+        assert_eq!(record.payload_hash, sha256(b"payload"));
+        assert_eq!(record.signature_hash, sha256(b"signature"));
+        assert_eq!(record.previous_hash, [0u8; 32]);
+        let decoded = AuditRecord::from_bytes(&record.to_bytes()).unwrap();
+        assert_eq!(decoded.to_bytes(), record.to_bytes());
+        assert!(AuditRecord::from_bytes(&record.to_bytes()[1..]).is_none());
+        assert_eq!(log.head().unwrap(), record.hash());
+    }
+
+    #[test]
+    fn test_verify_chain() {
+        let log = AuditLog::new(Box::new(MemoryAuditStorage::new())).unwrap();
+        assert_eq!(log.verify(&KEY, 0).unwrap(), 0);
+        append(&log, 0, "m/44'/60'/0'/0/0");
+        append(&log, 1, "m/44'/501'/0'/0'");
+        append(&log, 0, "m/44'/60'/0'/0/1");
+        assert_eq!(log.verify(&KEY, 0).unwrap(), 2);
+        assert_eq!(log.verify(&KEY, 1).unwrap(), 1);
+        assert!(matches!(log.verify(&[0x02; 32], 0), Err(KSError::AuditLogTampered(_))));
+    }
+
+    #[test]
+    fn test_detect_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let storage = || Box::new(FileAuditStorage::new(path.to_str().unwrap().to_string()));
+        let log = AuditLog::new(storage()).unwrap();
+        for index in 0..3 {
+            append(&log, 0, &format!("m/44'/60'/0'/0/{}", index));
+        }
+        let head = log.head().unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+
+        // the log is reopened from the file
+        assert_eq!(AuditLog::new(storage()).unwrap().verify(&KEY, 0).unwrap(), 3);
+
+        // edited path of the second record
+        let mut record = AuditRecord::from_bytes(&hex::decode(lines[1]).unwrap()).unwrap();
+        record.path = "m/44'/60'/0'/0/9".to_string();
+        let edited = [lines[0].to_string(), hex::encode(record.to_bytes()), lines[2].to_string()].join("\n");
+        fs::write(&path, edited).unwrap();
+        assert!(matches!(log.verify(&KEY, 0), Err(KSError::AuditLogTampered(_))));
+
+        // removed record in the middle
+        fs::write(&path, [lines[0], lines[2]].join("\n")).unwrap();
+        assert!(matches!(log.verify(&KEY, 0), Err(KSError::AuditLogTampered(_))));
+
+        // cut off last record, caught against the head in memory and the one kept after a restart
+        fs::write(&path, [lines[0], lines[1]].join("\n")).unwrap();
+        assert!(matches!(log.verify(&KEY, 0), Err(KSError::AuditLogTampered(_))));
+        let resumed = AuditLog::resume(storage(), head).unwrap();
+        assert!(matches!(resumed.verify(&KEY, 0), Err(KSError::AuditLogTampered(_))));
+    }
+}
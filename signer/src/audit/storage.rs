@@ -0,0 +1,72 @@
+use crate::error::KSError;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// where the encoded audit records are kept, records are only ever appended
+pub trait AuditStorage {
+    fn append(&self, record: &[u8]) -> Result<(), KSError>;
+
+    fn load(&self) -> Result<Vec<Vec<u8>>, KSError>;
+}
+
+#[derive(Default)]
+pub struct MemoryAuditStorage {
+    records: Mutex<Vec<Vec<u8>>>,
+}
+
+impl MemoryAuditStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AuditStorage for MemoryAuditStorage {
+    fn append(&self, record: &[u8]) -> Result<(), KSError> {
+        self.records
+            .lock()
+            .map_err(|e| KSError::AuditLogError(e.to_string()))?
+            .push(record.to_vec());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<Vec<u8>>, KSError> {
+        Ok(self.records.lock().map_err(|e| KSError::AuditLogError(e.to_string()))?.clone())
+    }
+}
+
+// one hex encoded record per line
+pub struct FileAuditStorage {
+    path: PathBuf,
+}
+
+impl FileAuditStorage {
+    pub fn new(path: String) -> Self {
+        Self { path: PathBuf::from(path) }
+    }
+}
+
+impl AuditStorage for FileAuditStorage {
+    fn append(&self, record: &[u8]) -> Result<(), KSError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| KSError::AuditLogError(e.to_string()))?;
+        writeln!(file, "{}", hex::encode(record))
+            .and_then(|_| file.sync_data())
+            .map_err(|e| KSError::AuditLogError(e.to_string()))
+    }
+
+    fn load(&self) -> Result<Vec<Vec<u8>>, KSError> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let content = fs::read_to_string(&self.path).map_err(|e| KSError::AuditLogError(e.to_string()))?;
+        content
+            .lines()
+            .map(|line| hex::decode(line).map_err(|e| KSError::AuditLogError(e.to_string())))
+            .collect()
+    }
+}
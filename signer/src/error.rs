@@ -2,6 +2,8 @@ use std::array::TryFromSliceError;
 use std::convert::Infallible;
 use thiserror::Error;
 
+use crate::keymaster::se::status::SeStatus;
+
 #[derive(Debug, Error)]
 pub enum KSError {
     #[error("SerialManagerError:{0}")]
@@ -39,4 +41,61 @@ pub enum KSError {
 
     #[error("GetPublicKeyError: {0}")]
     GetPublicKeyError(String),
+
+    #[error("SignatureMismatch")]
+    SignatureMismatch,
+
+    #[error("VerifyError: {0}")]
+    VerifyError(String),
+
+    #[error("KeyStoreError: {0}")]
+    KeyStoreError(String),
+
+    #[error("SecureChannelError: {0}")]
+    SecureChannelError(String),
+
+    #[error("WrongPassword: {} attempts left", .remaining_attempts.map_or("unknown".to_string(), |n| n.to_string()))]
+    WrongPassword { remaining_attempts: Option<u8> },
+
+    #[error("WalletLocked")]
+    WalletLocked,
+
+    #[error("WalletNotFound")]
+    WalletNotFound,
+
+    #[error("SecretNotSet")]
+    SecretNotSet,
+
+    #[error("InvalidAuthToken")]
+    InvalidAuthToken,
+
+    #[error("UnsupportedCommand: {0:#06x}")]
+    UnsupportedCommand(u16),
+
+    #[error("ChipBusy")]
+    ChipBusy,
+
+    #[error("SEStatusError: {0:?}")]
+    SEStatusError(SeStatus),
+
+    #[error("MnemonicError: {0}")]
+    MnemonicError(String),
+
+    #[error("Bip85Error: {0}")]
+    Bip85Error(String),
+
+    #[error("PolicyViolation: {0}")]
+    PolicyViolation(String),
+
+    #[error("InvalidPolicy: {0}")]
+    InvalidPolicy(String),
+
+    #[error("AuditLogError: {0}")]
+    AuditLogError(String),
+
+    #[error("AuditLogTampered: {0}")]
+    AuditLogTampered(String),
+
+    #[error("AttestationError: {0}")]
+    AttestationError(String),
 }
\ No newline at end of file
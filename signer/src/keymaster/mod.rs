@@ -1,17 +1,32 @@
 use crate::algorithm;
 use crate::error::KSError;
+use crate::secret::{SecretBytes, SecretPassword};
 use openssl::sign::RsaPssSaltlen;
+use zeroize::Zeroizing;
 
 pub(crate) mod hash_wraper;
-pub(crate) mod local;
 pub(crate) mod se;
+pub(crate) mod software;
 
+// the number is the word count of the mnemonic the entropy is generated for
 pub enum EntropyLength {
     Short(u32),
     Long(u32),
 }
 
-#[derive(Clone, Copy)]
+impl EntropyLength {
+    pub fn byte_length(&self) -> Result<usize, KSError> {
+        let (EntropyLength::Short(words) | EntropyLength::Long(words)) = self;
+        match words {
+            12 => Ok(16),
+            18 => Ok(24),
+            24 => Ok(32),
+            _ => Err(KSError::MnemonicError(format!("{} words mnemonic is not supported", words))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SigningAlgorithm {
     Secp256k1,
     Secp256R1,
@@ -20,39 +35,160 @@ pub enum SigningAlgorithm {
     RSA,
 }
 
+#[derive(Clone, Copy)]
+pub enum SignatureEncoding {
+    // r || s, 64 bytes
+    Raw,
+    DER,
+}
+
+#[derive(Clone, Copy)]
+pub enum PublicKeyFormat {
+    // sec1 33 bytes, secp256k1 and secp256r1
+    Compressed,
+    // sec1 65 bytes, secp256k1 and secp256r1
+    Uncompressed,
+    // 32 bytes, ed25519 and sr25519
+    Raw,
+    // base58 encoded BIP32 extended public key, carrying depth, parent fingerprint and chain code
+    ExtendedPublicKey,
+}
+
+#[derive(Clone, Copy)]
+pub enum Secp256k1Encoding {
+    // r || s || v, v is 27 + recovery id, or recovery id + chain_id * 2 + 35 with EIP-155, as minimal big endian bytes
+    Ethereum { chain_id: Option<u64> },
+    // DER signature followed by the sighash type byte
+    Bitcoin { sighash_type: u8 },
+    // r || s, 64 bytes
+    Compact,
+    // r || s || recovery id, 65 bytes
+    Recoverable,
+}
+
+// digest of the message taken before signing, see algorithm::digest for the registry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Keccak256,
+    Sha256,
+    // sha256 applied twice, as bitcoin does
+    Sha256d,
+    // sui and cardano
+    Blake2b256,
+    // aptos
+    Sha3_256,
+    // the first 32 bytes of sha512, as xrp does
+    Sha512Half,
+}
+
+// BIP341 output key tweak, merkle_root is None for key path only outputs
+#[derive(Clone, Copy)]
+pub struct TaprootTweak {
+    pub merkle_root: Option<[u8; 32]>,
+}
+
 #[derive(Clone, Copy)]
 pub enum SigningOption {
     RSA { salt_len: i32 },
-    ADA
+    ADA,
+    Secp256R1 { encoding: SignatureEncoding },
+    Schnorr { tweak: Option<TaprootTweak>, aux_rand: Option<[u8; 32]> },
+    // hash None signs the data as an already computed 32 bytes digest, the signature is always low-S
+    Secp256k1 { encoding: Secp256k1Encoding, hash: Option<HashAlgorithm> },
 }
 
+// one entry of a batch signed with a single authentication
+pub struct SignRequest {
+    pub data: Vec<u8>,
+    pub algo: SigningAlgorithm,
+    pub path: String,
+    pub option: Option<SigningOption>,
+}
+
+// one result per request of a batch
+pub type BatchSignatures = Vec<Result<Vec<u8>, KSError>>;
+
 pub trait KeyMaster {
-    fn generate_entropy(&self, length: EntropyLength) -> Result<Vec<u8>, KSError>;
+    fn generate_entropy(&self, length: EntropyLength) -> Result<SecretBytes, KSError>;
 
-    fn setup_ada_root_key(&self, mnemonic_id: u8, password: String, passphrase: String) -> Result<bool, KSError>;
+    // xpub export and ADA signing use the stored root key, so they follow the scheme chosen here
+    fn setup_ada_root_key(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        passphrase: &SecretPassword,
+        scheme: algorithm::bip32_ed25519::AdaMasterKeyScheme,
+    ) -> Result<bool, KSError>;
 
-    fn get_ada_extended_public_key(&self, mnemonic_id: u8, password: String, path: String) -> Result<String, KSError>;
+    fn get_ada_extended_public_key(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        path: String,
+    ) -> Result<String, KSError>;
 
-    fn get_rsa_public_key(&self, mnemonic_id: u8, password: String) -> Result<Vec<u8>, KSError>;
+    fn get_rsa_public_key(&self, mnemonic_id: u8, password: &SecretPassword) -> Result<Vec<u8>, KSError>;
 
-    fn get_ada_root_key(&self, mnemonic_id: u8, password: Vec<u8>) -> Result<Vec<u8>, KSError>;
+    fn get_ada_root_key(&self, mnemonic_id: u8, password: &SecretPassword) -> Result<SecretBytes, KSError>;
 
     fn set_ada_root_key(
         &self,
         mnemonic_id: u8,
-        password: String,
-        secret: Vec<u8>,
+        password: &SecretPassword,
+        secret: SecretBytes,
     ) -> Result<bool, KSError>;
 
     fn sign_data(
         &self,
         mnemonic_id: u8,
-        password: String,
+        password: &SecretPassword,
         data: Vec<u8>,
         algo: SigningAlgorithm,
         derivation_path: String,
         signing_option: Option<SigningOption>,
     ) -> Result<Vec<u8>, KSError>;
 
+    // fails as a whole only when the password is rejected, otherwise every request gets its own result
+    fn sign_batch(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        requests: Vec<SignRequest>,
+    ) -> Result<Vec<Result<Vec<u8>, KSError>>, KSError>;
+
+    // the bip85 child is derived under the same authentication as the batch
+    fn sign_batch_with_bip85(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        requests: Vec<SignRequest>,
+        application: algorithm::bip85::Bip85Application,
+    ) -> Result<(BatchSignatures, Zeroizing<String>), KSError>;
+
+    fn get_public_key(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        algo: SigningAlgorithm,
+        derivation_path: String,
+        format: PublicKeyFormat,
+    ) -> Result<Vec<u8>, KSError>;
+
+    fn get_master_fingerprint(&self, mnemonic_id: u8, password: &SecretPassword) -> Result<[u8; 4], KSError>;
+
+    // BIP85 child mnemonic, key or password derived from the master seed
+    fn derive_bip85(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        application: algorithm::bip85::Bip85Application,
+    ) -> Result<Zeroizing<String>, KSError>;
+
     fn get_version(&self) -> Result<Vec<u8>, KSError>;
+
+    // parsed firmware version, serial number and the commands the firmware handles
+    fn get_device_info(&self) -> Result<se::device::DeviceInfo, KSError>;
+
+    // the device key signs the challenge, check the result with DeviceAttestation::verify
+    fn attest(&self, challenge: Vec<u8>) -> Result<se::device::DeviceAttestation, KSError>;
 }
@@ -1,22 +1,24 @@
-use bytes::{Buf, BufMut, Bytes, BytesMut};
 use indexmap::IndexMap;
+use zeroize::Zeroizing;
 
-use super::tags::{methods, COMMAND_TAG, RESPONSE_TAG};
+use super::tags::{methods, COMMAND_TAG};
 use super::tvl::{Packet, TVL};
+use crate::secret::SecretBytes;
 
 #[derive(Default)]
 pub struct CommandParams {
     pub wallet_id: Option<u8>,
     pub path: Option<String>,
-    pub auth_token: Option<Vec<u8>>,
-    pub password: Option<Vec<u8>>,
+    pub auth_token: Option<SecretBytes>,
+    pub password: Option<SecretBytes>,
     pub curve: Option<u8>,
     pub hash: Option<[u8; 128]>,
     pub is_master_seed: Option<bool>,
     pub is_rsa_secret: Option<bool>,
     pub is_entropy: Option<bool>,
     pub is_ada_root: Option<bool>,
-    pub secret: Option<Vec<u8>>,
+    pub secret: Option<SecretBytes>,
+    pub challenge: Option<Vec<u8>>,
 }
 
 pub trait CommandBuilder {
@@ -83,7 +85,7 @@ impl CommandBuilder for GETKeyCommand {
             _ => (),
         };
         match params.auth_token {
-            Some(auth_token) => builder.add_payload(methods::AUTH_TOKEN_TAG, &auth_token),
+            Some(auth_token) => builder.add_payload(methods::AUTH_TOKEN_TAG, auth_token.expose()),
             None => (),
         };
 
@@ -118,7 +120,7 @@ impl CommandBuilder for SignTxCommand {
         builder.add_payload(methods::CURVE_TAG, &[00]);
         builder.add_payload(methods::WALLET_FLAG_TAG, &[00]);
 
-        builder.add_payload(methods::AUTH_TOKEN_TAG, &auth_token);
+        builder.add_payload(methods::AUTH_TOKEN_TAG, auth_token.expose());
         builder.add_payload(methods::TX_HASH_TAG, &tx_hash);
         let packet = builder.build();
         return Some(Command {
@@ -134,7 +136,7 @@ impl CommandBuilder for GenerateTokenCommand {
     fn build(params: Option<CommandParams>) -> Option<Command> {
         let params = params?;
         let password = params.password?;
-        let password_slices = password.as_slice();
+        let password_slices = password.expose();
         let mut builder = PacketBuilder::new();
         builder.add_command_id(methods::VERIFY_USER_PASSWORD);
         builder.add_payload(methods::CURRENT_PASSWORD, password_slices);
@@ -167,9 +169,9 @@ impl CommandBuilder for SetSecretCommand {
     fn build(params: Option<CommandParams>) -> Option<Command> {
         let params = params?;
         let password = params.password?;
-        let password_slices = password.as_slice();
+        let password_slices = password.expose();
         let secret = params.secret?;
-        let secret_slices = secret.as_slice();
+        let secret_slices = secret.expose();
         let mut builder = PacketBuilder::new();
         builder.add_command_id(methods::SET_SECRET_TAG);
         builder.add_payload(methods::CURRENT_PASSWORD, password_slices);
@@ -192,17 +194,30 @@ impl CommandBuilder for SetSecretCommand {
     }
 }
 
+pub struct AttestCommand;
+
+impl CommandBuilder for AttestCommand {
+    fn build(params: Option<CommandParams>) -> Option<Command> {
+        let challenge = params?.challenge?;
+        let mut builder = PacketBuilder::new();
+        builder.add_command_id(methods::ATTEST_TAG);
+        builder.add_payload(methods::CHALLENGE_TAG, &challenge);
+        let packet = builder.build();
+        Some(Command {
+            packet,
+            tag: methods::ATTEST_TAG,
+        })
+    }
+}
+
 fn build_packet(tag: u16) -> Packet {
-    let mut mm = BytesMut::new();
-    mm.put_u16(tag);
-    let bytes = mm.freeze();
-    let tvl = TVL::new(COMMAND_TAG, 2, bytes);
+    let tvl = TVL::new(COMMAND_TAG, 2, tag.to_be_bytes().to_vec());
     let mut payloads: IndexMap<u16, TVL> = IndexMap::new();
     payloads.insert(1, tvl);
     Packet::new(payloads)
 }
 
-struct PacketBuilder {
+pub(crate) struct PacketBuilder {
     payloads: IndexMap<u16, TVL>,
 }
 
@@ -214,39 +229,23 @@ impl PacketBuilder {
     }
 
     pub fn add_command_id(&mut self, tag: u16) {
-        let mut mm = BytesMut::new();
-        mm.put_u16(tag);
-        let bytes = mm.freeze();
-        let tvl = TVL::new(COMMAND_TAG, 2, bytes);
+        let tvl = TVL::new(COMMAND_TAG, 2, tag.to_be_bytes().to_vec());
         self.payloads.insert(COMMAND_TAG, tvl);
     }
 
     pub fn add_payload(&mut self, tag: u16, value: &[u8]) {
-        let mm = Bytes::copy_from_slice(value);
         let length = value.len() as u16;
-        let tvl = TVL::new(tag, length, mm);
+        let tvl = TVL::new(tag, length, value.to_vec());
         self.payloads.insert(tag, tvl);
     }
 
     pub fn build(self) -> Packet {
         Packet::new(self.payloads)
     }
-}
 
-pub fn parse_result(packet: &Packet, request_tag: u16) -> bool {
-    if let Some(tag) = packet.payloads.get(&COMMAND_TAG) {
-        let mut tmp = Bytes::copy_from_slice(tag.value.chunk());
-        if tmp.get_uint(tmp.len()) == request_tag.into() {
-            if let Some(v) = packet.payloads.get(&RESPONSE_TAG) {
-                let mut tmp = Bytes::copy_from_slice(v.value.chunk());
-                if tmp.get_uint(tmp.len()) == 0 {
-                    return true;
-                }
-            }
-        }
+    pub fn build_with_encryption_flag(self, encryption_flag: u8) -> Packet {
+        Packet::new_with_encryption_flag(self.payloads, encryption_flag)
     }
-
-    return false;
 }
 
 pub struct Command {
@@ -255,7 +254,7 @@ pub struct Command {
 }
 
 impl Command {
-    pub fn to_vec(&self) -> Vec<u8> {
+    pub fn to_vec(&self) -> Zeroizing<Vec<u8>> {
         self.packet.to_vec()
     }
 }
@@ -268,7 +267,7 @@ mod tests {
     fn it_should_turn_right_get_firmware_command() {
         let command = GetFirmwareStatusCommand::build(None).unwrap();
         assert_eq!(
-            command.to_vec(),
+            *command.to_vec(),
             vec![02, 00, 00, 06, 00, 01, 00, 02, 01, 02, 03, 07]
         )
     }
@@ -277,7 +276,7 @@ mod tests {
     fn it_should_turn_right_get_entropy_command() {
         let command = GenerateEntropyCommand::build(None).unwrap();
         assert_eq!(
-            command.to_vec(),
+            *command.to_vec(),
             vec![2, 0, 0, 17, 0, 1, 0, 2, 3, 1, 2, 1, 0, 2, 1, 0, 3, 11, 0, 1, 0, 3, 24]
         )
     }
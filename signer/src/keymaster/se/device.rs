@@ -0,0 +1,240 @@
+use std::fmt;
+use std::str::FromStr;
+
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::sign::Verifier;
+use openssl::stack::Stack;
+use openssl::x509::store::X509StoreBuilder;
+use openssl::x509::{X509StoreContext, X509};
+
+use super::tags::{methods, result};
+use super::tvl::Packet;
+use crate::error::KSError;
+
+// prefixed to the host challenge before the SE signs it, so the device key signs nothing else by accident
+pub(crate) const ATTESTATION_CONTEXT: &[u8] = b"rcc se attestation";
+pub(crate) const MIN_CHALLENGE_LENGTH: usize = 16;
+
+// firmware that does not report its command set understands these
+const LEGACY_COMMANDS: [u16; 7] = [
+    methods::GET_FIRMWARE_STATUS_TAG,
+    methods::GET_RANDOM_ENTROPY_TAG,
+    methods::GET_KEY_TAG,
+    methods::SET_SECRET_TAG,
+    methods::VERIFY_USER_PASSWORD,
+    methods::CLEAR_TOKEN_TAG,
+    methods::SIGN_TAG,
+];
+
+// major.minor.patch, the chip appends a build number as a fourth field, e.g. 1.2.0.000000
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FirmwareVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub build: Option<String>,
+}
+
+impl FromStr for FirmwareVersion {
+    type Err = KSError;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        let invalid = || KSError::SEError(format!("invalid firmware version {}", version));
+        let mut fields = version.trim_end_matches('\0').splitn(4, '.');
+        let mut number = || fields.next().and_then(|field| field.parse::<u32>().ok()).ok_or_else(invalid);
+        let (major, minor, patch) = (number()?, number()?, number()?);
+        let build = fields.next().map(str::to_string);
+        if build.as_deref() == Some("") {
+            return Err(invalid());
+        }
+        Ok(Self { major, minor, patch, build })
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        match &self.build {
+            Some(build) => write!(f, ".{}", build),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub version: FirmwareVersion,
+    // None for firmware that does not report it
+    pub serial_number: Option<String>,
+    // command ids the firmware handles
+    pub commands: Vec<u16>,
+}
+
+impl DeviceInfo {
+    // the answer to the firmware status command
+    pub(crate) fn from_packet(packet: &Packet) -> Result<Self, KSError> {
+        let field = |tag| packet.payloads.get(&tag).map(|tvl| tvl.value.as_ref());
+        let version = field(result::FIRMWARE_APP_VERSION)
+            .ok_or(KSError::SEError("required field is missing".to_string()))?;
+        let version = std::str::from_utf8(version)
+            .map_err(|_| KSError::SEError("invalid firmware version".to_string()))?
+            .parse()?;
+        let serial_number = field(result::SERIAL_NUMBER)
+            .map(|serial| String::from_utf8(serial.to_vec()))
+            .transpose()
+            .map_err(|_| KSError::SEError("invalid serial number".to_string()))?;
+        let commands = match field(result::SUPPORTED_COMMANDS) {
+            Some(commands) if commands.len() % 2 == 0 => commands
+                .chunks(2)
+                .map(|command| u16::from_be_bytes([command[0], command[1]]))
+                .collect(),
+            Some(_) => return Err(KSError::SEError("invalid command set".to_string())),
+            None => LEGACY_COMMANDS.to_vec(),
+        };
+        Ok(Self { version, serial_number, commands })
+    }
+
+    pub fn supports(&self, command: u16) -> bool {
+        self.commands.contains(&command)
+    }
+}
+
+// a host challenge signed by the SE device key, with the DER certificate chain of that key, device certificate first
+#[derive(Clone, Debug)]
+pub struct DeviceAttestation {
+    // as reported by the device, verify checks the serial number the host expects instead
+    pub serial_number: Option<String>,
+    pub challenge: Vec<u8>,
+    // DER encoded ecdsa signature over sha256 of ATTESTATION_CONTEXT || challenge
+    pub signature: Vec<u8>,
+    pub certificate_chain: Vec<Vec<u8>>,
+}
+
+impl DeviceAttestation {
+    // checks the chain up to the pinned DER vendor root, the device certificate against the serial number the host
+    // expects, and the signature over the challenge the host sent
+    pub fn verify(&self, challenge: &[u8], serial_number: &str, vendor_root: &[u8]) -> Result<(), KSError> {
+        let error = |e: openssl::error::ErrorStack| KSError::AttestationError(e.to_string());
+        if self.challenge != challenge {
+            return Err(KSError::AttestationError("challenge mismatch".to_string()));
+        }
+        let (device, intermediates) = self
+            .certificate_chain
+            .split_first()
+            .ok_or(KSError::AttestationError("certificate chain is empty".to_string()))?;
+        let device = X509::from_der(device).map_err(error)?;
+        let mut chain = Stack::new().map_err(error)?;
+        for certificate in intermediates {
+            chain.push(X509::from_der(certificate).map_err(error)?).map_err(error)?;
+        }
+        let mut store = X509StoreBuilder::new().map_err(error)?;
+        store.add_cert(X509::from_der(vendor_root).map_err(error)?).map_err(error)?;
+        let store = store.build();
+        let mut context = X509StoreContext::new().map_err(error)?;
+        let trusted = context
+            .init(&store, &device, &chain, |context| context.verify_cert())
+            .map_err(error)?;
+        if !trusted {
+            return Err(KSError::AttestationError(format!(
+                "certificate chain is not trusted: {}",
+                context.error()
+            )));
+        }
+
+        let common_name = device
+            .subject_name()
+            .entries_by_nid(Nid::COMMONNAME)
+            .next()
+            .and_then(|entry| entry.data().as_utf8().ok())
+            .map(|name| name.to_string());
+        if common_name.as_deref() != Some(serial_number) {
+            return Err(KSError::AttestationError("certificate does not belong to the device".to_string()));
+        }
+
+        let public_key = device.public_key().map_err(error)?;
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &public_key).map_err(error)?;
+        verifier.update(ATTESTATION_CONTEXT).map_err(error)?;
+        verifier.update(&self.challenge).map_err(error)?;
+        match verifier.verify(&self.signature) {
+            Ok(true) => Ok(()),
+            _ => Err(KSError::AttestationError("signature mismatch".to_string())),
+        }
+    }
+}
+
+// u16 big endian length before every certificate
+#[cfg(any(test, feature = "simulator"))]
+pub(crate) fn encode_certificate_chain(chain: &[Vec<u8>]) -> Vec<u8> {
+    chain
+        .iter()
+        .flat_map(|certificate| [(certificate.len() as u16).to_be_bytes().to_vec(), certificate.clone()])
+        .flatten()
+        .collect()
+}
+
+pub(crate) fn decode_certificate_chain(mut data: &[u8]) -> Result<Vec<Vec<u8>>, KSError> {
+    let mut chain = vec![];
+    while !data.is_empty() {
+        if data.len() < 2 {
+            return Err(KSError::SEError("invalid certificate chain".to_string()));
+        }
+        let length = u16::from_be_bytes([data[0], data[1]]) as usize;
+        let certificate = data
+            .get(2..2 + length)
+            .ok_or(KSError::SEError("invalid certificate chain".to_string()))?;
+        chain.push(certificate.to_vec());
+        data = &data[2 + length..];
+    }
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymaster::se::command::PacketBuilder;
+
+    #[test]
+    fn it_should_parse_firmware_version() {
+        let version: FirmwareVersion = "1.2.0.000000".parse().unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (1, 2, 0));
+        assert_eq!(version.build.as_deref(), Some("000000"));
+        assert_eq!(version.to_string(), "1.2.0.000000");
+        assert_eq!("2.0.1".parse::<FirmwareVersion>().unwrap().to_string(), "2.0.1");
+        assert!("1.10.0".parse::<FirmwareVersion>().unwrap() > "1.9.3".parse().unwrap());
+        for invalid in ["", "1.2", "1.x.0", "1.2.0."] {
+            assert!(invalid.parse::<FirmwareVersion>().is_err());
+        }
+    }
+
+    #[test]
+    fn it_should_read_device_info() {
+        let mut builder = PacketBuilder::new();
+        builder.add_payload(result::FIRMWARE_APP_VERSION, b"1.2.0.000000");
+        let legacy = DeviceInfo::from_packet(&builder.build()).unwrap();
+        assert_eq!(legacy.serial_number, None);
+        assert!(legacy.supports(methods::GET_KEY_TAG));
+        assert!(!legacy.supports(methods::ATTEST_TAG));
+
+        let mut builder = PacketBuilder::new();
+        builder.add_payload(result::FIRMWARE_APP_VERSION, b"1.3.0");
+        builder.add_payload(result::SERIAL_NUMBER, b"KS0001");
+        builder.add_payload(result::SUPPORTED_COMMANDS, &[0x01, 0x02, 0x01, 0x03]);
+        let info = DeviceInfo::from_packet(&builder.build()).unwrap();
+        assert_eq!(info.serial_number.as_deref(), Some("KS0001"));
+        assert_eq!(info.commands, vec![methods::GET_FIRMWARE_STATUS_TAG, methods::ATTEST_TAG]);
+
+        let mut builder = PacketBuilder::new();
+        builder.add_payload(result::FIRMWARE_APP_VERSION, b"1.3.0");
+        builder.add_payload(result::SUPPORTED_COMMANDS, &[0x01, 0x02, 0x01]);
+        assert!(DeviceInfo::from_packet(&builder.build()).is_err());
+    }
+
+    #[test]
+    fn it_should_encode_certificate_chain() {
+        let chain = vec![vec![0x30; 300], vec![], vec![0x01]];
+        assert_eq!(decode_certificate_chain(&encode_certificate_chain(&chain)).unwrap(), chain);
+        assert!(decode_certificate_chain(&[0x00, 0x02, 0x01]).is_err());
+        assert!(decode_certificate_chain(&[0x00]).is_err());
+    }
+}
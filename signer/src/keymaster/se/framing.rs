@@ -0,0 +1,298 @@
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind, Read, Write};
+
+use super::command::PacketBuilder;
+use super::tags::{encryption, methods, COMMAND_TAG, FRAGMENT_TAG};
+use super::tvl::{lrc, Packet};
+use crate::error::KSError;
+
+const STX: u8 = 0x02;
+const ETX: u8 = 0x03;
+// stx, encryption flag and the u16 payload length
+const FRAME_HEADER_LENGTH: usize = 4;
+// etx and lrc
+const FRAME_TRAILER_LENGTH: usize = 2;
+// tvl tag and length, then the u16 fragment index and count
+const FRAGMENT_HEADER_LENGTH: usize = 8;
+// messages above this size are split in fragment frames of at most this size
+const MAX_FRAME_LENGTH: usize = 1024;
+const MAX_FRAGMENT_DATA_LENGTH: usize =
+    MAX_FRAME_LENGTH - FRAME_HEADER_LENGTH - FRAME_TRAILER_LENGTH - FRAGMENT_HEADER_LENGTH;
+
+// reads one complete frame, bytes before the stx are skipped and a broken etx or lrc is reported as InvalidData
+pub(crate) fn read_frame<R: Read>(reader: &mut R) -> Result<Vec<u8>, Error> {
+    let mut stx = [0u8; 1];
+    while stx[0] != STX {
+        reader.read_exact(&mut stx)?;
+    }
+    let mut frame = vec![STX; FRAME_HEADER_LENGTH];
+    reader.read_exact(&mut frame[1..])?;
+    let length = u16::from_be_bytes([frame[2], frame[3]]) as usize;
+    frame.resize(FRAME_HEADER_LENGTH + length + FRAME_TRAILER_LENGTH, 0);
+    reader.read_exact(&mut frame[FRAME_HEADER_LENGTH..])?;
+    if frame[frame.len() - 2] != ETX {
+        return Err(Error::new(ErrorKind::InvalidData, "etx is not 3"));
+    }
+    if lrc(&frame) != 0 {
+        return Err(Error::new(ErrorKind::InvalidData, "lrc is not matched"));
+    }
+    Ok(frame)
+}
+
+// reads one message, reassembling it when it arrives in fragment frames
+pub(crate) fn read_message<R: Read>(reader: &mut R) -> Result<Vec<u8>, Error> {
+    let mut message = vec![];
+    let mut next_index = 0u16;
+    loop {
+        let frame = read_frame(reader)?;
+        let fragment = match parse_fragment(&frame)? {
+            Some(fragment) => fragment,
+            None if next_index == 0 => return Ok(frame),
+            None => return Err(Error::new(ErrorKind::InvalidData, "fragment is missing")),
+        };
+        if fragment.index != next_index || fragment.index >= fragment.count {
+            return Err(Error::new(ErrorKind::InvalidData, "fragment is out of order"));
+        }
+        message.extend_from_slice(&fragment.data);
+        next_index += 1;
+        if next_index == fragment.count {
+            return Ok(message);
+        }
+    }
+}
+
+// writes the message as is when it fits in one frame, otherwise as numbered fragment frames
+pub(crate) fn write_message<W: Write>(writer: &mut W, message: &[u8]) -> Result<(), Error> {
+    if message.len() <= MAX_FRAME_LENGTH {
+        writer.write_all(message)?;
+        return writer.flush();
+    }
+    let chunks = message.chunks(MAX_FRAGMENT_DATA_LENGTH);
+    let count = u16::try_from(chunks.len())
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "message is too large"))?;
+    for (index, chunk) in (0..count).zip(chunks) {
+        let mut builder = PacketBuilder::new();
+        builder.add_payload(
+            FRAGMENT_TAG,
+            &[&index.to_be_bytes()[..], &count.to_be_bytes(), chunk].concat(),
+        );
+        writer.write_all(&builder.build().to_vec())?;
+    }
+    writer.flush()
+}
+
+// sends the request and reads the response, a response failing the etx or lrc check is requested again
+// when the command does not change the SE state, otherwise the SE would execute it twice
+pub(crate) fn exchange<S: Read + Write>(
+    stream: &mut S,
+    request: &[u8],
+    retries: u32,
+    mut before_retry: impl FnMut(&mut S),
+) -> Result<Vec<u8>, Error> {
+    let retries = if is_read_only(request) { retries } else { 0 };
+    let mut attempt = 0;
+    loop {
+        write_message(stream, request)?;
+        match read_message(stream) {
+            Err(e) if e.kind() == ErrorKind::InvalidData && attempt < retries => {
+                attempt += 1;
+                before_retry(stream);
+            }
+            result => return result,
+        }
+    }
+}
+
+// encrypted and handshake packets are never sent twice, the channel rejects replays
+fn is_read_only(request: &[u8]) -> bool {
+    let packet = match Packet::try_from(request.to_vec()) {
+        Ok(packet) if packet.encryption_flag == encryption::PLAIN => packet,
+        _ => return false,
+    };
+    let command = packet
+        .payloads
+        .get(&COMMAND_TAG)
+        .filter(|tvl| tvl.value.len() == 2)
+        .map(|tvl| u16::from_be_bytes([tvl.value[0], tvl.value[1]]));
+    matches!(
+        command,
+        Some(methods::GET_FIRMWARE_STATUS_TAG | methods::GET_KEY_TAG | methods::SIGN_TAG | methods::ATTEST_TAG)
+    )
+}
+
+pub(crate) fn map_io_error(error: Error) -> KSError {
+    match error.kind() {
+        ErrorKind::TimedOut | ErrorKind::WouldBlock => KSError::SerialTimeout,
+        _ => KSError::SerialManagerError(error.to_string()),
+    }
+}
+
+struct Fragment {
+    index: u16,
+    count: u16,
+    data: Vec<u8>,
+}
+
+// None when the frame carries a whole message
+fn parse_fragment(frame: &[u8]) -> Result<Option<Fragment>, Error> {
+    let packet = Packet::try_from(frame.to_vec())
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+    let value = match packet.payloads.get(&FRAGMENT_TAG) {
+        Some(tvl) if packet.payloads.len() == 1 => &tvl.value,
+        _ => return Ok(None),
+    };
+    if value.len() < 4 {
+        return Err(Error::new(ErrorKind::InvalidData, "fragment header is missing"));
+    }
+    Ok(Some(Fragment {
+        index: u16::from_be_bytes([value[0], value[1]]),
+        count: u16::from_be_bytes([value[2], value[3]]),
+        data: value[4..].to_vec(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymaster::se::command::{ClearTokenCommand, CommandBuilder};
+    use std::collections::VecDeque;
+    use std::io::Cursor;
+
+    const VERSION_REQUEST: [u8; 12] = [0x02, 0x00, 0x00, 0x06, 0x00, 0x01, 0x00, 0x02, 0x01, 0x02, 0x03, 0x07];
+
+    fn large_message(length: usize) -> Vec<u8> {
+        let mut builder = PacketBuilder::new();
+        builder.add_payload(0x0003, &(0..length).map(|i| i as u8).collect::<Vec<u8>>());
+        builder.build().to_vec().to_vec()
+    }
+
+    // hands out the data one byte per read, like a slow uart
+    struct Trickle(Cursor<Vec<u8>>);
+
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+            let length = buf.len().min(1);
+            self.0.read(&mut buf[..length])
+        }
+    }
+
+    // answers every write with the next queued response
+    struct Device {
+        responses: VecDeque<Vec<u8>>,
+        pending: Cursor<Vec<u8>>,
+        requests: usize,
+    }
+
+    impl Read for Device {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+            match self.pending.read(buf)? {
+                0 => Err(Error::new(ErrorKind::TimedOut, "no response")),
+                length => Ok(length),
+            }
+        }
+    }
+
+    impl Write for Device {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+            self.requests += 1;
+            self.pending = Cursor::new(self.responses.pop_front().unwrap_or_default());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn it_should_read_frame_across_reads() {
+        let mut data = vec![0xff, 0x00];
+        data.extend_from_slice(&VERSION_REQUEST);
+        let mut reader = Trickle(Cursor::new(data));
+        assert_eq!(read_message(&mut reader).unwrap(), VERSION_REQUEST.to_vec());
+    }
+
+    #[test]
+    fn it_should_reject_broken_frames() {
+        let mut data = VERSION_REQUEST.to_vec();
+        data[11] ^= 1;
+        let error = read_frame(&mut Cursor::new(data)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        let mut data = VERSION_REQUEST.to_vec();
+        data[10] = 0x04;
+        let error = read_frame(&mut Cursor::new(data)).unwrap_err();
+        assert_eq!(error.to_string(), "etx is not 3");
+        let error = read_frame(&mut Cursor::new(VERSION_REQUEST[..8].to_vec())).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn it_should_fragment_large_messages() {
+        let message = large_message(5000);
+        let mut written = vec![];
+        write_message(&mut written, &message).unwrap();
+        let mut reader = Cursor::new(written.clone());
+        let mut frames = 0;
+        while (reader.position() as usize) < written.len() {
+            assert!(read_frame(&mut reader).unwrap().len() <= MAX_FRAME_LENGTH);
+            frames += 1;
+        }
+        assert_eq!(frames, 5);
+        assert_eq!(read_message(&mut Trickle(Cursor::new(written))).unwrap(), message);
+
+        let mut written = vec![];
+        write_message(&mut written, &VERSION_REQUEST).unwrap();
+        assert_eq!(written, VERSION_REQUEST.to_vec());
+    }
+
+    #[test]
+    fn it_should_reject_fragments_out_of_order() {
+        let mut written = vec![];
+        write_message(&mut written, &large_message(3000)).unwrap();
+        let mut reader = Cursor::new(written);
+        read_frame(&mut reader).unwrap();
+        let error = read_message(&mut reader).unwrap_err();
+        assert_eq!(error.to_string(), "fragment is out of order");
+    }
+
+    #[test]
+    fn it_should_retry_on_lrc_mismatch() {
+        let response = large_message(2000);
+        let mut corrupted = vec![];
+        write_message(&mut corrupted, &response).unwrap();
+        corrupted[100] ^= 1;
+        let mut device = Device {
+            responses: VecDeque::from(vec![corrupted.clone(), corrupted.clone(), response.clone()]),
+            pending: Cursor::new(vec![]),
+            requests: 0,
+        };
+        assert_eq!(exchange(&mut device, &VERSION_REQUEST, 3, |_| {}).unwrap(), response);
+        assert_eq!(device.requests, 3);
+
+        let mut device = Device {
+            responses: VecDeque::from(vec![corrupted.clone(), corrupted]),
+            pending: Cursor::new(vec![]),
+            requests: 0,
+        };
+        let error = exchange(&mut device, &VERSION_REQUEST, 1, |_| {}).unwrap_err();
+        assert_eq!(error.to_string(), "lrc is not matched");
+        assert_eq!(device.requests, 2);
+        assert!(matches!(map_io_error(Error::new(ErrorKind::TimedOut, "")), KSError::SerialTimeout));
+    }
+
+    #[test]
+    fn it_should_not_resend_state_changing_commands() {
+        let response = large_message(100);
+        let mut corrupted = response.clone();
+        corrupted[10] ^= 1;
+        let clear_token = ClearTokenCommand::build(None).unwrap().to_vec();
+        let mut device = Device {
+            responses: VecDeque::from(vec![corrupted, response]),
+            pending: Cursor::new(vec![]),
+            requests: 0,
+        };
+        let error = exchange(&mut device, &clear_token, 3, |_| {}).unwrap_err();
+        assert_eq!(error.to_string(), "lrc is not matched");
+        assert_eq!(device.requests, 1);
+    }
+}
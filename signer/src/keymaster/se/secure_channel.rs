@@ -0,0 +1,389 @@
+use std::convert::TryFrom;
+use std::sync::Mutex;
+
+#[cfg(any(test, feature = "simulator"))]
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use cryptoxide::hkdf::{hkdf_expand, hkdf_extract};
+use cryptoxide::sha2::Sha256;
+use p256::ecdh::diffie_hellman;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use zeroize::Zeroizing;
+
+use super::command::PacketBuilder;
+use super::tags::{channel, encryption};
+use super::transport::Transport;
+use super::tvl::Packet;
+use crate::error::KSError;
+
+const CHANNEL_INFO: &[u8] = b"rcc se secure channel";
+const SESSION_NONCE_LENGTH: usize = 32;
+const COUNTER_LENGTH: usize = 8;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Role {
+    Host,
+    #[cfg(any(test, feature = "simulator"))]
+    SecureElement,
+}
+
+// keys of one channel session, each direction has its own key and a strictly increasing counter
+pub(crate) struct Session {
+    send_key: Zeroizing<[u8; 32]>,
+    receive_key: Zeroizing<[u8; 32]>,
+    send_counter: u64,
+    receive_counter: Option<u64>,
+}
+
+impl Session {
+    // hkdf-sha256 over the ecdh secret, salted with the host ephemeral key and the SE session nonce
+    pub(crate) fn derive(shared_secret: &[u8], host_public_key: &[u8], session_nonce: &[u8], role: Role) -> Self {
+        let salt = [host_public_key, session_nonce].concat();
+        let mut prk = Zeroizing::new([0u8; 32]);
+        hkdf_extract(Sha256::new(), &salt, shared_secret, prk.as_mut());
+        let mut okm = Zeroizing::new([0u8; 64]);
+        hkdf_expand(Sha256::new(), prk.as_ref(), CHANNEL_INFO, okm.as_mut());
+        let mut host_to_se = Zeroizing::new([0u8; 32]);
+        let mut se_to_host = Zeroizing::new([0u8; 32]);
+        host_to_se.copy_from_slice(&okm[..32]);
+        se_to_host.copy_from_slice(&okm[32..]);
+        let (send_key, receive_key) = match role {
+            Role::Host => (host_to_se, se_to_host),
+            #[cfg(any(test, feature = "simulator"))]
+            Role::SecureElement => (se_to_host, host_to_se),
+        };
+        Session {
+            send_key,
+            receive_key,
+            send_counter: 0,
+            receive_counter: None,
+        }
+    }
+
+    // counter || aes-256-gcm ciphertext, the counter is the nonce and the associated data
+    pub(crate) fn seal(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, KSError> {
+        let counter = self.send_counter;
+        self.send_counter = counter
+            .checked_add(1)
+            .ok_or(KSError::SecureChannelError("session counter is exhausted".to_string()))?;
+        let counter_bytes = counter.to_be_bytes();
+        let cipher = Aes256Gcm::new_from_slice(self.send_key.as_ref())
+            .map_err(|e| KSError::SecureChannelError(e.to_string()))?;
+        let ciphertext = cipher
+            .encrypt(&nonce(counter), Payload { msg: plaintext, aad: &counter_bytes })
+            .map_err(|e| KSError::SecureChannelError(e.to_string()))?;
+        Ok([counter_bytes.as_slice(), &ciphertext].concat())
+    }
+
+    // rejects packets whose counter is not above the last accepted one
+    pub(crate) fn open(&mut self, sealed: &[u8]) -> Result<Zeroizing<Vec<u8>>, KSError> {
+        if sealed.len() < COUNTER_LENGTH {
+            return Err(KSError::SecureChannelError("sealed packet is too short".to_string()));
+        }
+        let (counter_bytes, ciphertext) = sealed.split_at(COUNTER_LENGTH);
+        let counter = u64::from_be_bytes(
+            counter_bytes
+                .try_into()
+                .map_err(|_| KSError::SecureChannelError("invalid counter".to_string()))?,
+        );
+        if self.receive_counter.is_some_and(|last| counter <= last) {
+            return Err(KSError::SecureChannelError("replayed packet".to_string()));
+        }
+        let cipher = Aes256Gcm::new_from_slice(self.receive_key.as_ref())
+            .map_err(|e| KSError::SecureChannelError(e.to_string()))?;
+        let plaintext = cipher
+            .decrypt(&nonce(counter), Payload { msg: ciphertext, aad: counter_bytes })
+            .map_err(|_| KSError::SecureChannelError("packet authentication failed".to_string()))?;
+        self.receive_counter = Some(counter);
+        Ok(Zeroizing::new(plaintext))
+    }
+
+    pub(crate) fn wrap(&mut self, packet: &[u8]) -> Result<Zeroizing<Vec<u8>>, KSError> {
+        let mut builder = PacketBuilder::new();
+        builder.add_payload(channel::CIPHERTEXT_TAG, &self.seal(packet)?);
+        Ok(builder.build_with_encryption_flag(encryption::ENCRYPTED).to_vec())
+    }
+
+    pub(crate) fn unwrap(&mut self, packet: &Packet) -> Result<Zeroizing<Vec<u8>>, KSError> {
+        if packet.encryption_flag != encryption::ENCRYPTED {
+            return Err(KSError::SecureChannelError("packet is not encrypted".to_string()));
+        }
+        let sealed = packet
+            .payloads
+            .get(&channel::CIPHERTEXT_TAG)
+            .ok_or(KSError::SecureChannelError("ciphertext is missing".to_string()))?;
+        self.open(&sealed.value)
+    }
+}
+
+fn nonce(counter: u64) -> Nonce<aes_gcm::aead::consts::U12> {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&counter.to_be_bytes());
+    *Nonce::from_slice(&nonce)
+}
+
+// SE side of the handshake, answers with a fresh session nonce and a key confirmation sealed with the new session
+#[cfg(any(test, feature = "simulator"))]
+pub(crate) fn accept_handshake(
+    static_key: &p256::SecretKey,
+    request: &Packet,
+) -> Result<(Session, Zeroizing<Vec<u8>>), KSError> {
+    let host_public_key = request
+        .payloads
+        .get(&channel::EPHEMERAL_PUBLIC_KEY_TAG)
+        .ok_or(KSError::SecureChannelError("ephemeral public key is missing".to_string()))?;
+    let host_key = p256::PublicKey::from_sec1_bytes(&host_public_key.value)
+        .map_err(|e| KSError::SecureChannelError(e.to_string()))?;
+    let mut session_nonce = [0u8; SESSION_NONCE_LENGTH];
+    OsRng.fill_bytes(&mut session_nonce);
+    let shared_secret = diffie_hellman(static_key.to_nonzero_scalar(), host_key.as_affine());
+    let mut session = Session::derive(
+        shared_secret.raw_secret_bytes(),
+        &host_public_key.value,
+        &session_nonce,
+        Role::SecureElement,
+    );
+    let mut builder = PacketBuilder::new();
+    builder.add_payload(channel::SESSION_NONCE_TAG, &session_nonce);
+    builder.add_payload(channel::KEY_CONFIRMATION_TAG, &session.seal(&[])?);
+    Ok((session, builder.build_with_encryption_flag(encryption::HANDSHAKE).to_vec()))
+}
+
+// wraps every packet of the inner transport, the session is negotiated on first use and after any failure
+pub struct SecureChannel {
+    transport: Box<dyn Transport>,
+    se_public_key: p256::PublicKey,
+    session: Mutex<Option<Session>>,
+}
+
+impl SecureChannel {
+    pub fn new(transport: Box<dyn Transport>, se_public_key: &[u8]) -> Result<Self, KSError> {
+        let se_public_key = p256::PublicKey::from_sec1_bytes(se_public_key)
+            .map_err(|e| KSError::SecureChannelError(e.to_string()))?;
+        Ok(SecureChannel {
+            transport,
+            se_public_key,
+            session: Mutex::new(None),
+        })
+    }
+
+    fn handshake(&self) -> Result<Session, KSError> {
+        let ephemeral_key = p256::SecretKey::random(&mut OsRng);
+        let public_key = ephemeral_key.public_key().to_encoded_point(true);
+        let mut builder = PacketBuilder::new();
+        builder.add_payload(channel::EPHEMERAL_PUBLIC_KEY_TAG, public_key.as_bytes());
+        let request = builder.build_with_encryption_flag(encryption::HANDSHAKE);
+        let response = Packet::try_from(self.transport.send_data(&request.to_vec())?)?;
+        if response.encryption_flag != encryption::HANDSHAKE {
+            return Err(KSError::SecureChannelError("secure channel is not supported by the SE".to_string()));
+        }
+        let session_nonce = response
+            .payloads
+            .get(&channel::SESSION_NONCE_TAG)
+            .filter(|tvl| tvl.value.len() == SESSION_NONCE_LENGTH)
+            .ok_or(KSError::SecureChannelError("session nonce is missing".to_string()))?;
+        let confirmation = response
+            .payloads
+            .get(&channel::KEY_CONFIRMATION_TAG)
+            .ok_or(KSError::SecureChannelError("key confirmation is missing".to_string()))?;
+        let shared_secret = diffie_hellman(ephemeral_key.to_nonzero_scalar(), self.se_public_key.as_affine());
+        let mut session = Session::derive(
+            shared_secret.raw_secret_bytes(),
+            public_key.as_bytes(),
+            &session_nonce.value,
+            Role::Host,
+        );
+        // only the holder of the SE static key can produce the confirmation
+        session
+            .open(&confirmation.value)
+            .map_err(|_| KSError::SecureChannelError("SE key confirmation failed".to_string()))?;
+        Ok(session)
+    }
+}
+
+impl Transport for SecureChannel {
+    fn send_data(&self, data: &[u8]) -> Result<Vec<u8>, KSError> {
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| KSError::SecureChannelError("session is poisoned".to_string()))?;
+        if session.is_none() {
+            *session = Some(self.handshake()?);
+        }
+        let active_session = session
+            .as_mut()
+            .ok_or(KSError::SecureChannelError("secure channel is not established".to_string()))?;
+        let result = active_session.wrap(data).and_then(|request| {
+            let response = Packet::try_from(self.transport.send_data(&request)?)?;
+            Ok(active_session.unwrap(&response)?.to_vec())
+        });
+        if result.is_err() {
+            *session = None;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymaster::se::simulator::SeSimulator;
+    use crate::keymaster::se::SecureElement;
+    use crate::keymaster::{KeyMaster, PublicKeyFormat, SigningAlgorithm};
+    use std::sync::Arc;
+
+    const PASSWORD: &str = "f6cda9bc3afff095f7c96a78455b2925c6339db3ce3563013e7fb75cc0e4829d";
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const SE_STATIC_KEY: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+
+    // records every packet put on the wire
+    struct Wire {
+        simulator: Arc<SeSimulator>,
+        packets: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl Transport for Wire {
+        fn send_data(&self, data: &[u8]) -> Result<Vec<u8>, KSError> {
+            self.packets.lock().unwrap().push(data.to_vec());
+            let response = self.simulator.send_data(data)?;
+            self.packets.lock().unwrap().push(response.clone());
+            Ok(response)
+        }
+    }
+
+    fn simulator() -> (Arc<SeSimulator>, Vec<u8>) {
+        let simulator = SeSimulator::new(hex::decode(PASSWORD).unwrap());
+        simulator.import_mnemonic(0, Zeroizing::new(MNEMONIC.to_string()), &"".into()).unwrap();
+        let se_public_key = simulator
+            .enable_secure_channel(&hex::decode(SE_STATIC_KEY).unwrap())
+            .unwrap();
+        (Arc::new(simulator), se_public_key)
+    }
+
+    fn wire(simulator: &Arc<SeSimulator>) -> (Wire, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let packets = Arc::new(Mutex::new(vec![]));
+        let wire = Wire {
+            simulator: simulator.clone(),
+            packets: packets.clone(),
+        };
+        (wire, packets)
+    }
+
+    #[test]
+    fn it_should_seal_and_open() {
+        let shared_secret = [0x11u8; 32];
+        let mut host = Session::derive(&shared_secret, b"host", b"nonce", Role::Host);
+        let mut se = Session::derive(&shared_secret, b"host", b"nonce", Role::SecureElement);
+        let first = host.seal(b"first").unwrap();
+        let second = host.seal(b"second").unwrap();
+        assert_eq!(se.open(&first).unwrap().as_slice(), b"first");
+        assert_eq!(se.open(&second).unwrap().as_slice(), b"second");
+        assert!(se.open(&first).is_err());
+        assert!(se.open(&second).is_err());
+        // the reverse direction uses a different key
+        let response = se.seal(b"response").unwrap();
+        assert!(se.open(&response).is_err());
+        assert_eq!(host.open(&response).unwrap().as_slice(), b"response");
+        let mut tampered = host.seal(b"third").unwrap();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(se.open(&tampered).is_err());
+    }
+
+    #[test]
+    fn it_should_use_se_through_secure_channel() {
+        let (simulator, se_public_key) = simulator();
+        let (wire, packets) = wire(&simulator);
+        let channel = SecureChannel::new(Box::new(wire), &se_public_key).unwrap();
+        let se = SecureElement::new_with_transport(Box::new(channel));
+        let public_key = se
+            .get_public_key(0, &PASSWORD.into(), SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'".to_string(), PublicKeyFormat::Raw)
+            .unwrap();
+        assert_eq!(bs58::encode(public_key).into_string(), "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+        assert_eq!(hex::encode(se.get_master_fingerprint(0, &PASSWORD.into()).unwrap()), "73c5da0a");
+
+        let packets = packets.lock().unwrap();
+        assert_eq!(packets[0][1], encryption::HANDSHAKE);
+        assert_eq!(packets[1][1], encryption::HANDSHAKE);
+        assert!(packets[2..].iter().all(|packet| packet[1] == encryption::ENCRYPTED));
+        let password = hex::decode(PASSWORD).unwrap();
+        assert!(packets.iter().all(|packet| !packet.windows(password.len()).any(|w| w == password)));
+    }
+
+    #[test]
+    fn it_should_reject_plain_and_replayed_packets() {
+        let (simulator, se_public_key) = simulator();
+        let plain_se = SecureElement::new_with_transport(Box::new(wire(&simulator).0));
+        assert!(plain_se.open_session(&PASSWORD.into()).is_err());
+
+        let (wire, packets) = wire(&simulator);
+        let se = SecureElement::new_with_transport(Box::new(SecureChannel::new(Box::new(wire), &se_public_key).unwrap()));
+        let session = se.open_session(&PASSWORD.into()).unwrap();
+        let request = packets.lock().unwrap()[2].clone();
+        let response = Packet::try_from(simulator.send_data(&request).unwrap()).unwrap();
+        assert_eq!(response.encryption_flag, encryption::PLAIN);
+        // the channel keeps working for the legitimate host
+        assert_eq!(session.get_entropy(0).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn it_should_reject_unknown_se_key() {
+        let (simulator, _) = simulator();
+        let other_key = p256::SecretKey::random(&mut OsRng).public_key().to_encoded_point(true);
+        let channel = SecureChannel::new(Box::new(wire(&simulator).0), other_key.as_bytes()).unwrap();
+        let result = channel.send_data(&[0x02, 0x00, 0x00, 0x06, 0x00, 0x01, 0x00, 0x02, 0x01, 0x02, 0x03, 0x07]);
+        assert_eq!(result.unwrap_err().to_string(), "SecureChannelError: SE key confirmation failed");
+    }
+
+    #[test]
+    fn it_should_renegotiate_after_session_loss() {
+        let (simulator, se_public_key) = simulator();
+        let se = SecureElement::new_with_transport(Box::new(SecureChannel::new(Box::new(wire(&simulator).0), &se_public_key).unwrap()));
+        assert!(se.get_version().is_ok());
+        // a restarted SE forgets the session
+        simulator.enable_secure_channel(&hex::decode(SE_STATIC_KEY).unwrap()).unwrap();
+        assert!(se.get_version().is_err());
+        assert!(se.get_version().is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_should_use_serial_port_through_secure_channel() {
+        use crate::keymaster::se::framing::{read_message, write_message};
+        use serialport::{SerialPort, TTYPort};
+        use std::io::ErrorKind;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::thread;
+        use std::time::Duration;
+
+        let (simulator, se_public_key) = simulator();
+        let (mut se_side, host_side) = TTYPort::pair().unwrap();
+        se_side.set_timeout(Duration::from_millis(50)).unwrap();
+        let done = Arc::new(AtomicBool::new(false));
+        let (wire, packets) = wire(&simulator);
+        // the SE end of the pseudo terminal answers until the host is done
+        let se_thread = {
+            let done = done.clone();
+            thread::spawn(move || loop {
+                match read_message(&mut se_side) {
+                    Ok(request) => write_message(&mut se_side, &wire.send_data(&request).unwrap()).unwrap(),
+                    Err(e) if e.kind() == ErrorKind::TimedOut && !done.load(Ordering::SeqCst) => continue,
+                    Err(_) => break,
+                }
+            })
+        };
+
+        let signer = crate::Signer::new_with_se_secure_channel(host_side.name().unwrap(), &se_public_key).unwrap();
+        let fingerprint = signer.get_master_fingerprint(0, &PASSWORD.into());
+        done.store(true, Ordering::SeqCst);
+        se_thread.join().unwrap();
+        assert_eq!(hex::encode(fingerprint.unwrap()), "73c5da0a");
+
+        let packets = packets.lock().unwrap();
+        assert_eq!(packets[0][1], encryption::HANDSHAKE);
+        assert!(packets[2..].iter().all(|packet| packet[1] == encryption::ENCRYPTED));
+        let password = hex::decode(PASSWORD).unwrap();
+        assert!(packets.iter().all(|packet| !packet.windows(password.len()).any(|w| w == password)));
+    }
+}
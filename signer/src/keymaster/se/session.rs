@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use k256::ecdsa::SigningKey;
+
+use super::{GetKeyType, SecureElement};
+use crate::algorithm;
+use crate::algorithm::SecretKey;
+use crate::error::KSError;
+use crate::secret::{SecretBytes, SecretPassword};
+use crate::keymaster::{SignRequest, SigningAlgorithm, SigningOption};
+
+// keys fetched from the SE while signing, wiped when the cache is dropped
+#[derive(Default)]
+struct KeyCache {
+    keys: HashMap<(GetKeyType, Option<SigningAlgorithm>, String), SecretBytes>,
+}
+
+// an auth token verified against the user password, the token is cleared on the chip and wiped from memory on drop
+pub struct SeSession<'a> {
+    se: &'a SecureElement,
+    auth_token: SecretBytes,
+}
+
+impl<'a> SeSession<'a> {
+    pub(crate) fn open(se: &'a SecureElement, password: &SecretPassword) -> Result<Self, KSError> {
+        let auth_token = se.generate_token(password)?;
+        Ok(SeSession {
+            se,
+            auth_token,
+        })
+    }
+
+    pub fn get_key(
+        &self,
+        mnemonic_id: u8,
+        path: String,
+        algo: SigningAlgorithm,
+        key_type: GetKeyType,
+    ) -> Result<SecretBytes, KSError> {
+        self.se.get_key(mnemonic_id, path, Some(&self.auth_token), algo, key_type)
+    }
+
+    pub fn get_entropy(&self, mnemonic_id: u8) -> Result<SecretBytes, KSError> {
+        const FAKE_PATH: &str = "m'/1'";
+        self.get_key(
+            mnemonic_id,
+            FAKE_PATH.to_string(),
+            SigningAlgorithm::Secp256k1,
+            GetKeyType::Entropy,
+        )
+    }
+
+    pub fn get_ada_root_key(&self, mnemonic_id: u8) -> Result<SecretBytes, KSError> {
+        const ADA_FAKE_PATH: &str = "m/1852'";
+        self.get_key(
+            mnemonic_id,
+            ADA_FAKE_PATH.to_string(),
+            SigningAlgorithm::Ed25519,
+            GetKeyType::ADARootKey,
+        )
+    }
+
+    pub fn sign_data(
+        &self,
+        mnemonic_id: u8,
+        data: Vec<u8>,
+        algo: SigningAlgorithm,
+        derivation_path: String,
+        signing_option: Option<SigningOption>,
+    ) -> Result<Vec<u8>, KSError> {
+        let request = SignRequest {
+            data,
+            algo,
+            path: derivation_path,
+            option: signing_option,
+        };
+        self.sign_with_cache(&mut KeyCache::default(), mnemonic_id, request)
+    }
+
+    // keys are fetched once per path for the whole batch
+    pub fn sign_batch(&self, mnemonic_id: u8, requests: Vec<SignRequest>) -> Vec<Result<Vec<u8>, KSError>> {
+        let mut cache = KeyCache::default();
+        requests
+            .into_iter()
+            .map(|request| self.sign_with_cache(&mut cache, mnemonic_id, request))
+            .collect()
+    }
+
+    fn get_cached_key<'c>(
+        &self,
+        cache: &'c mut KeyCache,
+        mnemonic_id: u8,
+        path: &str,
+        algo: SigningAlgorithm,
+        key_type: GetKeyType,
+    ) -> Result<&'c [u8], KSError> {
+        // only extended private keys depend on the path and curve
+        let cache_key = match key_type {
+            GetKeyType::ExtendedPrivateKey => (key_type, Some(algo), path.to_string()),
+            _ => (key_type, None, String::new()),
+        };
+        if !cache.keys.contains_key(&cache_key) {
+            let key = match key_type {
+                GetKeyType::Entropy => self.get_entropy(mnemonic_id)?,
+                GetKeyType::ADARootKey => self.get_ada_root_key(mnemonic_id)?,
+                _ => self.get_key(mnemonic_id, path.to_string(), algo, key_type)?,
+            };
+            cache.keys.insert(cache_key.clone(), key);
+        }
+        Ok(cache.keys[&cache_key].expose())
+    }
+
+    fn sign_with_cache(&self, cache: &mut KeyCache, mnemonic_id: u8, request: SignRequest) -> Result<Vec<u8>, KSError> {
+        let SignRequest { data, algo, path, option: signing_option } = request;
+        if let (SigningAlgorithm::Ed25519, Some(SigningOption::ADA)) = (algo, signing_option) {
+            // sign with bip32_ed25519
+            let ada_root_key = self.get_cached_key(cache, mnemonic_id, &path, algo, GetKeyType::ADARootKey)?;
+            let signature = algorithm::bip32_ed25519::sign_message(&data, path, ada_root_key)
+                .map_err(KSError::SignDataError)?;
+            return Ok(signature.to_vec());
+        }
+        match algo {
+            SigningAlgorithm::Secp256k1 => {
+                let secret = self.get_cached_key(cache, mnemonic_id, &path, algo, GetKeyType::ExtendedPrivateKey)?;
+                if let Some(SigningOption::Schnorr { .. }) = signing_option {
+                    let schnorr = algorithm::schnorr::Schnorr::from_secret(secret)?;
+                    return schnorr.sign(data, signing_option);
+                }
+                let secp256k1 = SigningKey::from_secret(secret)?;
+                secp256k1.sign(data, signing_option)
+            }
+            SigningAlgorithm::Secp256R1 => {
+                let secret = self.get_cached_key(cache, mnemonic_id, &path, algo, GetKeyType::ExtendedPrivateKey)?;
+                let secp256r1 = p256::ecdsa::SigningKey::from_secret(secret)?;
+                secp256r1.sign(data, signing_option)
+            }
+            SigningAlgorithm::Ed25519 => {
+                // get master seed from SE and derive with slip10
+                let master_seed = self.get_cached_key(cache, mnemonic_id, &path, algo, GetKeyType::MasterSeed)?;
+                let signature = algorithm::slip10_ed25519::sign_message(&data, path, master_seed)
+                    .map_err(KSError::SignDataError)?;
+                Ok(signature.to_vec())
+            }
+            SigningAlgorithm::SR25519 => {
+                // substrate derives sr25519 keys from the entropy
+                let entropy = self.get_cached_key(cache, mnemonic_id, &path, algo, GetKeyType::Entropy)?;
+                let signature = algorithm::sr25519::sign_message(&data, path, entropy)
+                    .map_err(KSError::SignDataError)?;
+                Ok(signature.to_vec())
+            }
+            SigningAlgorithm::RSA => {
+                // get rsa secret from SE
+                let secret = self.get_cached_key(cache, mnemonic_id, &path, algo, GetKeyType::RSASecret)?;
+                let rsa = algorithm::rsa::RSA::from_secret(secret)?;
+                match signing_option {
+                    Some(SigningOption::RSA { salt_len }) => rsa.sign(data, Some(SigningOption::RSA { salt_len })),
+                    _ => Err(KSError::RSASignError),
+                }
+            }
+        }
+    }
+
+    // raw 32 bytes public keys of the curves the SE can't export an extended public key for
+    pub fn get_public_key(
+        &self,
+        mnemonic_id: u8,
+        algo: SigningAlgorithm,
+        derivation_path: String,
+    ) -> Result<Vec<u8>, KSError> {
+        let public_key = match algo {
+            SigningAlgorithm::Ed25519 => {
+                let master_seed = self.get_key(mnemonic_id, derivation_path.clone(), algo, GetKeyType::MasterSeed)?;
+                algorithm::slip10_ed25519::get_public_key(derivation_path, master_seed.expose())
+            }
+            SigningAlgorithm::SR25519 => {
+                let entropy = self.get_entropy(mnemonic_id)?;
+                algorithm::sr25519::get_public_key(derivation_path, entropy.expose())
+            }
+            _ => Err("public key format is not supported".to_string()),
+        }
+        .map_err(KSError::GetPublicKeyError)?;
+        Ok(public_key.to_vec())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn auth_token(&self) -> SecretBytes {
+        SecretBytes::from_slice(self.auth_token.expose())
+    }
+}
+
+impl Drop for SeSession<'_> {
+    fn drop(&mut self) {
+        let _ = self.se.clear_token();
+    }
+}
@@ -0,0 +1,871 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use bip39::Mnemonic;
+use bitcoin::base58;
+use bitcoin::bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey};
+use bitcoin::hashes::{hash160, Hash};
+use bitcoin::secp256k1::Secp256k1;
+use bitcoin::Network;
+use p256::ecdsa::signature::Signer;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use zeroize::Zeroizing;
+
+use super::command::PacketBuilder;
+use super::device::{encode_certificate_chain, ATTESTATION_CONTEXT};
+use super::secure_channel::{accept_handshake, Session};
+use super::tags::{encryption, methods, result, status, COMMAND_TAG, RESPONSE_TAG};
+use super::transport::Transport;
+use super::tvl::Packet;
+use crate::algorithm::secp256r1::ExtendedPrivateKey;
+use crate::error::KSError;
+use crate::secret::SecretPassword;
+
+const FIRMWARE_VERSION: &str = "1.2.0.000000";
+pub const SERIAL_NUMBER: &str = "KS0000000001";
+// attest is added once a device key is provisioned
+const COMMANDS: [u16; 6] = [
+    methods::GET_FIRMWARE_STATUS_TAG,
+    methods::GET_RANDOM_ENTROPY_TAG,
+    methods::VERIFY_USER_PASSWORD,
+    methods::CLEAR_TOKEN_TAG,
+    methods::GET_KEY_TAG,
+    methods::SET_SECRET_TAG,
+];
+const ENTROPY_LENGTH: usize = 32;
+const AUTH_TOKEN_LENGTH: usize = 32;
+const XPRV_VERSION: [u8; 4] = [0x04, 0x88, 0xad, 0xe4];
+const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+
+// wrong passwords allowed before the chip locks
+const MAX_PASSWORD_ATTEMPTS: u8 = 5;
+
+struct Wallet {
+    entropy: Zeroizing<Vec<u8>>,
+    seed: Zeroizing<Vec<u8>>,
+    ada_root_key: Option<Zeroizing<Vec<u8>>>,
+    rsa_secret: Option<Zeroizing<Vec<u8>>>,
+}
+
+struct State {
+    password: Zeroizing<Vec<u8>>,
+    wallets: HashMap<u8, Wallet>,
+    // set secret commands carry no wallet id, they write to the wallet last addressed by get key
+    active_wallet: Option<u8>,
+    auth_token: Option<Zeroizing<Vec<u8>>>,
+    remaining_password_attempts: u8,
+    // once a channel key is set only encrypted packets are served
+    channel_key: Option<p256::SecretKey>,
+    session: Option<Session>,
+    // device key and its DER certificate chain, device certificate first
+    attestation: Option<(p256::ecdsa::SigningKey, Vec<Vec<u8>>)>,
+}
+
+type CommandResult = Result<Vec<(u16, Vec<u8>)>, u16>;
+
+// in-process SE speaking the tvl packet protocol, for exercising SecureElement without hardware
+pub struct SeSimulator {
+    state: Mutex<State>,
+}
+
+impl SeSimulator {
+    pub fn new(password: Vec<u8>) -> Self {
+        SeSimulator {
+            state: Mutex::new(State {
+                password: Zeroizing::new(password),
+                wallets: HashMap::new(),
+                active_wallet: None,
+                auth_token: None,
+                remaining_password_attempts: MAX_PASSWORD_ATTEMPTS,
+                channel_key: None,
+                session: None,
+                attestation: None,
+            }),
+        }
+    }
+
+    pub fn import_mnemonic(
+        &self,
+        wallet_id: u8,
+        mnemonic: Zeroizing<String>,
+        passphrase: &SecretPassword,
+    ) -> Result<(), KSError> {
+        let mnemonic = Mnemonic::parse(mnemonic.as_str())
+            .map_err(|e| KSError::SEError(e.to_string()))?;
+        let wallet = Wallet {
+            entropy: Zeroizing::new(mnemonic.to_entropy()),
+            seed: Zeroizing::new(mnemonic.to_seed(passphrase.expose()).to_vec()),
+            ada_root_key: None,
+            rsa_secret: None,
+        };
+        let mut state = self.lock()?;
+        state.wallets.insert(wallet_id, wallet);
+        state.active_wallet.get_or_insert(wallet_id);
+        Ok(())
+    }
+
+    // sets the static channel key and drops the current session, returns the compressed public key for the host
+    pub fn enable_secure_channel(&self, secret_key: &[u8]) -> Result<Vec<u8>, KSError> {
+        let secret_key = p256::SecretKey::from_be_bytes(secret_key)
+            .map_err(|e| KSError::SecureChannelError(e.to_string()))?;
+        let public_key = secret_key.public_key().to_encoded_point(true).as_bytes().to_vec();
+        let mut state = self.lock()?;
+        state.channel_key = Some(secret_key);
+        state.session = None;
+        Ok(public_key)
+    }
+
+    // device key the attest command signs with, the leaf certificate is expected to name SERIAL_NUMBER
+    pub fn provision_attestation(&self, device_key: &[u8], certificate_chain: Vec<Vec<u8>>) -> Result<(), KSError> {
+        let device_key = p256::ecdsa::SigningKey::from_bytes(device_key)
+            .map_err(|e| KSError::SEError(e.to_string()))?;
+        self.lock()?.attestation = Some((device_key, certificate_chain));
+        Ok(())
+    }
+
+    // whether an auth token issued by verify password is still live
+    pub fn is_authenticated(&self) -> Result<bool, KSError> {
+        Ok(self.lock()?.auth_token.is_some())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, State>, KSError> {
+        self.state
+            .lock()
+            .map_err(|_| KSError::SEError("simulator state is poisoned".to_string()))
+    }
+}
+
+impl Transport for SeSimulator {
+    fn send_data(&self, data: &[u8]) -> Result<Vec<u8>, KSError> {
+        // the copy handed back is wiped by the packet parser on the host side
+        self.respond(data).map(|response| response.to_vec())
+    }
+}
+
+impl SeSimulator {
+    fn respond(&self, data: &[u8]) -> Result<Zeroizing<Vec<u8>>, KSError> {
+        let request = Packet::try_from(data)?;
+        let mut state = self.lock()?;
+        let channel_key = match &state.channel_key {
+            Some(channel_key) => channel_key.clone(),
+            None if request.encryption_flag == encryption::PLAIN => return state.handle(&request),
+            None => return Ok(status_response(status::SECURE_CHANNEL_UNSUPPORTED)),
+        };
+        match request.encryption_flag {
+            encryption::HANDSHAKE => match accept_handshake(&channel_key, &request) {
+                Ok((session, response)) => {
+                    state.session = Some(session);
+                    Ok(response)
+                }
+                Err(_) => Ok(status_response(status::INVALID_PARAMETER)),
+            },
+            encryption::ENCRYPTED => {
+                let mut session = match state.session.take() {
+                    Some(session) => session,
+                    None => return Ok(status_response(status::SECURE_CHANNEL_REQUIRED)),
+                };
+                // replayed or tampered packets are answered in plain and leave the session usable
+                let response = match session.unwrap(&request) {
+                    Ok(inner) => {
+                        let inner = Packet::try_from(inner.to_vec())?;
+                        let response = state.handle(&inner)?;
+                        session.wrap(&response)
+                    }
+                    Err(_) => Ok(status_response(status::SECURE_CHANNEL_REQUIRED)),
+                };
+                state.session = Some(session);
+                response
+            }
+            _ => Ok(status_response(status::SECURE_CHANNEL_REQUIRED)),
+        }
+    }
+}
+
+fn status_response(status: u16) -> Zeroizing<Vec<u8>> {
+    let mut builder = PacketBuilder::new();
+    builder.add_payload(RESPONSE_TAG, &status.to_be_bytes());
+    builder.build().to_vec()
+}
+
+fn payload(packet: &Packet, tag: u16) -> Option<&[u8]> {
+    packet.payloads.get(&tag).map(|tvl| tvl.value.as_ref())
+}
+
+impl State {
+    fn handle(&mut self, request: &Packet) -> Result<Zeroizing<Vec<u8>>, KSError> {
+        let command = request
+            .payloads
+            .get(&COMMAND_TAG)
+            .filter(|tvl| tvl.value.len() == 2)
+            .map(|tvl| u16::from_be_bytes([tvl.value[0], tvl.value[1]]))
+            .ok_or(KSError::TVLError("command id is missing".to_string()))?;
+        let result = match command {
+            methods::GET_FIRMWARE_STATUS_TAG => {
+                let mut commands = COMMANDS.to_vec();
+                if self.attestation.is_some() {
+                    commands.push(methods::ATTEST_TAG);
+                }
+                let commands = commands.iter().flat_map(|command| command.to_be_bytes()).collect();
+                Ok(vec![
+                    (result::FIRMWARE_APP_VERSION, FIRMWARE_VERSION.as_bytes().to_vec()),
+                    (result::SERIAL_NUMBER, SERIAL_NUMBER.as_bytes().to_vec()),
+                    (result::SUPPORTED_COMMANDS, commands),
+                ])
+            }
+            methods::ATTEST_TAG => self.attest(request),
+            methods::GET_RANDOM_ENTROPY_TAG => {
+                let mut entropy = vec![0u8; ENTROPY_LENGTH];
+                OsRng.fill_bytes(&mut entropy);
+                Ok(vec![(result::ENTROPY, entropy)])
+            }
+            methods::VERIFY_USER_PASSWORD => self.verify_password(request),
+            methods::CLEAR_TOKEN_TAG => {
+                self.auth_token = None;
+                Ok(vec![])
+            }
+            methods::GET_KEY_TAG => self.get_key(request),
+            methods::SET_SECRET_TAG => self.set_secret(request),
+            // signing happens on the host with the key returned by get key
+            _ => Err(status::UNSUPPORTED_COMMAND),
+        };
+        let mut builder = PacketBuilder::new();
+        builder.add_command_id(command);
+        match result {
+            Ok(fields) => {
+                builder.add_payload(RESPONSE_TAG, &result::SUCCESS.to_be_bytes());
+                fields
+                    .iter()
+                    .for_each(|(tag, value)| builder.add_payload(*tag, value));
+            }
+            Err(code) => {
+                builder.add_payload(RESPONSE_TAG, &code.to_be_bytes());
+                if code == status::WRONG_PASSWORD {
+                    builder.add_payload(result::REMAINING_PASSWORD_ATTEMPTS, &[self.remaining_password_attempts]);
+                }
+            }
+        }
+        Ok(builder.build().to_vec())
+    }
+
+    fn attest(&self, request: &Packet) -> CommandResult {
+        let (device_key, certificate_chain) = self.attestation.as_ref().ok_or(status::UNSUPPORTED_COMMAND)?;
+        let challenge = payload(request, methods::CHALLENGE_TAG).ok_or(status::INVALID_PARAMETER)?;
+        let signature: p256::ecdsa::Signature = device_key.sign(&[ATTESTATION_CONTEXT, challenge].concat());
+        Ok(vec![
+            (result::ATTESTATION_SIGNATURE, signature.to_der().as_bytes().to_vec()),
+            (result::DEVICE_CERTIFICATE_CHAIN, encode_certificate_chain(certificate_chain)),
+        ])
+    }
+
+    fn verify_password(&mut self, request: &Packet) -> CommandResult {
+        self.check_password(request)?;
+        let mut auth_token = Zeroizing::new(vec![0u8; AUTH_TOKEN_LENGTH]);
+        OsRng.fill_bytes(&mut auth_token);
+        self.auth_token = Some(auth_token.clone());
+        Ok(vec![(result::AUTH_TOKEN, auth_token.to_vec())])
+    }
+
+    // the attempt counter is reset by a right password, at zero every password is refused
+    fn check_password(&mut self, request: &Packet) -> Result<(), u16> {
+        let password = payload(request, methods::CURRENT_PASSWORD).ok_or(status::INVALID_PARAMETER)?;
+        if self.remaining_password_attempts == 0 {
+            return Err(status::WALLET_LOCKED);
+        }
+        if password != self.password.as_slice() {
+            self.remaining_password_attempts -= 1;
+            return match self.remaining_password_attempts {
+                0 => Err(status::WALLET_LOCKED),
+                _ => Err(status::WRONG_PASSWORD),
+            };
+        }
+        self.remaining_password_attempts = MAX_PASSWORD_ATTEMPTS;
+        Ok(())
+    }
+
+    fn get_key(&mut self, request: &Packet) -> CommandResult {
+        let wallet_id = payload(request, methods::WALLET_FLAG_TAG)
+            .and_then(|v| v.first().copied())
+            .ok_or(status::INVALID_PARAMETER)?;
+        let curve = payload(request, methods::CURVE_TAG)
+            .and_then(|v| v.first().copied())
+            .ok_or(status::INVALID_PARAMETER)?;
+        let path = payload(request, methods::PATH_TAG)
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .ok_or(status::INVALID_PARAMETER)?;
+        let authorized = match payload(request, methods::AUTH_TOKEN_TAG) {
+            Some(token) => match &self.auth_token {
+                Some(auth_token) if auth_token.as_slice() == token => true,
+                _ => return Err(status::INVALID_AUTH_TOKEN),
+            },
+            None => false,
+        };
+        let wallet = self.wallets.get(&wallet_id).ok_or(status::WALLET_NOT_FOUND)?;
+        self.active_wallet = Some(wallet_id);
+
+        let has_flag = |tag| payload(request, tag).is_some();
+        let secret = if has_flag(methods::MASTER_SEED_FLAG_TAG) {
+            Some((result::EXT_MASTER_SEED, Some(&wallet.seed)))
+        } else if has_flag(methods::CURRENT_SECRET) {
+            Some((result::EXT_ENTROPY, Some(&wallet.entropy)))
+        } else if has_flag(methods::RSA_SECRET_FLAG_TAG) {
+            Some((result::EXT_RSA_SECRET, wallet.rsa_secret.as_ref()))
+        } else if has_flag(methods::ADA_FLAG_TAG) {
+            Some((result::EXT_ADA_ROOT_KEY, wallet.ada_root_key.as_ref()))
+        } else {
+            None
+        };
+        if let Some((tag, secret)) = secret {
+            if !authorized {
+                return Err(status::INVALID_AUTH_TOKEN);
+            }
+            let secret = secret.ok_or(status::SECRET_NOT_SET)?;
+            return Ok(vec![(tag, secret.to_vec())]);
+        }
+
+        // M paths give the extended public key, m paths the extended private key which needs a token,
+        // like the chip the field is left out rather than failing when the token is missing
+        let (public, rest) = match (path.strip_prefix('M'), path.strip_prefix('m')) {
+            (Some(rest), _) => (true, rest),
+            (_, Some(rest)) => (false, rest),
+            _ => return Err(status::INVALID_PARAMETER),
+        };
+        if !public && !authorized {
+            return Ok(vec![]);
+        }
+        let path = DerivationPath::from_str(&format!("m{}", rest)).map_err(|_| status::INVALID_PARAMETER)?;
+        let key = match curve {
+            0 => encode_secp256k1_key(&wallet.seed, &path, public),
+            1 => encode_secp256r1_key(&wallet.seed, &path, public),
+            _ => None,
+        }
+        .ok_or(status::INVALID_PARAMETER)?;
+        Ok(vec![(result::EXT_KET, key.as_bytes().to_vec())])
+    }
+
+    fn set_secret(&mut self, request: &Packet) -> CommandResult {
+        self.check_password(request)?;
+        let wallet = self
+            .active_wallet
+            .and_then(|id| self.wallets.get_mut(&id))
+            .ok_or(status::WALLET_NOT_FOUND)?;
+        if let Some(secret) = payload(request, methods::WRITE_ADA_ROOT_FLAG) {
+            wallet.ada_root_key = Some(Zeroizing::new(secret.to_vec()));
+        } else if let Some(secret) = payload(request, methods::WRITE_RSA_SECRET_FLAG) {
+            wallet.rsa_secret = Some(Zeroizing::new(secret.to_vec()));
+        } else {
+            return Err(status::INVALID_PARAMETER);
+        }
+        Ok(vec![])
+    }
+}
+
+fn encode_secp256k1_key(seed: &[u8], path: &DerivationPath, public: bool) -> Option<Zeroizing<String>> {
+    let secp = Secp256k1::new();
+    let xprv = ExtendedPrivKey::new_master(Network::Bitcoin, seed)
+        .and_then(|master| master.derive_priv(&secp, path))
+        .ok()?;
+    match public {
+        true => Some(Zeroizing::new(ExtendedPubKey::from_priv(&secp, &xprv).to_string())),
+        false => Some(Zeroizing::new(xprv.to_string())),
+    }
+}
+
+// BIP32 serialization of the SLIP-0010 nist256p1 key
+fn encode_secp256r1_key(seed: &[u8], path: &DerivationPath, public: bool) -> Option<Zeroizing<String>> {
+    let children: Vec<ChildNumber> = path.clone().into();
+    let mut parent: Option<ExtendedPrivateKey> = None;
+    let mut xprv = ExtendedPrivateKey::new_master(seed);
+    for child in children.iter() {
+        let next = xprv.derive_child(*child);
+        parent = Some(std::mem::replace(&mut xprv, next));
+    }
+    let parent_fingerprint = parent
+        .map(|p| hash160::Hash::hash(&p.public_key()).to_byte_array())
+        .map_or([0u8; 4], |h| [h[0], h[1], h[2], h[3]]);
+    let child_number = children.last().map_or(0, |c| u32::from(*c));
+    let mut data = Zeroizing::new(Vec::with_capacity(78));
+    data.extend_from_slice(if public { &XPUB_VERSION } else { &XPRV_VERSION });
+    data.push(u8::try_from(children.len()).ok()?);
+    data.extend_from_slice(&parent_fingerprint);
+    data.extend_from_slice(&child_number.to_be_bytes());
+    data.extend_from_slice(xprv.chain_code());
+    match public {
+        true => data.extend_from_slice(&xprv.public_key()),
+        false => {
+            data.push(0);
+            data.extend_from_slice(xprv.private_key());
+        }
+    }
+    Some(Zeroizing::new(base58::encode_check(&data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm;
+    use crate::keymaster::se::{GetKeyType, SecureElement};
+    use crate::algorithm::bip32_ed25519::AdaMasterKeyScheme;
+    use crate::algorithm::bip85::Bip85Application;
+    use crate::keymaster::software::Software;
+    use crate::mnemonic::MnemonicLanguage;
+    use crate::keymaster::{
+        EntropyLength, HashAlgorithm, KeyMaster, PublicKeyFormat, Secp256k1Encoding, SigningAlgorithm, SigningOption,
+    };
+    use std::sync::Arc;
+
+    const PASSWORD: &str = "f6cda9bc3afff095f7c96a78455b2925c6339db3ce3563013e7fb75cc0e4829d";
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const DATA: &str = "af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd";
+
+    fn secure_element() -> (SecureElement, Arc<SeSimulator>) {
+        let simulator = Arc::new(SeSimulator::new(hex::decode(PASSWORD).unwrap()));
+        simulator
+            .import_mnemonic(0, Zeroizing::new(MNEMONIC.to_string()), &"".into())
+            .unwrap();
+        (SecureElement::new_with_transport(Box::new(simulator.clone())), simulator)
+    }
+
+    #[test]
+    fn it_should_get_version_and_entropy() {
+        let (se, _) = secure_element();
+        assert_eq!(String::from_utf8(se.get_version().unwrap()).unwrap(), FIRMWARE_VERSION);
+        assert_eq!(se.generate_entropy(EntropyLength::Short(12)).unwrap().len(), 16);
+        assert_eq!(se.generate_entropy(EntropyLength::Long(24)).unwrap().len(), 32);
+    }
+
+    #[test]
+    fn it_should_get_keys_with_session() {
+        let (se, _) = secure_element();
+        assert!(se.open_session(&"00".into()).is_err());
+        let session = se.open_session(&PASSWORD.into()).unwrap();
+        let token = session.auth_token();
+        let key = session
+            .get_key(0, "m/44'/60'/0'/0/0".to_string(), SigningAlgorithm::Secp256k1, GetKeyType::ExtendedPrivateKey)
+            .unwrap();
+        assert_eq!(String::from_utf8(key.expose().to_vec()).unwrap(), "xprvA46yrWykFh3LjMHn1eqk7A8WNBt7JzJqEeBX1RNz2bx9Ditu6peK7MJWR8tfXUqPjWNuL7LwLvphdgkWShNpYXiJBuvi9agxJUWiHGHtoNk");
+        let seed = session
+            .get_key(0, "m/44'/472'".to_string(), SigningAlgorithm::RSA, GetKeyType::MasterSeed)
+            .unwrap();
+        assert_eq!(hex::encode(seed.expose()), "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4");
+        assert_eq!(hex::encode(session.get_entropy(0).unwrap().expose()), "00000000000000000000000000000000");
+        drop(session);
+        let result = se.get_key(0, "m'/1'".to_string(), Some(&token), SigningAlgorithm::Secp256k1, GetKeyType::Entropy);
+        assert!(matches!(result, Err(KSError::InvalidAuthToken)));
+    }
+
+    #[test]
+    fn it_should_clear_token_when_session_ends() {
+        let (se, simulator) = secure_element();
+        {
+            let _session = se.open_session(&PASSWORD.into()).unwrap();
+            assert!(simulator.is_authenticated().unwrap());
+        }
+        assert!(!simulator.is_authenticated().unwrap());
+        // failures half way through an operation still clear the token
+        assert!(se.setup_ada_root_key(3, &PASSWORD.into(), &"".into(), AdaMasterKeyScheme::Icarus).is_err());
+        assert!(!simulator.is_authenticated().unwrap());
+        let result = se.sign_data(
+            0,
+            &PASSWORD.into(),
+            hex::decode(DATA).unwrap(),
+            SigningAlgorithm::RSA,
+            "m/44'/472'".to_string(),
+            Some(SigningOption::RSA { salt_len: 0 }),
+        );
+        assert!(result.is_err());
+        assert!(!simulator.is_authenticated().unwrap());
+        assert!(se.get_ada_extended_public_key(0, &PASSWORD.into(), "m/1852'/1815'/0'".to_string()).is_err());
+        assert!(!simulator.is_authenticated().unwrap());
+    }
+
+    #[test]
+    fn it_should_get_keys_without_token() {
+        let (se, _) = secure_element();
+        let result = se
+            .get_key(0, "m/44'/60'/0'/0/0".to_string(), None, SigningAlgorithm::Secp256k1, GetKeyType::ExtendedPrivateKey)
+            .unwrap_err();
+        assert_eq!(result.to_string(), "SEError:required field is missing");
+        let key = se
+            .get_key(0, "M/44'/60'/0'/0".to_string(), None, SigningAlgorithm::Secp256k1, GetKeyType::ExtendedPublicKey)
+            .unwrap();
+        assert_eq!(String::from_utf8(key.expose().to_vec()).unwrap(), "xpub6EF8jXqFeFEW5bwMU7RpQtHkzE4KJxcqJtvkCjJumzW8CPpacXkb92ek4WzLQXjL93HycJwTPUAcuNxCqFPKKU5m5Z2Vq4nCyh5CyPeBFFr");
+        let result = se.get_key(1, "M/44'/60'/0'/0".to_string(), None, SigningAlgorithm::Secp256k1, GetKeyType::ExtendedPublicKey);
+        assert!(result.is_err());
+        assert_eq!(hex::encode(se.get_master_fingerprint(0, &PASSWORD.into()).unwrap()), "73c5da0a");
+        assert!(matches!(
+            se.get_master_fingerprint(0, &"00".repeat(32).into()),
+            Err(KSError::WrongPassword { .. })
+        ));
+    }
+
+    #[test]
+    fn it_should_get_public_keys() {
+        let (se, _) = secure_element();
+        let seed = hex::decode("5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4").unwrap();
+        let path = "m/44'/60'/0'/0/0".to_string();
+        let public_key = se
+            .get_public_key(0, &PASSWORD.into(), SigningAlgorithm::Secp256R1, path.clone(), PublicKeyFormat::Compressed)
+            .unwrap();
+        let expected = algorithm::secp256r1::get_extended_private_key(path, &seed).unwrap().public_key();
+        assert_eq!(public_key, expected.to_vec());
+        let public_key = se
+            .get_public_key(0, &PASSWORD.into(), SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'".to_string(), PublicKeyFormat::Raw)
+            .unwrap();
+        assert_eq!(bs58::encode(public_key).into_string(), "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+    }
+
+    #[test]
+    fn it_should_sign_and_verify() {
+        let (se, _) = secure_element();
+        let data = hex::decode(DATA).unwrap();
+        let cases = [
+            (SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/0", PublicKeyFormat::Compressed, None),
+            (SigningAlgorithm::Secp256R1, "m/44'/60'/0'/0/0", PublicKeyFormat::Compressed, None),
+            (SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'", PublicKeyFormat::Raw, None),
+            (SigningAlgorithm::SR25519, "//polkadot", PublicKeyFormat::Raw, None),
+        ];
+        for (algo, path, format, signing_option) in cases {
+            let signature = se
+                .sign_data(0, &PASSWORD.into(), data.clone(), algo, path.to_string(), signing_option)
+                .unwrap();
+            let public_key = se
+                .get_public_key(0, &PASSWORD.into(), algo, path.to_string(), format)
+                .unwrap();
+            assert!(algorithm::verifier::verify(&public_key, &data, &signature, algo, signing_option).is_ok());
+        }
+    }
+
+    // the same wallet kept by Software, the keystore is removed with the returned directory
+    fn software() -> (Software, tempfile::TempDir) {
+        let keystore_dir = tempfile::tempdir().unwrap();
+        let software = Software::new(keystore_dir.path().to_str().unwrap().to_string());
+        software
+            .import_mnemonic(0, Zeroizing::new(MNEMONIC.to_string()), &"".into(), &"test_pass".into())
+            .unwrap();
+        (software, keystore_dir)
+    }
+
+    #[test]
+    fn it_should_derive_bip85_like_software() {
+        let (se, _) = secure_element();
+        let (software, _keystore_dir) = software();
+        let applications = [
+            Bip85Application::Bip39 { language: MnemonicLanguage::English, words: 24, index: 0 },
+            Bip85Application::Wif { index: 3 },
+            Bip85Application::Xprv { index: 0 },
+            Bip85Application::Hex { length: 32, index: 1 },
+            Bip85Application::Base64Password { length: 20, index: 0 },
+        ];
+        for application in applications {
+            let derived = se.derive_bip85(0, &PASSWORD.into(), application).unwrap();
+            assert_eq!(derived, software.derive_bip85(0, &"test_pass".into(), application).unwrap());
+        }
+        let application = Bip85Application::Bip39 { language: MnemonicLanguage::English, words: 12, index: 0 };
+        let mnemonic = se.derive_bip85(0, &PASSWORD.into(), application).unwrap();
+        assert!(crate::mnemonic::validate_mnemonic(&mnemonic, MnemonicLanguage::English).is_ok());
+        assert_ne!(mnemonic.as_str(), MNEMONIC);
+    }
+
+    #[test]
+    fn it_should_hash_like_software() {
+        let (se, _) = secure_element();
+        let (software, _keystore_dir) = software();
+        let path = "m/44'/784'/0'/0'/0'".to_string();
+        for hash in [None, Some(HashAlgorithm::Blake2b256), Some(HashAlgorithm::Sha3_256), Some(HashAlgorithm::Sha512Half)] {
+            let option = Some(SigningOption::Secp256k1 { encoding: Secp256k1Encoding::Recoverable, hash });
+            let data = hex::decode(DATA).unwrap();
+            let signature = se
+                .sign_data(0, &PASSWORD.into(), data.clone(), SigningAlgorithm::Secp256k1, path.clone(), option)
+                .unwrap();
+            let expected = software
+                .sign_data(0, &"test_pass".into(), data, SigningAlgorithm::Secp256k1, path.clone(), option)
+                .unwrap();
+            assert_eq!(signature, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_set_up_ada_root_key() {
+        let (se, _) = secure_element();
+        assert!(se.set_ada_root_key(0, &PASSWORD.into(), vec![0x02; 96].into()).unwrap());
+        assert_eq!(se.get_ada_root_key(0, &PASSWORD.into()).unwrap().expose(), vec![0x02; 96]);
+
+        assert!(se.setup_ada_root_key(0, &PASSWORD.into(), &"".into(), AdaMasterKeyScheme::Icarus).unwrap());
+        let path = "m/1852'/1815'/0'/0/0".to_string();
+        let xpub = se.get_ada_extended_public_key(0, &PASSWORD.into(), path.clone()).unwrap();
+        let root_key = algorithm::bip32_ed25519::get_icarus_master_key(&[0u8; 16], b"");
+        let expected = algorithm::bip32_ed25519::get_extended_public_key(path.clone(), root_key).unwrap();
+        assert_eq!(xpub, expected.to_string());
+
+        let data = hex::decode(DATA).unwrap();
+        let signature = se
+            .sign_data(0, &PASSWORD.into(), data.clone(), SigningAlgorithm::Ed25519, path, Some(SigningOption::ADA))
+            .unwrap();
+        let public_key = hex::decode(xpub).unwrap();
+        let result = algorithm::verifier::verify(&public_key, &data, &signature, SigningAlgorithm::Ed25519, Some(SigningOption::ADA));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn it_should_set_up_ada_root_key_like_software() {
+        let (se, _) = secure_element();
+        let (software, _dir) = software();
+        let path = "m/1852'/1815'/0'".to_string();
+        let data = hex::decode(DATA).unwrap();
+        let option = Some(SigningOption::ADA);
+        for scheme in [AdaMasterKeyScheme::Icarus, AdaMasterKeyScheme::IcarusTrezor, AdaMasterKeyScheme::Ledger { language: MnemonicLanguage::English }] {
+            se.setup_ada_root_key(0, &PASSWORD.into(), &"foo".into(), scheme).unwrap();
+            software.setup_ada_root_key(0, &"test_pass".into(), &"foo".into(), scheme).unwrap();
+            let xpub = se.get_ada_extended_public_key(0, &PASSWORD.into(), path.clone()).unwrap();
+            let expected = software.get_ada_extended_public_key(0, &"test_pass".into(), path.clone()).unwrap();
+            assert_eq!(xpub, expected);
+            let signature = se
+                .sign_data(0, &PASSWORD.into(), data.clone(), SigningAlgorithm::Ed25519, path.clone(), option)
+                .unwrap();
+            let expected = software
+                .sign_data(0, &"test_pass".into(), data.clone(), SigningAlgorithm::Ed25519, path.clone(), option)
+                .unwrap();
+            assert_eq!(signature, expected);
+        }
+        let icarus = algorithm::bip32_ed25519::get_master_key(&[0u8; 16], &"foo".into(), AdaMasterKeyScheme::Icarus).unwrap();
+        let ledger = se.get_ada_root_key(0, &PASSWORD.into()).unwrap();
+        assert_ne!(ledger.expose(), icarus.as_ref());
+    }
+
+    #[test]
+    fn it_should_write_and_read_rsa_secret() {
+        let (se, _) = secure_element();
+        let modulus = se.get_rsa_public_key(0, &PASSWORD.into()).unwrap();
+        assert_eq!(modulus.len(), 512);
+        assert!(hex::encode(&modulus).starts_with("c41a50ed2155a5740b45df8e3815774d6b8d193e5ad80c9efaaf6d6d0253f350"));
+        let session = se.open_session(&PASSWORD.into()).unwrap();
+        let secret = session
+            .get_key(0, "m/44'/472'".to_string(), SigningAlgorithm::RSA, GetKeyType::RSASecret)
+            .unwrap();
+        assert_eq!(secret.len(), 1536);
+        assert!(hex::encode(secret.expose()).starts_with("fdec3a1aee520780ca4058402d0422b5cd5950b715728f532499dd4bbcb68e5d"));
+    }
+
+    #[test]
+    fn it_should_reject_unknown_wallet_and_command() {
+        let (se, _) = secure_element();
+        let session = se.open_session(&PASSWORD.into()).unwrap();
+        assert!(matches!(session.get_entropy(3), Err(KSError::WalletNotFound)));
+        assert!(matches!(session.get_ada_root_key(0), Err(KSError::SecretNotSet)));
+        let simulator = SeSimulator::new(vec![]);
+        let mut builder = PacketBuilder::new();
+        builder.add_command_id(methods::SIGN_TAG);
+        let response = Packet::try_from(simulator.send_data(&builder.build().to_vec()).unwrap()).unwrap();
+        assert_eq!(
+            payload(&response, RESPONSE_TAG).unwrap(),
+            status::UNSUPPORTED_COMMAND.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn it_should_count_down_and_lock_on_wrong_passwords() {
+        let (se, _) = secure_element();
+        let wrong_password = &SecretPassword::from("00".repeat(32));
+        for remaining in (1..MAX_PASSWORD_ATTEMPTS).rev() {
+            let result = se.open_session(wrong_password);
+            assert!(matches!(result, Err(KSError::WrongPassword { remaining_attempts: Some(n) }) if n == remaining));
+        }
+        // the right password resets the counter
+        assert!(se.open_session(&PASSWORD.into()).is_ok());
+        for _ in 1..MAX_PASSWORD_ATTEMPTS {
+            assert!(matches!(se.open_session(wrong_password), Err(KSError::WrongPassword { .. })));
+        }
+        assert!(matches!(se.open_session(wrong_password), Err(KSError::WalletLocked)));
+        assert!(matches!(se.open_session(&PASSWORD.into()), Err(KSError::WalletLocked)));
+        assert!(matches!(se.set_ada_root_key(0, &PASSWORD.into(), vec![0x02; 96].into()), Err(KSError::WalletLocked)));
+    }
+
+    // counts the packets sent to the simulator
+    struct Counter {
+        simulator: Arc<SeSimulator>,
+        packets: Arc<Mutex<usize>>,
+    }
+
+    impl Transport for Counter {
+        fn send_data(&self, data: &[u8]) -> Result<Vec<u8>, KSError> {
+            *self.packets.lock().unwrap() += 1;
+            self.simulator.send_data(data)
+        }
+    }
+
+    #[test]
+    fn it_should_sign_batch_in_one_session() {
+        let (_, simulator) = secure_element();
+        let packets = Arc::new(Mutex::new(0));
+        let se = SecureElement::new_with_transport(Box::new(Counter {
+            simulator: simulator.clone(),
+            packets: packets.clone(),
+        }));
+        let data = hex::decode(DATA).unwrap();
+        let request = |algo, path: &str, option| crate::keymaster::SignRequest {
+            data: data.clone(),
+            algo,
+            path: path.to_string(),
+            option,
+        };
+        let requests = vec![
+            request(SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/0", None),
+            request(SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/0", None),
+            request(SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/1", None),
+            request(SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'", None),
+            request(SigningAlgorithm::Ed25519, "m/44'/501'/1'/0'", None),
+            // the rsa secret is not written yet
+            request(SigningAlgorithm::RSA, "m/44'/472'", Some(SigningOption::RSA { salt_len: 0 })),
+            request(SigningAlgorithm::Secp256R1, "m/44'/60'/0'/0/0", None),
+        ];
+        let results = se.sign_batch(0, &PASSWORD.into(), requests).unwrap();
+        // firmware status, verify password, 2 secp256k1 keys, 1 master seed, the missing rsa secret, 1 secp256r1 key,
+        // clear token
+        assert_eq!(*packets.lock().unwrap(), 8);
+        assert!(!simulator.is_authenticated().unwrap());
+        assert!(matches!(results[5], Err(KSError::SecretNotSet)));
+        assert_eq!(results[0].as_ref().unwrap(), results[1].as_ref().unwrap());
+        let cases = [
+            (0, SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/0", PublicKeyFormat::Compressed),
+            (2, SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/1", PublicKeyFormat::Compressed),
+            (4, SigningAlgorithm::Ed25519, "m/44'/501'/1'/0'", PublicKeyFormat::Raw),
+            (6, SigningAlgorithm::Secp256R1, "m/44'/60'/0'/0/0", PublicKeyFormat::Compressed),
+        ];
+        for (index, algo, path, format) in cases {
+            let public_key = se.get_public_key(0, &PASSWORD.into(), algo, path.to_string(), format).unwrap();
+            let signature = results[index].as_ref().unwrap();
+            assert!(algorithm::verifier::verify(&public_key, &data, signature, algo, None).is_ok());
+        }
+
+        assert!(matches!(
+            se.sign_batch(0, &"00".repeat(32).into(), vec![]),
+            Err(KSError::WrongPassword { .. })
+        ));
+    }
+
+    #[test]
+    fn it_should_derive_bip85_in_the_batch_session() {
+        let (_, simulator) = secure_element();
+        let packets = Arc::new(Mutex::new(0));
+        let se = SecureElement::new_with_transport(Box::new(Counter {
+            simulator: simulator.clone(),
+            packets: packets.clone(),
+        }));
+        let request = crate::keymaster::SignRequest {
+            data: hex::decode(DATA).unwrap(),
+            algo: SigningAlgorithm::Secp256k1,
+            path: "m/44'/60'/0'/0/0".to_string(),
+            option: None,
+        };
+        let application = Bip85Application::Hex { length: 32, index: 0 };
+        let (results, child) = se
+            .sign_batch_with_bip85(0, &PASSWORD.into(), vec![request], application)
+            .unwrap();
+        // firmware status, verify password, master seed, 1 secp256k1 key, clear token
+        assert_eq!(*packets.lock().unwrap(), 5);
+        assert!(results[0].is_ok());
+        assert_eq!(child, se.derive_bip85(0, &PASSWORD.into(), application).unwrap());
+    }
+
+    #[test]
+    fn it_should_negotiate_device_info() {
+        let (_, simulator) = secure_element();
+        let packets = Arc::new(Mutex::new(0));
+        let se = SecureElement::new_with_transport(Box::new(Counter {
+            simulator: simulator.clone(),
+            packets: packets.clone(),
+        }));
+        let info = se.get_device_info().unwrap();
+        assert_eq!(info.version.to_string(), FIRMWARE_VERSION);
+        assert_eq!((info.version.major, info.version.minor), (1, 2));
+        assert_eq!(info.serial_number.as_deref(), Some(SERIAL_NUMBER));
+        assert!(info.supports(methods::GET_KEY_TAG));
+        assert!(!info.supports(methods::ATTEST_TAG));
+        se.generate_entropy(EntropyLength::Short(12)).unwrap();
+        assert_eq!(*packets.lock().unwrap(), 2);
+
+        // commands the firmware does not list never reach it
+        let result = se.test_sign(0, "m/44'/60'/0'/0/0".to_string(), vec![0x01; 32].into(), 0, [0u8; 128]);
+        assert!(matches!(result, Err(KSError::UnsupportedCommand(methods::SIGN_TAG))));
+        assert!(matches!(se.attest(vec![0x01; 32]), Err(KSError::UnsupportedCommand(methods::ATTEST_TAG))));
+        assert_eq!(*packets.lock().unwrap(), 2);
+    }
+
+    fn certificate(
+        common_name: &str,
+        key: &openssl::pkey::PKey<openssl::pkey::Private>,
+        issuer: Option<(&openssl::x509::X509, &openssl::pkey::PKey<openssl::pkey::Private>)>,
+    ) -> openssl::x509::X509 {
+        use openssl::asn1::Asn1Time;
+        use openssl::hash::MessageDigest;
+        use openssl::x509::extension::BasicConstraints;
+        use openssl::x509::{X509Builder, X509NameBuilder};
+
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", common_name).unwrap();
+        let name = name.build();
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_pubkey(key).unwrap();
+        builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        builder.set_not_after(&Asn1Time::days_from_now(3650).unwrap()).unwrap();
+        let (issuer_name, signing_key) = match issuer {
+            Some((issuer, issuer_key)) => (issuer.subject_name(), issuer_key),
+            None => {
+                builder.append_extension(BasicConstraints::new().critical().ca().build().unwrap()).unwrap();
+                (name.as_ref(), key)
+            }
+        };
+        builder.set_issuer_name(issuer_name).unwrap();
+        builder.sign(signing_key, MessageDigest::sha256()).unwrap();
+        builder.build()
+    }
+
+    fn ec_key() -> openssl::pkey::PKey<openssl::pkey::Private> {
+        let group = openssl::ec::EcGroup::from_curve_name(openssl::nid::Nid::X9_62_PRIME256V1).unwrap();
+        openssl::pkey::PKey::from_ec_key(openssl::ec::EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn it_should_attest_device_key() {
+        let (se, simulator) = secure_element();
+        let root_key = ec_key();
+        let root = certificate("vendor root", &root_key, None);
+        let device_key = ec_key();
+        let device = certificate(SERIAL_NUMBER, &device_key, Some((&root, &root_key)));
+        let device_secret = device_key.ec_key().unwrap().private_key().to_vec_padded(32).unwrap();
+        simulator
+            .provision_attestation(&device_secret, vec![device.to_der().unwrap()])
+            .unwrap();
+
+        let challenge = vec![0x5a; 32];
+        assert!(matches!(se.attest(vec![0x5a; 8]), Err(KSError::AttestationError(_))));
+        let attestation = se.attest(challenge.clone()).unwrap();
+        let vendor_root = root.to_der().unwrap();
+        attestation.verify(&challenge, SERIAL_NUMBER, &vendor_root).unwrap();
+        assert!(attestation.verify(&[0x5b; 32], SERIAL_NUMBER, &vendor_root).is_err());
+
+        let foreign_key = ec_key();
+        let foreign_root = certificate("vendor root", &foreign_key, None).to_der().unwrap();
+        assert!(matches!(attestation.verify(&challenge, SERIAL_NUMBER, &foreign_root), Err(KSError::AttestationError(_))));
+
+        let mut tampered = attestation.clone();
+        tampered.challenge[0] ^= 0x01;
+        assert!(tampered.verify(&tampered.challenge.clone(), SERIAL_NUMBER, &vendor_root).is_err());
+
+        // a valid certificate of another device
+        let other = certificate("KS0000000002", &device_key, Some((&root, &root_key)));
+        let mut other_device = attestation.clone();
+        other_device.certificate_chain = vec![other.to_der().unwrap()];
+        assert!(other_device.verify(&challenge, SERIAL_NUMBER, &vendor_root).is_err());
+        // the serial number the device reports is not trusted
+        other_device.serial_number = None;
+        assert!(other_device.verify(&challenge, SERIAL_NUMBER, &vendor_root).is_err());
+        assert!(attestation.verify(&challenge, "KS0000000002", &vendor_root).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use super::tags::{result, status, COMMAND_TAG, RESPONSE_TAG};
+use super::tvl::Packet;
+use crate::error::KSError;
+
+// decoded RESPONSE_TAG status word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeStatus {
+    Success,
+    UnsupportedCommand,
+    InvalidParameter,
+    WrongPassword,
+    InvalidAuthToken,
+    WalletNotFound,
+    SecretNotSet,
+    SecureChannelRequired,
+    SecureChannelUnsupported,
+    ChipBusy,
+    WalletLocked,
+    Unknown(u16),
+}
+
+impl From<u16> for SeStatus {
+    fn from(value: u16) -> Self {
+        match value {
+            status::SUCCESS => SeStatus::Success,
+            status::UNSUPPORTED_COMMAND => SeStatus::UnsupportedCommand,
+            status::INVALID_PARAMETER => SeStatus::InvalidParameter,
+            status::WRONG_PASSWORD => SeStatus::WrongPassword,
+            status::INVALID_AUTH_TOKEN => SeStatus::InvalidAuthToken,
+            status::WALLET_NOT_FOUND => SeStatus::WalletNotFound,
+            status::SECRET_NOT_SET => SeStatus::SecretNotSet,
+            status::SECURE_CHANNEL_REQUIRED => SeStatus::SecureChannelRequired,
+            status::SECURE_CHANNEL_UNSUPPORTED => SeStatus::SecureChannelUnsupported,
+            status::CHIP_BUSY => SeStatus::ChipBusy,
+            status::WALLET_LOCKED => SeStatus::WalletLocked,
+            other => SeStatus::Unknown(other),
+        }
+    }
+}
+
+impl SeStatus {
+    pub(crate) fn from_packet(packet: &Packet) -> Result<Self, KSError> {
+        let value = packet
+            .payloads
+            .get(&RESPONSE_TAG)
+            .filter(|tvl| !tvl.value.is_empty() && tvl.value.len() <= 2)
+            .ok_or(KSError::SEError("response status is missing".to_string()))?;
+        // the chip may send the status word in a single byte
+        let value = value.value.iter().fold(0u16, |status, byte| status << 8 | *byte as u16);
+        Ok(SeStatus::from(value))
+    }
+
+    pub(crate) fn into_error(self, packet: &Packet, command: u16) -> KSError {
+        match self {
+            SeStatus::WrongPassword => KSError::WrongPassword {
+                remaining_attempts: packet
+                    .payloads
+                    .get(&result::REMAINING_PASSWORD_ATTEMPTS)
+                    .and_then(|tvl| tvl.value.first().copied()),
+            },
+            SeStatus::WalletLocked => KSError::WalletLocked,
+            SeStatus::WalletNotFound => KSError::WalletNotFound,
+            SeStatus::SecretNotSet => KSError::SecretNotSet,
+            SeStatus::InvalidAuthToken => KSError::InvalidAuthToken,
+            SeStatus::UnsupportedCommand => KSError::UnsupportedCommand(command),
+            SeStatus::ChipBusy => KSError::ChipBusy,
+            other => KSError::SEStatusError(other),
+        }
+    }
+}
+
+// checks the response answers the command and carries a success status
+pub(crate) fn check_response(packet: &Packet, command: u16) -> Result<(), KSError> {
+    let status = SeStatus::from_packet(packet)?;
+    if status != SeStatus::Success {
+        return Err(status.into_error(packet, command));
+    }
+    let echoed = packet
+        .payloads
+        .get(&COMMAND_TAG)
+        .filter(|tvl| tvl.value.len() == 2)
+        .map(|tvl| u16::from_be_bytes([tvl.value[0], tvl.value[1]]));
+    match echoed {
+        Some(echoed) if echoed == command => Ok(()),
+        _ => Err(KSError::SEError("response does not match the command".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymaster::se::command::PacketBuilder;
+    use crate::keymaster::se::tags::methods;
+
+    fn response(command: Option<u16>, status: &[u8], fields: &[(u16, &[u8])]) -> Packet {
+        let mut builder = PacketBuilder::new();
+        if let Some(command) = command {
+            builder.add_command_id(command);
+        }
+        builder.add_payload(RESPONSE_TAG, status);
+        fields.iter().for_each(|(tag, value)| builder.add_payload(*tag, value));
+        builder.build()
+    }
+
+    #[test]
+    fn it_should_decode_status_words() {
+        let cases = [
+            (status::WRONG_PASSWORD, SeStatus::WrongPassword),
+            (status::WALLET_LOCKED, SeStatus::WalletLocked),
+            (status::CHIP_BUSY, SeStatus::ChipBusy),
+            (0x6a80, SeStatus::Unknown(0x6a80)),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(SeStatus::from(value), expected);
+        }
+        let packet = response(None, &[0x06], &[]);
+        assert_eq!(SeStatus::from_packet(&packet).unwrap(), SeStatus::SecretNotSet);
+        assert!(SeStatus::from_packet(&response(None, &[], &[])).is_err());
+    }
+
+    #[test]
+    fn it_should_map_status_to_errors() {
+        let command = methods::VERIFY_USER_PASSWORD;
+        let packet = response(Some(command), &[0x00, 0x03], &[(result::REMAINING_PASSWORD_ATTEMPTS, &[2])]);
+        let error = check_response(&packet, command).unwrap_err();
+        assert!(matches!(error, KSError::WrongPassword { remaining_attempts: Some(2) }));
+        assert_eq!(error.to_string(), "WrongPassword: 2 attempts left");
+        let packet = response(Some(command), &[0x00, 0x03], &[]);
+        assert_eq!(check_response(&packet, command).unwrap_err().to_string(), "WrongPassword: unknown attempts left");
+
+        let packet = response(Some(methods::SIGN_TAG), &[0x00, 0x01], &[]);
+        assert_eq!(check_response(&packet, methods::SIGN_TAG).unwrap_err().to_string(), "UnsupportedCommand: 0x0307");
+        let packet = response(Some(command), &[0x00, 0x09], &[]);
+        assert!(matches!(check_response(&packet, command), Err(KSError::ChipBusy)));
+        let packet = response(None, &[0x00, 0x07], &[]);
+        assert!(matches!(
+            check_response(&packet, command),
+            Err(KSError::SEStatusError(SeStatus::SecureChannelRequired))
+        ));
+        let packet = response(Some(methods::GET_KEY_TAG), &[0x00, 0x00], &[]);
+        assert!(check_response(&packet, command).is_err());
+        let packet = response(Some(command), &[0x00, 0x00], &[]);
+        assert!(check_response(&packet, command).is_ok());
+    }
+}
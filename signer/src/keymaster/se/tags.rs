@@ -1,8 +1,43 @@
 pub const COMMAND_TAG: u16 = 0x0001;
 pub const RESPONSE_TAG:u16 = 0x0002;
+// u16 index, u16 count and the data of one fragment of a message too large for a single frame
+pub const FRAGMENT_TAG: u16 = 0x0B01;
+
+// status word carried in RESPONSE_TAG
+pub mod status {
+    pub const SUCCESS: u16 = 0x0000;
+    pub const UNSUPPORTED_COMMAND: u16 = 0x0001;
+    pub const INVALID_PARAMETER: u16 = 0x0002;
+    pub const WRONG_PASSWORD: u16 = 0x0003;
+    pub const INVALID_AUTH_TOKEN: u16 = 0x0004;
+    pub const WALLET_NOT_FOUND: u16 = 0x0005;
+    pub const SECRET_NOT_SET: u16 = 0x0006;
+    pub const SECURE_CHANNEL_REQUIRED: u16 = 0x0007;
+    pub const SECURE_CHANNEL_UNSUPPORTED: u16 = 0x0008;
+    pub const CHIP_BUSY: u16 = 0x0009;
+    // too many wrong passwords
+    pub const WALLET_LOCKED: u16 = 0x000A;
+}
+
+// packet encryption_flag values
+pub mod encryption {
+    pub const PLAIN: u8 = 0x00;
+    pub const HANDSHAKE: u8 = 0x01;
+    pub const ENCRYPTED: u8 = 0x02;
+}
+
+pub mod channel {
+    pub const EPHEMERAL_PUBLIC_KEY_TAG: u16 = 0x0A01;
+    pub const SESSION_NONCE_TAG: u16 = 0x0A02;
+    pub const KEY_CONFIRMATION_TAG: u16 = 0x0A03;
+    pub const CIPHERTEXT_TAG: u16 = 0x0A04;
+}
 
 pub mod methods {
     pub const GET_FIRMWARE_STATUS_TAG: u16 = 0x0102;
+    pub const ATTEST_TAG: u16 = 0x0103;
+    // host challenge the device key signs
+    pub const CHALLENGE_TAG: u16 = 0x0C01;
     pub const GET_RANDOM_ENTROPY_TAG: u16 = 0x0301;
     pub const ENTROPY_TYPE_TAG: u16 = 0x0201;
     pub const ENTROPY_CHECKSUM_TAG: u16 = 0x030b;
@@ -40,6 +75,12 @@ pub mod result {
     // 
 
     pub const FIRMWARE_APP_VERSION: u16 = 0x0106;
+    pub const SERIAL_NUMBER: u16 = 0x0107;
+    // u16 big endian command ids, missing on firmware older than the attestation support
+    pub const SUPPORTED_COMMANDS: u16 = 0x0108;
+    pub const ATTESTATION_SIGNATURE: u16 = 0x0C02;
+    // u16 big endian length prefixed DER certificates, device certificate first
+    pub const DEVICE_CERTIFICATE_CHAIN: u16 = 0x0C03;
     pub const ENTROPY: u16 = 0x0202;
     pub const EXT_KET: u16 = 0x020a;
     pub const EXT_MASTER_SEED: u16 = 0x0211;
@@ -48,6 +89,9 @@ pub mod result {
     pub const EXT_ADA_ROOT_KEY: u16 = 0x021B;
     // pub const `KEY`: u16 = 0x0302;
     pub const AUTH_TOKEN: u16 = 0x0404;
+    // u8, sent along a wrong password status
+    pub const REMAINING_PASSWORD_ATTEMPTS: u16 = 0x0407;
+    #[cfg(any(test, feature = "simulator"))]
     pub const SUCCESS: u16 = 0x0000;
 }
 
@@ -0,0 +1,80 @@
+use crate::error::KSError;
+
+// carries one request packet to the SE and returns the response packet, the request is borrowed so the caller
+// keeps the buffer it wipes
+pub trait Transport {
+    fn send_data(&self, data: &[u8]) -> Result<Vec<u8>, KSError>;
+}
+
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    fn send_data(&self, data: &[u8]) -> Result<Vec<u8>, KSError> {
+        (**self).send_data(data)
+    }
+}
+
+#[cfg(unix)]
+pub struct UnixSocketTransport {
+    path: String,
+    timeout_ms: u64,
+}
+
+#[cfg(unix)]
+impl UnixSocketTransport {
+    pub fn new(path: String, timeout_ms: u64) -> Self {
+        UnixSocketTransport { path, timeout_ms }
+    }
+}
+
+#[cfg(unix)]
+impl Transport for UnixSocketTransport {
+    fn send_data(&self, data: &[u8]) -> Result<Vec<u8>, KSError> {
+        use super::framing::{map_io_error, read_message, write_message};
+        use std::os::unix::net::UnixStream;
+        use std::time::Duration;
+
+        let mut stream = UnixStream::connect(&self.path)
+            .map_err(|e| KSError::SerialManagerError(format!("Fail open socket: {}", e)))?;
+        let timeout = Some(Duration::from_millis(self.timeout_ms));
+        stream
+            .set_read_timeout(timeout)
+            .and_then(|_| stream.set_write_timeout(timeout))
+            .map_err(|e| KSError::SerialManagerError(e.to_string()))?;
+        write_message(&mut stream, data)
+            .map_err(|_| KSError::SerialManagerError("Fail write socket error".to_string()))?;
+        read_message(&mut stream).map_err(map_io_error)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::keymaster::se::framing::read_message;
+    use std::io::Write;
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+
+    #[test]
+    fn it_should_send_packet_over_unix_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("se.sock").to_str().unwrap().to_string();
+        let listener = UnixListener::bind(&path).unwrap();
+        let response = hex::decode("0200000e0003000465727221000200020202034c").unwrap();
+        let expected = response.clone();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = read_message(&mut stream).unwrap();
+            stream.write_all(&response).unwrap();
+            request
+        });
+        let transport = UnixSocketTransport::new(path, 1000);
+        let request = vec![0x02, 0x00, 0x00, 0x06, 0x00, 0x01, 0x00, 0x02, 0x01, 0x02, 0x03, 0x07];
+        assert_eq!(transport.send_data(&request).unwrap(), expected);
+        assert_eq!(server.join().unwrap(), request);
+    }
+
+    #[test]
+    fn it_should_fail_without_listener() {
+        let transport = UnixSocketTransport::new("/nonexistent/se.sock".to_string(), 1000);
+        assert!(transport.send_data(&[0x02, 0x00, 0x00, 0x00, 0x03, 0x01]).is_err());
+    }
+}
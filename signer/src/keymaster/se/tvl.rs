@@ -1,8 +1,17 @@
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{Buf, BufMut};
 use indexmap::IndexMap;
 use std::convert::{TryFrom, TryInto};
+use zeroize::Zeroizing;
 
+use super::tags::encryption;
 use crate::error::KSError;
+
+// stx, encryption flag, u16 length, then etx and lrc after the payloads
+const FRAME_OVERHEAD: usize = 6;
+// u16 tag and u16 length before every value
+const TVL_HEADER_LENGTH: usize = 4;
+
+// requests and responses carry passwords, tokens and keys, so every buffer holding them is wiped on drop
 #[derive(Debug)]
 pub struct Packet {
     pub stx: u8,
@@ -15,16 +24,20 @@ pub struct Packet {
 
 impl Packet {
     pub fn new(payloads: IndexMap<u16, TVL>) -> Self {
+        Self::new_with_encryption_flag(payloads, encryption::PLAIN)
+    }
+
+    pub fn new_with_encryption_flag(payloads: IndexMap<u16, TVL>, encryption_flag: u8) -> Self {
         let len: u16 = payloads.iter().fold(0, |len, (_, each_value)| {
-            len + each_value.to_vec().len() as u16
+            len + (TVL_HEADER_LENGTH + each_value.value.len()) as u16
         });
 
-        let bytes = Self::combine_bytes(2, 0, len, &payloads, 3);
-        let lrc_bit = lrc(&bytes);
+        let bytes = Self::combine_bytes(2, encryption_flag, len, &payloads, 3);
+        let lrc_bit = lrc(bytes.iter());
 
         Self {
             stx: 2,
-            encryption_flag: 0,
+            encryption_flag,
             length: len,
             payloads,
             etx: 3,
@@ -32,7 +45,7 @@ impl Packet {
         }
     }
 
-    pub fn to_vec(&self) -> Vec<u8> {
+    pub fn to_vec(&self) -> Zeroizing<Vec<u8>> {
         let mut bytes = Self::combine_bytes(
             self.stx,
             self.encryption_flag,
@@ -40,26 +53,26 @@ impl Packet {
             &self.payloads,
             self.etx,
         );
-        let lrc_bit = lrc(&bytes);
+        let lrc_bit = lrc(bytes.iter());
         bytes.put_u8(lrc_bit);
-        bytes.freeze().to_vec()
+        bytes
     }
 
+    // allocated once at the final size, so no reallocation leaves a copy behind
     fn combine_bytes(
         stx: u8,
         encryption_flag: u8,
         length: u16,
         payloads: &IndexMap<u16, TVL>,
         etx: u8,
-    ) -> BytesMut {
-        let mut mm = BytesMut::new();
+    ) -> Zeroizing<Vec<u8>> {
+        let mut mm = Zeroizing::new(Vec::with_capacity(FRAME_OVERHEAD + length as usize));
         mm.put_u8(stx);
         mm.put_u8(encryption_flag);
         mm.put_u16(length);
 
         for (_key, value) in payloads.iter() {
-            let tvl_vec: Vec<u8> = value.to_vec();
-            mm.put_slice(tvl_vec.as_slice());
+            value.write_to(&mut mm);
         }
         mm.put_u8(etx);
         mm
@@ -70,11 +83,20 @@ impl TryFrom<Vec<u8>> for Packet {
     type Error = KSError;
 
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
-        let caculate_lrc = lrc(&value);
-        let mut mm = Bytes::from(value);
+        let value = Zeroizing::new(value);
+        Self::try_from(value.as_slice())
+    }
+}
+
+impl TryFrom<&[u8]> for Packet {
+    type Error = KSError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let caculate_lrc = lrc(value);
+        let mut mm = value;
         let all_length = mm.len();
 
-        if all_length < 6 {
+        if all_length < FRAME_OVERHEAD {
             return Err(Self::Error::TVLError(
                 "vector length is small than 6".to_string(),
             ));
@@ -94,9 +116,17 @@ impl TryFrom<Vec<u8>> for Packet {
         let mut payloads = IndexMap::new();
 
         while mm.remaining() > 2 {
+            if mm.remaining() < TVL_HEADER_LENGTH + 2 {
+                return Err(Self::Error::TVLError("value length is not fit".to_string()));
+            }
             let tag = mm.get_u16();
             let length = mm.get_u16();
-            let value = mm.copy_to_bytes(length.into());
+            let value = mm
+                .get(..length.into())
+                .filter(|_| mm.remaining() >= length as usize + 2)
+                .ok_or(Self::Error::TVLError("value length is not fit".to_string()))?
+                .to_vec();
+            mm.advance(length.into());
             payloads.insert(tag, TVL::new(tag, length, value));
         }
 
@@ -125,20 +155,18 @@ impl TryFrom<Vec<u8>> for Packet {
 pub struct TVL {
     pub tag: u16,
     pub length: u16,
-    pub value: Bytes,
+    pub value: Zeroizing<Vec<u8>>,
 }
 
 impl TVL {
-    pub fn new(tag: u16, length: u16, value: Bytes) -> TVL {
-        Self { tag, length, value }
+    pub fn new(tag: u16, length: u16, value: Vec<u8>) -> TVL {
+        Self { tag, length, value: Zeroizing::new(value) }
     }
 
-    pub fn to_vec(&self) -> Vec<u8> {
-        let mut mm = BytesMut::new();
+    fn write_to(&self, mm: &mut Vec<u8>) {
         mm.put_u16(self.tag);
         mm.put_u16(self.length);
-        mm.put_slice(self.value.chunk());
-        Vec::from(mm.chunk())
+        mm.put_slice(&self.value);
     }
 }
 
@@ -146,20 +174,16 @@ impl TryFrom<Vec<u8>> for TVL {
     type Error = KSError;
 
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
-        let mut mm = Bytes::from(value);
+        let value = Zeroizing::new(value);
+        let mut mm = value.as_slice();
 
-        if mm.len() < 4 {
+        if mm.len() < TVL_HEADER_LENGTH {
             return Err(Self::Error::TVLError("value length is not fit".to_string()));
         }
 
         let tag = mm.get_u16();
         let length = mm.get_u16();
-        let value = mm.copy_to_bytes(mm.chunk().len());
-
-        if mm.has_remaining() {
-            return Err(Self::Error::TVLError("".to_string()));
-        }
-        Ok(Self { tag, length, value })
+        Ok(Self::new(tag, length, mm.to_vec()))
     }
 }
 
@@ -193,7 +217,7 @@ mod tests {
         payloads.insert(1, tvl);
 
         let packet = Packet::new(payloads);
-        assert_eq!(packet.to_vec(), vec![02, 00, 00, 06, 00, 01, 00, 02, 01, 02, 03, 07]);
+        assert_eq!(*packet.to_vec(), vec![02, 00, 00, 06, 00, 01, 00, 02, 01, 02, 03, 07]);
     }
 
     #[test]
@@ -202,7 +226,9 @@ mod tests {
         let tvl = TVL::try_from(version_sig).unwrap();
         assert_eq!(tvl.tag, 1);
         assert_eq!(&tvl.value[..], &[1, 2]);
-        assert_eq!(tvl.to_vec(), vec![00, 01, 00, 02, 01, 02])
+        let mut bytes = vec![];
+        tvl.write_to(&mut bytes);
+        assert_eq!(bytes, vec![00, 01, 00, 02, 01, 02])
     }
 
     #[test]
@@ -218,12 +244,12 @@ mod tests {
         let packet = Packet::try_from(response).unwrap();
         let tvl = packet.payloads.get(&3).unwrap();
 
-        assert_eq!(tvl.value, &b"err!"[..]);
+        assert_eq!(*tvl.value, &b"err!"[..]);
         let tvl = packet.payloads.get(&2).unwrap();
-        assert_eq!(tvl.value, &b"\x02\x02"[..]);
+        assert_eq!(*tvl.value, &b"\x02\x02"[..]);
         let restore = packet.to_vec();
         assert_eq!(
-            restore,
+            *restore,
             hex::decode("0200000e0003000465727221000200020202034c").unwrap()
         );
     }
@@ -0,0 +1,526 @@
+use super::{BatchSignatures, EntropyLength, KeyMaster, PublicKeyFormat, SignRequest, SigningAlgorithm};
+use crate::algorithm::bip32_ed25519::AdaMasterKeyScheme;
+use crate::algorithm::bip85::Bip85Application;
+use super::se::device::{DeviceAttestation, DeviceInfo};
+use crate::algorithm::SecretKey;
+use crate::secret::{SecretBytes, SecretPassword};
+use crate::error::KSError;
+use crate::{algorithm, SigningOption};
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{Aes256Gcm, Nonce};
+use bip39::Mnemonic;
+use bitcoin::bip32::{DerivationPath, ExtendedPrivKey, ExtendedPubKey};
+use bitcoin::secp256k1::Secp256k1;
+use bitcoin::Network;
+use ed25519_bip32_core::XPrv;
+use k256::ecdsa::SigningKey;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use zeroize::Zeroizing;
+
+const KEYSTORE_VERSION: u8 = 1;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+// scrypt n = 2^15, r = 8, p = 1
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+// secrets of one mnemonic, stored encrypted as version || salt || nonce || aes-256-gcm ciphertext
+struct Vault {
+    entropy: Zeroizing<Vec<u8>>,
+    seed: Zeroizing<Vec<u8>>,
+    ada_root_key: Option<Zeroizing<Vec<u8>>>,
+    rsa_secret: Option<Zeroizing<Vec<u8>>>,
+}
+
+impl Vault {
+    // every field is prefixed with its u32 big endian length, an empty field is None
+    fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let empty = Zeroizing::new(vec![]);
+        let fields = [
+            &self.entropy,
+            &self.seed,
+            self.ada_root_key.as_ref().unwrap_or(&empty),
+            self.rsa_secret.as_ref().unwrap_or(&empty),
+        ];
+        let mut bytes = Zeroizing::new(vec![]);
+        for field in fields {
+            bytes.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(field);
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, KSError> {
+        let mut fields = vec![];
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            if rest.len() < 4 {
+                return Err(KSError::KeyStoreError("malformed keystore".to_string()));
+            }
+            let (length, value) = rest.split_at(4);
+            let length = u32::from_be_bytes([length[0], length[1], length[2], length[3]]) as usize;
+            if value.len() < length {
+                return Err(KSError::KeyStoreError("malformed keystore".to_string()));
+            }
+            fields.push(Zeroizing::new(value[..length].to_vec()));
+            rest = &value[length..];
+        }
+        if fields.len() != 4 {
+            return Err(KSError::KeyStoreError("malformed keystore".to_string()));
+        }
+        let optional = |field: Zeroizing<Vec<u8>>| if field.is_empty() { None } else { Some(field) };
+        let mut fields = fields.into_iter();
+        Ok(Self {
+            entropy: fields.next().unwrap_or_default(),
+            seed: fields.next().unwrap_or_default(),
+            ada_root_key: fields.next().and_then(optional),
+            rsa_secret: fields.next().and_then(optional),
+        })
+    }
+}
+
+// software key master backed by password encrypted keystore files, one file per mnemonic id
+pub struct Software {
+    keystore_dir: PathBuf,
+}
+
+impl Software {
+    pub fn new(keystore_dir: String) -> Self {
+        Software {
+            keystore_dir: PathBuf::from(keystore_dir),
+        }
+    }
+
+    pub fn import_mnemonic(
+        &self,
+        mnemonic_id: u8,
+        mnemonic: Zeroizing<String>,
+        passphrase: &SecretPassword,
+        password: &SecretPassword,
+    ) -> Result<bool, KSError> {
+        // the language is detected from the words
+        let mnemonic = Mnemonic::parse(mnemonic.as_str())
+            .map_err(|e| KSError::KeyStoreError(e.to_string()))?;
+        let vault = Vault {
+            entropy: Zeroizing::new(mnemonic.to_entropy()),
+            seed: Zeroizing::new(mnemonic.to_seed(passphrase.expose()).to_vec()),
+            ada_root_key: None,
+            rsa_secret: None,
+        };
+        fs::create_dir_all(&self.keystore_dir)
+            .map_err(|e| KSError::KeyStoreError(e.to_string()))?;
+        self.store_vault(mnemonic_id, password, &vault)?;
+        Ok(true)
+    }
+
+    fn keystore_path(&self, mnemonic_id: u8) -> PathBuf {
+        self.keystore_dir.join(format!("{}.keystore", mnemonic_id))
+    }
+
+    fn derive_key(password: &SecretPassword, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, KSError> {
+        let params = scrypt::Params::new(SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P, 32)
+            .map_err(|e| KSError::KeyStoreError(e.to_string()))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        scrypt::scrypt(password.expose().as_bytes(), salt, &params, key.as_mut())
+            .map_err(|e| KSError::KeyStoreError(e.to_string()))?;
+        Ok(key)
+    }
+
+    fn load_vault(&self, mnemonic_id: u8, password: &SecretPassword) -> Result<Vault, KSError> {
+        let content = fs::read(self.keystore_path(mnemonic_id))
+            .map_err(|e| KSError::KeyStoreError(e.to_string()))?;
+        if content.len() < 1 + SALT_LENGTH + NONCE_LENGTH || content[0] != KEYSTORE_VERSION {
+            return Err(KSError::KeyStoreError("unsupported keystore".to_string()));
+        }
+        let (salt, rest) = content[1..].split_at(SALT_LENGTH);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LENGTH);
+        let key = Self::derive_key(password, salt)?;
+        let cipher = Aes256Gcm::new_from_slice(key.as_ref())
+            .map_err(|e| KSError::KeyStoreError(e.to_string()))?;
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: &[KEYSTORE_VERSION, mnemonic_id] })
+            .map_err(|_| KSError::KeyStoreError("invalid password".to_string()))?;
+        Vault::from_bytes(&Zeroizing::new(plaintext))
+    }
+
+    // a fresh salt and nonce is used on every write, the file is replaced atomically
+    fn store_vault(&self, mnemonic_id: u8, password: &SecretPassword, vault: &Vault) -> Result<(), KSError> {
+        let mut salt = [0u8; SALT_LENGTH];
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+        let key = Self::derive_key(password, &salt)?;
+        let cipher = Aes256Gcm::new_from_slice(key.as_ref())
+            .map_err(|e| KSError::KeyStoreError(e.to_string()))?;
+        let plaintext = vault.to_bytes();
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: &[KEYSTORE_VERSION, mnemonic_id] })
+            .map_err(|e| KSError::KeyStoreError(e.to_string()))?;
+        let mut content = vec![KEYSTORE_VERSION];
+        content.extend_from_slice(&salt);
+        content.extend_from_slice(&nonce);
+        content.extend_from_slice(&ciphertext);
+        let path = self.keystore_path(mnemonic_id);
+        let temp_path = path.with_extension("keystore.tmp");
+        fs::write(&temp_path, content).map_err(|e| KSError::KeyStoreError(e.to_string()))?;
+        fs::rename(&temp_path, &path).map_err(|e| KSError::KeyStoreError(e.to_string()))
+    }
+
+    fn get_rsa_secret(&self, mnemonic_id: u8, password: &SecretPassword) -> Result<Zeroizing<Vec<u8>>, KSError> {
+        let mut vault = self.load_vault(mnemonic_id, password)?;
+        self.get_rsa_secret_from_vault(mnemonic_id, password, &mut vault)
+    }
+
+    // the rsa key generation is slow, the secret is cached in the keystore after the first use
+    fn get_rsa_secret_from_vault(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        vault: &mut Vault,
+    ) -> Result<Zeroizing<Vec<u8>>, KSError> {
+        if let Some(secret) = &vault.rsa_secret {
+            return Ok(secret.clone());
+        }
+        let secret = algorithm::rsa::RSA::from_seed(vault.seed.as_slice())?.into_zeroizing();
+        vault.rsa_secret = Some(secret.clone());
+        self.store_vault(mnemonic_id, password, vault)?;
+        Ok(secret)
+    }
+
+    fn sign_with_vault(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        vault: &mut Vault,
+        request: SignRequest,
+    ) -> Result<Vec<u8>, KSError> {
+        let SignRequest { data, algo, path: derivation_path, option: signing_option } = request;
+        match algo {
+            SigningAlgorithm::Secp256k1 => {
+                let xprv = Self::derive_secp256k1(&vault.seed, &derivation_path)
+                    .map_err(KSError::SignDataError)?;
+                let private_key = Zeroizing::new(xprv.private_key.secret_bytes());
+                if let Some(SigningOption::Schnorr { .. }) = signing_option {
+                    let schnorr = algorithm::schnorr::Schnorr::from_private_key(private_key.as_ref())?;
+                    return schnorr.sign(data, signing_option);
+                }
+                let secp256k1 = SigningKey::from_bytes(private_key.as_ref())
+                    .map_err(|_e| KSError::GenerateSigningKeyError("secp256k1".to_string()))?;
+                secp256k1.sign(data, signing_option)
+            }
+            SigningAlgorithm::Secp256R1 => {
+                let xprv = algorithm::secp256r1::get_extended_private_key(derivation_path, &vault.seed)
+                    .map_err(KSError::SignDataError)?;
+                xprv.signing_key()?.sign(data, signing_option)
+            }
+            SigningAlgorithm::Ed25519 => {
+                let signature = match signing_option {
+                    Some(SigningOption::ADA) => {
+                        let root_xprv = Self::get_ada_root_key_from_vault(vault)?;
+                        algorithm::bip32_ed25519::sign_message(&data, derivation_path, root_xprv.as_ref())
+                    }
+                    _ => algorithm::slip10_ed25519::sign_message(&data, derivation_path, &vault.seed),
+                }
+                .map_err(KSError::SignDataError)?;
+                Ok(signature.to_vec())
+            }
+            SigningAlgorithm::SR25519 => {
+                let signature = algorithm::sr25519::sign_message(&data, derivation_path, &vault.entropy)
+                    .map_err(KSError::SignDataError)?;
+                Ok(signature.to_vec())
+            }
+            SigningAlgorithm::RSA => {
+                let secret = self.get_rsa_secret_from_vault(mnemonic_id, password, vault)?;
+                let rsa = algorithm::rsa::RSA::from_secret(secret.as_slice())?;
+                match signing_option {
+                    Some(SigningOption::RSA { salt_len }) => rsa.sign(data, Some(SigningOption::RSA { salt_len })),
+                    _ => Err(KSError::RSASignError),
+                }
+            }
+        }
+    }
+
+    fn get_ada_root_key_from_vault(vault: &Vault) -> Result<XPrv, KSError> {
+        let ada_root_key = vault
+            .ada_root_key
+            .as_ref()
+            .ok_or(KSError::KeyStoreError("ada root key is not set up".to_string()))?;
+        XPrv::from_slice_verified(ada_root_key).map_err(|e| KSError::KeyStoreError(e.to_string()))
+    }
+
+    fn derive_secp256k1(seed: &[u8], derivation_path: &str) -> Result<ExtendedPrivKey, String> {
+        let secp = Secp256k1::new();
+        let path = DerivationPath::from_str(&algorithm::bip32_ed25519::normalize_path(&derivation_path.to_string()))
+            .map_err(|e| e.to_string())?;
+        ExtendedPrivKey::new_master(Network::Bitcoin, seed)
+            .and_then(|master| master.derive_priv(&secp, &path))
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl KeyMaster for Software {
+    fn generate_entropy(&self, length: EntropyLength) -> Result<SecretBytes, KSError> {
+        let mut entropy = SecretBytes::new(vec![0u8; length.byte_length()?]);
+        OsRng.fill_bytes(entropy.expose_mut());
+        Ok(entropy)
+    }
+
+    fn setup_ada_root_key(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        passphrase: &SecretPassword,
+        scheme: AdaMasterKeyScheme,
+    ) -> Result<bool, KSError> {
+        let mut vault = self.load_vault(mnemonic_id, password)?;
+        let root_key = algorithm::bip32_ed25519::get_master_key(&vault.entropy, passphrase, scheme)
+            .map_err(KSError::GenerateSigningKeyError)?;
+        vault.ada_root_key = Some(Zeroizing::new(root_key.as_ref().to_vec()));
+        self.store_vault(mnemonic_id, password, &vault)?;
+        Ok(true)
+    }
+
+    fn get_ada_extended_public_key(&self, mnemonic_id: u8, password: &SecretPassword, path: String) -> Result<String, KSError> {
+        let vault = self.load_vault(mnemonic_id, password)?;
+        let root_xprv = Self::get_ada_root_key_from_vault(&vault)?;
+        let xpub = algorithm::bip32_ed25519::get_extended_public_key(path, root_xprv)
+            .map_err(KSError::GetPublicKeyError)?;
+        Ok(xpub.to_string())
+    }
+
+    fn get_rsa_public_key(&self, mnemonic_id: u8, password: &SecretPassword) -> Result<Vec<u8>, KSError> {
+        let secret = self.get_rsa_secret(mnemonic_id, password)?;
+        let rsa = algorithm::rsa::RSA::from_secret(secret.as_slice())?;
+        Ok(rsa.keypair_modulus())
+    }
+
+    fn get_ada_root_key(&self, mnemonic_id: u8, password: &SecretPassword) -> Result<SecretBytes, KSError> {
+        let vault = self.load_vault(mnemonic_id, password)?;
+        Ok(SecretBytes::from_slice(Self::get_ada_root_key_from_vault(&vault)?.as_ref()))
+    }
+
+    fn set_ada_root_key(&self, mnemonic_id: u8, password: &SecretPassword, secret: SecretBytes) -> Result<bool, KSError> {
+        XPrv::from_slice_verified(secret.expose()).map_err(|e| KSError::WriteSecretError(e.to_string()))?;
+        let mut vault = self.load_vault(mnemonic_id, password)?;
+        vault.ada_root_key = Some(secret.into_zeroizing());
+        self.store_vault(mnemonic_id, password, &vault)?;
+        Ok(true)
+    }
+
+    fn sign_data(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        data: Vec<u8>,
+        algo: SigningAlgorithm,
+        derivation_path: String,
+        signing_option: Option<SigningOption>,
+    ) -> Result<Vec<u8>, KSError> {
+        let mut vault = self.load_vault(mnemonic_id, password)?;
+        let request = SignRequest {
+            data,
+            algo,
+            path: derivation_path,
+            option: signing_option,
+        };
+        self.sign_with_vault(mnemonic_id, password, &mut vault, request)
+    }
+
+    // the keystore is decrypted once for the whole batch
+    fn sign_batch(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        requests: Vec<SignRequest>,
+    ) -> Result<Vec<Result<Vec<u8>, KSError>>, KSError> {
+        let mut vault = self.load_vault(mnemonic_id, password)?;
+        Ok(requests
+            .into_iter()
+            .map(|request| self.sign_with_vault(mnemonic_id, password, &mut vault, request))
+            .collect())
+    }
+
+    fn sign_batch_with_bip85(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        requests: Vec<SignRequest>,
+        application: Bip85Application,
+    ) -> Result<(BatchSignatures, Zeroizing<String>), KSError> {
+        let mut vault = self.load_vault(mnemonic_id, password)?;
+        let child = algorithm::bip85::derive(&vault.seed, application)?;
+        let signatures = requests
+            .into_iter()
+            .map(|request| self.sign_with_vault(mnemonic_id, password, &mut vault, request))
+            .collect();
+        Ok((signatures, child))
+    }
+
+    fn get_public_key(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        algo: SigningAlgorithm,
+        derivation_path: String,
+        format: PublicKeyFormat,
+    ) -> Result<Vec<u8>, KSError> {
+        let vault = self.load_vault(mnemonic_id, password)?;
+        match (algo, format) {
+            (SigningAlgorithm::Secp256k1, _) => {
+                let xprv = Self::derive_secp256k1(&vault.seed, &derivation_path)
+                    .map_err(KSError::GetPublicKeyError)?;
+                let xpub = ExtendedPubKey::from_priv(&Secp256k1::new(), &xprv);
+                algorithm::format_extended_public_key(xpub.to_string().as_bytes(), algo, format)
+            }
+            (SigningAlgorithm::Secp256R1, PublicKeyFormat::Compressed | PublicKeyFormat::Uncompressed) => {
+                let xprv = algorithm::secp256r1::get_extended_private_key(derivation_path, &vault.seed)
+                    .map_err(KSError::GetPublicKeyError)?;
+                let compress = matches!(format, PublicKeyFormat::Compressed);
+                Ok(xprv.signing_key()?.verifying_key().to_encoded_point(compress).as_bytes().to_vec())
+            }
+            (SigningAlgorithm::Ed25519, PublicKeyFormat::Raw) => {
+                let public_key = algorithm::slip10_ed25519::get_public_key(derivation_path, &vault.seed)
+                    .map_err(KSError::GetPublicKeyError)?;
+                Ok(public_key.to_vec())
+            }
+            (SigningAlgorithm::SR25519, PublicKeyFormat::Raw) => {
+                let public_key = algorithm::sr25519::get_public_key(derivation_path, &vault.entropy)
+                    .map_err(KSError::GetPublicKeyError)?;
+                Ok(public_key.to_vec())
+            }
+            (SigningAlgorithm::RSA, _) => Err(KSError::GetPublicKeyError(
+                "use get_rsa_public_key for rsa".to_string(),
+            )),
+            _ => Err(KSError::GetPublicKeyError("public key format is not supported".to_string())),
+        }
+    }
+
+    fn get_master_fingerprint(&self, mnemonic_id: u8, password: &SecretPassword) -> Result<[u8; 4], KSError> {
+        let vault = self.load_vault(mnemonic_id, password)?;
+        let master = ExtendedPrivKey::new_master(Network::Bitcoin, &vault.seed)
+            .map_err(|e| KSError::GetPublicKeyError(e.to_string()))?;
+        Ok(master.fingerprint(&Secp256k1::new()).to_bytes())
+    }
+
+    fn derive_bip85(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        application: Bip85Application,
+    ) -> Result<Zeroizing<String>, KSError> {
+        let vault = self.load_vault(mnemonic_id, password)?;
+        algorithm::bip85::derive(&vault.seed, application)
+    }
+
+    fn get_version(&self) -> Result<Vec<u8>, KSError> {
+        Ok(env!("CARGO_PKG_VERSION").as_bytes().to_vec())
+    }
+
+    // no device behind the software keystore, so no serial number and no SE commands
+    fn get_device_info(&self) -> Result<DeviceInfo, KSError> {
+        Ok(DeviceInfo {
+            version: env!("CARGO_PKG_VERSION").parse()?,
+            serial_number: None,
+            commands: vec![],
+        })
+    }
+
+    fn attest(&self, _challenge: Vec<u8>) -> Result<DeviceAttestation, KSError> {
+        Err(KSError::NoneSupportedCommandError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mnemonic::MnemonicLanguage;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const PASSWORD: &str = "test_pass";
+
+    fn keystore() -> (Software, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let software = Software::new(dir.path().to_str().unwrap().to_string());
+        software
+            .import_mnemonic(0, Zeroizing::new(MNEMONIC.to_string()), &"".into(), &PASSWORD.into())
+            .unwrap();
+        (software, dir)
+    }
+
+    #[test]
+    fn test_import_mnemonic() {
+        let (software, _dir) = keystore();
+        let vault = software.load_vault(0, &PASSWORD.into()).unwrap();
+        assert_eq!(hex::encode(vault.entropy.as_slice()), "00000000000000000000000000000000");
+        assert_eq!(
+            hex::encode(vault.seed.as_slice()),
+            "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4"
+        );
+        assert!(vault.ada_root_key.is_none());
+        assert!(vault.rsa_secret.is_none());
+    }
+
+    #[test]
+    fn test_reject_wrong_password_and_unknown_mnemonic() {
+        let (software, _dir) = keystore();
+        assert!(matches!(software.load_vault(0, &"wrong_pass".into()), Err(KSError::KeyStoreError(_))));
+        assert!(matches!(software.load_vault(1, &PASSWORD.into()), Err(KSError::KeyStoreError(_))));
+        let result = software.import_mnemonic(1, Zeroizing::new("abandon abandon".to_string()), &"".into(), &PASSWORD.into());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_import_mnemonic_in_other_languages() {
+        let (software, _dir) = keystore();
+        let mnemonic = bip39::Mnemonic::from_entropy_in(bip39::Language::Spanish, &[0xff; 16]).unwrap();
+        software
+            .import_mnemonic(1, Zeroizing::new(mnemonic.to_string()), &"".into(), &PASSWORD.into())
+            .unwrap();
+        let vault = software.load_vault(1, &PASSWORD.into()).unwrap();
+        assert_eq!(hex::encode(vault.entropy.as_slice()), "ffffffffffffffffffffffffffffffff");
+    }
+
+    #[test]
+    fn test_passphrase_changes_seed() {
+        let (software, _dir) = keystore();
+        software
+            .import_mnemonic(1, Zeroizing::new(MNEMONIC.to_string()), &"TREZOR".into(), &PASSWORD.into())
+            .unwrap();
+        let fingerprint = software.get_master_fingerprint(0, &PASSWORD.into()).unwrap();
+        let with_passphrase = software.get_master_fingerprint(1, &PASSWORD.into()).unwrap();
+        assert_eq!(hex::encode(fingerprint), "73c5da0a");
+        assert_ne!(fingerprint, with_passphrase);
+    }
+
+    #[test]
+    fn test_setup_ada_root_key() {
+        let (software, _dir) = keystore();
+        let path = "m/1852'/1815'/0'".to_string();
+        assert!(software.get_ada_extended_public_key(0, &PASSWORD.into(), path.clone()).is_err());
+        software.setup_ada_root_key(0, &PASSWORD.into(), &"".into(), AdaMasterKeyScheme::Icarus).unwrap();
+        let root_key = software.get_ada_root_key(0, &PASSWORD.into()).unwrap();
+        let expected = algorithm::bip32_ed25519::get_icarus_master_key(&[0u8; 16], b"");
+        assert_eq!(root_key.expose(), expected.as_ref());
+        let xpub = software.get_ada_extended_public_key(0, &PASSWORD.into(), path.clone()).unwrap();
+        let expected = algorithm::bip32_ed25519::get_extended_public_key(path, expected).unwrap();
+        assert_eq!(xpub, expected.to_string());
+
+        software.setup_ada_root_key(0, &PASSWORD.into(), &"".into(), AdaMasterKeyScheme::Ledger { language: MnemonicLanguage::English }).unwrap();
+        let root_key = software.get_ada_root_key(0, &PASSWORD.into()).unwrap();
+        let expected = algorithm::bip32_ed25519::get_master_key(&[0u8; 16], &"".into(), AdaMasterKeyScheme::Ledger { language: MnemonicLanguage::English }).unwrap();
+        assert_eq!(root_key.expose(), expected.as_ref());
+    }
+
+    #[test]
+    fn test_generate_entropy() {
+        let software = Software::new("".to_string());
+        assert_eq!(software.generate_entropy(EntropyLength::Short(12)).unwrap().len(), 16);
+        assert_eq!(software.generate_entropy(EntropyLength::Long(18)).unwrap().len(), 24);
+        assert_eq!(software.generate_entropy(EntropyLength::Long(24)).unwrap().len(), 32);
+        assert!(software.generate_entropy(EntropyLength::Short(13)).is_err());
+    }
+}
@@ -3,62 +3,256 @@ extern crate core;
 mod error;
 mod keymaster;
 mod algorithm;
+mod mnemonic;
+mod audit;
+mod policy;
+mod secret;
 
-use error::KSError;
-use keymaster::{se::SecureElement, KeyMaster, local::Mini};
+pub use error::KSError;
+// secrets handed out by the signer are wiped on drop
+pub use zeroize::Zeroizing;
+use keymaster::{se::SecureElement, BatchSignatures, KeyMaster};
 pub use keymaster::SigningAlgorithm;
-pub use crate::keymaster::SigningOption;
+pub use crate::keymaster::{
+    EntropyLength, HashAlgorithm, PublicKeyFormat, Secp256k1Encoding, SignRequest, SignatureEncoding, SigningOption, TaprootTweak,
+};
+pub use crate::keymaster::software::Software;
+pub use crate::algorithm::bip32_ed25519::AdaMasterKeyScheme;
+pub use crate::algorithm::bip85::Bip85Application;
+pub use crate::policy::SigningPolicy;
+pub use crate::secret::{SecretBytes, SecretPassword};
+pub use crate::audit::{AuditLog, AuditRecord};
+pub use crate::audit::storage::{AuditStorage, FileAuditStorage, MemoryAuditStorage};
+pub use crate::mnemonic::{entropy_to_mnemonic, mnemonic_to_entropy, slip39, validate_mnemonic, MnemonicLanguage};
+pub use crate::keymaster::se::device::{DeviceAttestation, DeviceInfo, FirmwareVersion};
+#[cfg(any(test, feature = "simulator"))]
+pub use crate::keymaster::se::simulator::SeSimulator;
+pub use crate::keymaster::se::secure_channel::SecureChannel;
+pub use crate::keymaster::se::status::SeStatus;
+pub use crate::keymaster::se::transport::Transport;
+#[cfg(unix)]
+pub use crate::keymaster::se::transport::UnixSocketTransport;
 
 pub struct Signer {
     inner: Box<dyn KeyMaster>,
+    policy: SigningPolicy,
+    audit_log: Option<AuditLog>,
 }
 
 impl Signer {
     pub fn new_with_se(port_name: String) -> Self {
         Self {
             inner: Box::new(SecureElement::new(port_name)),
+            policy: SigningPolicy::default(),
+            audit_log: None,
         }
     }
 
-    fn new_with_mini() -> Self {
-        Self { inner: Box::new(Mini {}) }
+    pub fn new_with_se_timeout(port_name: String, timeout_ms: u64) -> Self {
+        Self {
+            inner: Box::new(SecureElement::new_with_timeout(port_name, timeout_ms)),
+            policy: SigningPolicy::default(),
+            audit_log: None,
+        }
+    }
+
+    pub fn new_with_se_secure_channel(port_name: String, se_public_key: &[u8]) -> Result<Self, KSError> {
+        Ok(Self {
+            inner: Box::new(SecureElement::new_with_secure_channel(port_name, se_public_key)?),
+            policy: SigningPolicy::default(),
+            audit_log: None,
+        })
+    }
+
+    pub fn new_with_se_transport(transport: Box<dyn Transport>) -> Self {
+        Self {
+            inner: Box::new(SecureElement::new_with_transport(transport)),
+            policy: SigningPolicy::default(),
+            audit_log: None,
+        }
+    }
+
+    // keys are derived from the mnemonics imported with Software::import_mnemonic into keystore_dir
+    pub fn new_with_software(keystore_dir: String) -> Self {
+        Self {
+            inner: Box::new(Software::new(keystore_dir)),
+            policy: SigningPolicy::default(),
+            audit_log: None,
+        }
+    }
+
+    // replaces the default policy every sign request is checked against
+    pub fn with_policy(mut self, policy: SigningPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    // every signature is appended to the log before it is returned
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    pub fn audit_log(&self) -> Option<&AuditLog> {
+        self.audit_log.as_ref()
+    }
+
+    // new mnemonic from the keymaster's entropy, the SE chip or the OS rng for Software
+    pub fn generate_mnemonic(
+        &self,
+        length: EntropyLength,
+        language: MnemonicLanguage,
+    ) -> Result<Zeroizing<String>, KSError> {
+        let entropy = self.inner.generate_entropy(length)?;
+        entropy_to_mnemonic(entropy.expose(), language)
     }
 
     pub fn sign_data(
         &self,
         mnemonic_id: u8,
-        password: String,
+        password: &SecretPassword,
         data: Vec<u8>,
         algo: SigningAlgorithm,
         derivation_path: String,
         signing_option: Option<SigningOption>,
     ) -> Result<Vec<u8>, KSError> {
-        self.inner
-            .sign_data(mnemonic_id, password, data, algo, derivation_path, signing_option)
+        self.policy.check(algo, &derivation_path, signing_option)?;
+        if self.audit_log.is_none() {
+            return self.inner.sign_data(mnemonic_id, password, data, algo, derivation_path, signing_option);
+        }
+        let request = SignRequest { data, algo, path: derivation_path, option: signing_option };
+        self.sign_audited(mnemonic_id, password, vec![request])?
+            .pop()
+            .unwrap_or(Err(KSError::SignDataError("missing result".to_string())))
+    }
+
+    // requests rejected by the policy are never sent to the keymaster
+    pub fn sign_batch(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        requests: Vec<SignRequest>,
+    ) -> Result<Vec<Result<Vec<u8>, KSError>>, KSError> {
+        let mut results = vec![];
+        let mut allowed = vec![];
+        for request in requests {
+            match self.policy.check(request.algo, &request.path, request.option) {
+                Ok(()) => {
+                    results.push(None);
+                    allowed.push(request);
+                }
+                Err(e) => results.push(Some(Err(e))),
+            }
+        }
+        let signed = self.sign_audited(mnemonic_id, password, allowed)?;
+        let mut signed = signed.into_iter();
+        Ok(results
+            .into_iter()
+            .map(|result| result.or_else(|| signed.next()).unwrap_or(Err(KSError::SignDataError("missing result".to_string()))))
+            .collect())
+    }
+
+    // checks the chain of the whole log and the MACs of this wallet's records, returns how many were verified
+    pub fn verify_audit_log(&self, mnemonic_id: u8, password: &SecretPassword) -> Result<usize, KSError> {
+        let audit_log = self
+            .audit_log
+            .as_ref()
+            .ok_or(KSError::AuditLogError("audit log is not enabled".to_string()))?;
+        let key = self.audit_key(mnemonic_id, password)?;
+        audit_log.verify(key.expose(), mnemonic_id)
+    }
+
+    // the audit key is derived under the same authentication as the signatures it covers
+    fn sign_audited(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        requests: Vec<SignRequest>,
+    ) -> Result<BatchSignatures, KSError> {
+        let audit_log = match &self.audit_log {
+            Some(audit_log) => audit_log,
+            None => return self.inner.sign_batch(mnemonic_id, password, requests),
+        };
+        let payloads: Vec<_> = requests
+            .iter()
+            .map(|request| (request.algo, request.path.clone(), request.data.clone()))
+            .collect();
+        let (signed, key) = self.inner.sign_batch_with_bip85(mnemonic_id, password, requests, audit_key_application())?;
+        let key = audit_key(&key)?;
+        for ((algo, path, data), result) in payloads.iter().zip(&signed) {
+            if let Ok(signature) = result {
+                audit_log.append(key.expose(), mnemonic_id, *algo, path, data, signature)?;
+            }
+        }
+        Ok(signed)
+    }
+
+    fn audit_key(&self, mnemonic_id: u8, password: &SecretPassword) -> Result<SecretBytes, KSError> {
+        audit_key(&self.inner.derive_bip85(mnemonic_id, password, audit_key_application())?)
     }
 
     pub fn get_rsa_public_key(
         &self,
         mnemonic_id: u8,
-        password: String,
+        password: &SecretPassword,
     ) -> Result<Vec<u8>, KSError> {
         self.inner
             .get_rsa_public_key(mnemonic_id, password)
     }
 
+    pub fn get_public_key(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        algo: SigningAlgorithm,
+        derivation_path: String,
+        format: PublicKeyFormat,
+    ) -> Result<Vec<u8>, KSError> {
+        self.inner
+            .get_public_key(mnemonic_id, password, algo, derivation_path, format)
+    }
+
+    pub fn get_master_fingerprint(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+    ) -> Result<[u8; 4], KSError> {
+        self.inner.get_master_fingerprint(mnemonic_id, password)
+    }
+
+    pub fn derive_bip85(
+        &self,
+        mnemonic_id: u8,
+        password: &SecretPassword,
+        application: Bip85Application,
+    ) -> Result<Zeroizing<String>, KSError> {
+        self.inner.derive_bip85(mnemonic_id, password, application)
+    }
+
+    pub fn verify(
+        public_key: &[u8],
+        data: &[u8],
+        signature: &[u8],
+        algo: SigningAlgorithm,
+        signing_option: Option<SigningOption>,
+    ) -> Result<(), KSError> {
+        algorithm::verifier::verify(public_key, data, signature, algo, signing_option)
+    }
+
     pub fn setup_ada_root_key(
         &self,
         mnemonic_id: u8,
-        password: String,
-        passphrase: String,
+        password: &SecretPassword,
+        passphrase: &SecretPassword,
+        scheme: AdaMasterKeyScheme,
     ) -> Result<bool, KSError> {
-        self.inner.setup_ada_root_key(mnemonic_id, password, passphrase)
+        self.inner.setup_ada_root_key(mnemonic_id, password, passphrase, scheme)
     }
 
     pub fn get_ada_extended_public_key(
         &self,
         mnemonic_id: u8,
-        password: String,
+        password: &SecretPassword,
         path: String,
     ) -> Result<String, KSError> {
         self.inner.get_ada_extended_public_key(mnemonic_id, password, path)
@@ -67,13 +261,32 @@ impl Signer {
     pub fn get_ada_root_key(
         &self,
         mnemonic_id: u8,
-        password: String,
-    ) -> Result<Vec<u8>, KSError> {
+        password: &SecretPassword,
+    ) -> Result<SecretBytes, KSError> {
         self.inner
-            .get_ada_root_key(mnemonic_id, password.into())
+            .get_ada_root_key(mnemonic_id, password)
+    }
+
+    pub fn get_device_info(&self) -> Result<DeviceInfo, KSError> {
+        self.inner.get_device_info()
+    }
+
+    pub fn attest(&self, challenge: Vec<u8>) -> Result<DeviceAttestation, KSError> {
+        self.inner.attest(challenge)
     }
 }
 
+// the MAC key is bip85 child entropy of the wallet, so only the wallet owner can verify or forge records
+fn audit_key_application() -> Bip85Application {
+    Bip85Application::Hex { length: audit::AUDIT_KEY_LENGTH, index: audit::AUDIT_KEY_INDEX }
+}
+
+fn audit_key(child: &str) -> Result<SecretBytes, KSError> {
+    hex::decode(child)
+        .map(SecretBytes::new)
+        .map_err(|e| KSError::AuditLogError(e.to_string()))
+}
+
 #[cfg(all(test, target_os = "macos"))]
 mod tests {
     use k256::ecdsa::signature::Signature as _;
@@ -86,9 +299,11 @@ mod tests {
     use crate::algorithm::SecretKey;
     use crate::keymaster::SigningOption;
 
+    use super::signer_tests::test_signer;
+
     #[test]
     fn it_should_pass_test_sign_256k1() {
-        let fake_signer = Signer::new_with_mini();
+        let (fake_signer, _keystore_dir) = test_signer();
         let path = "m/44'/60'/0'/0/0".to_string();
 
         let data: Vec<u8> = hex::decode(
@@ -97,7 +312,7 @@ mod tests {
             .unwrap();
 
         let signature = fake_signer
-            .sign_data(0, "test_pass".to_string(), data, SigningAlgorithm::Secp256k1, path, None)
+            .sign_data(0, &"test_pass".into(), data, SigningAlgorithm::Secp256k1, path, None)
             .unwrap();
 
         let sig: Signature = Signature::from_bytes(signature.as_slice()).unwrap();
@@ -118,7 +333,7 @@ mod tests {
 
     #[test]
     fn it_should_pass_test_sign_rsa_salt_zero() {
-        let fake_signer = Signer::new_with_mini();
+        let (fake_signer, _keystore_dir) = test_signer();
         let path = "m/44'/472'".to_string();
 
         let data: Vec<u8> = hex::decode(
@@ -128,7 +343,7 @@ mod tests {
         let signing_option = SigningOption::RSA { salt_len: 0 };
 
         let signature = fake_signer
-            .sign_data(0, "test_pass".to_string(), data, SigningAlgorithm::RSA, path, Some(signing_option))
+            .sign_data(0, &"test_pass".into(), data, SigningAlgorithm::RSA, path, Some(signing_option))
             .unwrap();
         let data2: Vec<u8> = hex::decode("af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd".to_string()).unwrap();
         let sk_bytes = hex::decode("fdec3a1aee520780ca4058402d0422b5cd5950b715728f532499dd4bbcb68e5d44650818b43656782237316c4b0e2faa2b15c245fb82d10cf4f5b420f1f293ba75b2c8d8cef6ad899c34ce9de482cb248cc5ab802fd93094a63577590d812d5dd781846ef7d4f5d9018199c293966371c2349b0f847c818ec99caad800116e02085d35a39a913bc735327705161761ae30a4ec775f127fbb5165418c0fe08e54ae0aff8b2dab2b82d3b4b9c807de5fae116096075cf6d5b77450d743d743e7dcc56e7cafdcc555f228e57b363488e171d099876993e93e37a94983ccc12dba894c58ca84ac154c1343922c6a99008fabd0fa7010d3cc34f69884fec902984771c5b50031ba31ab7c8b76453ce771f048b84fb89a3e4d44c222c3d8c823c683988b0dbf354d8b8cbf65f3db53e1365d3c5e043f0155b41d1ebeca6e20b2d6778600b5c98ffdba33961dae73b018307ef2bce9d217bbdf32964080f8db6f0cf7ef27ac825fcaf98d5143690a5d7e138f4875280ed6de581e66ed17f83371c268a073e4594814bcc88a33cbb4ec8819cc722ea15490312b85fed06e39274c4f73ac91c7f4d1b899729691cce616fb1a5feee1972456addcb51ac830e947fcc1b823468f0eefbaf195ac3b34f0baf96afc6fa77ee2e176081d6d91ce8c93c3d0f3547e48d059c9da447ba05ee3984703bebfd6d704b7f327ffaea7d0f63d0d3c6d65542fd4042926629451ee9a4dace812428b6494acbf45370ddd2308c01e9ab9bf3974b561d5064f6f315f1a39632024bc18f2738c3acb11a1c1d25919477b0acc4f3e8b865aa50a9c3e781535079a06a668aa262ed675bb8ff979b93b5c877044528a0a89aa0a13855b37d96d1c213f237c2739a26aeca46427c517ecf0bc778becda2afb0be236988ed5d162c87ecca8db123af41129f8dfb3893f66293c64dd09d7313190ae66af5a2bef053ed25594a97bda6aa2c7eff560c815b9fe28ce2b68e89988a88322c34ef0e7e4c0822b2018545379900553d18c71de88bed451ef814c739296586d238bef428945ecb9f1eda9c098ba2345daf59229659b1588f2374438e978f94cf03ece881ded34790416d0f746b0701f7096aa74f381a21725dba3702b32670a5db7693763e95e751ae0ef5cd875ac38a4427dd716dd1d61d6c0e234ff64f80dbf0f1c2632883ac74b9e9387ad58e5ca928b7880d9844b513b448447c31b94d04160cfa83b0381b4e59b23deafd1cca01639e405bc494fa63758246eab4d25f94a6c2dfed72be6127217d7f806b05b573070850307a8c594233851a7efdb55e27f1624f2a9ca2a0c3e803024b1cbce919e7ae7e0b730d357a6ca62cd15978940f7998524404cb5837ccc93bca22caeb5156aa36abd92c83e047addef10d2e8f78e8c94a50fc305f9fe35a7f45f76271bd794b2f111db2eae41c41a50ed2155a5740b45df8e3815774d6b8d193e5ad80c9efaaf6d6d0253f350c85becf39eb7056d75841f6a064acf8381383eceb218e16859ef72be7273321a2b4855b87bc6f14c734e2a9c90850c34a8a0a4279ac9be3186b086db5b302fb68176b4c1fee337456c42f972c7993f618fdedc0bf1658c2d59cf2c0c6ac31a61ac1260e0fd4a761ca3707e27611c14b4c6b6abe698c11009ddf5d1511ae47ea271079b6892d229a27d0822e0c7aa12a4cf7f7c28fe23d201eae2adb7f403c9c5a1762c2d8cc96898ce41fe529ab0ef8184e50063e6fc62e0a808e8602254c142c9e7f7e94e6ef2c767ac0e99810d09a44bfde8db46298bc0e25b4a333b4ef86cd7ce658ff661ab0d1789b603b8770a6b433851a91c8ff07a7a8a0767702f6887098ea34bf4a8309eaab9baadd16d45cdd9b1899b6a303a2dce23745cec9fc2ecd9735a66c77fdea1bfd4cdb2be7bfb407a4fd5d3405c3cb33b5316e16559f0c4bf0bc7d1a3ada78917217b289c4d75eb60e0396f03035fd8d553727c790189cfd8dabcee8a4ae6607925b9a27ff7ad7ede26b98f8acd2532cf3175693f3eede9989a0aeedbdb3ff14fec823017531aead4cd22733ab30dbce76cebcdac64424128d6eeff3cdc1825d7cdb7113e74db126e6d931544467c6979aa8d50ac803f36084ed7077f34acfcf3f77bb13d5ebb723fc5d3f45212d2dd6ef20ea757fb4c95").unwrap();
@@ -139,7 +354,7 @@ mod tests {
 
     #[test]
     fn it_should_pass_test_sign_rsa_salt_digest_size() {
-        let fake_signer = Signer::new_with_mini();
+        let (fake_signer, _keystore_dir) = test_signer();
         let path = "m/44'/472'".to_string();
 
         let data: Vec<u8> = hex::decode(
@@ -149,7 +364,7 @@ mod tests {
         let signing_option = SigningOption::RSA { salt_len: 32 };
 
         let signature = fake_signer
-            .sign_data(0, "test_pass".to_string(), data, SigningAlgorithm::RSA, path, Some(signing_option))
+            .sign_data(0, &"test_pass".into(), data, SigningAlgorithm::RSA, path, Some(signing_option))
             .unwrap();
         let data2: Vec<u8> = hex::decode("af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd".to_string()).unwrap();
         let sk_bytes = hex::decode("fdec3a1aee520780ca4058402d0422b5cd5950b715728f532499dd4bbcb68e5d44650818b43656782237316c4b0e2faa2b15c245fb82d10cf4f5b420f1f293ba75b2c8d8cef6ad899c34ce9de482cb248cc5ab802fd93094a63577590d812d5dd781846ef7d4f5d9018199c293966371c2349b0f847c818ec99caad800116e02085d35a39a913bc735327705161761ae30a4ec775f127fbb5165418c0fe08e54ae0aff8b2dab2b82d3b4b9c807de5fae116096075cf6d5b77450d743d743e7dcc56e7cafdcc555f228e57b363488e171d099876993e93e37a94983ccc12dba894c58ca84ac154c1343922c6a99008fabd0fa7010d3cc34f69884fec902984771c5b50031ba31ab7c8b76453ce771f048b84fb89a3e4d44c222c3d8c823c683988b0dbf354d8b8cbf65f3db53e1365d3c5e043f0155b41d1ebeca6e20b2d6778600b5c98ffdba33961dae73b018307ef2bce9d217bbdf32964080f8db6f0cf7ef27ac825fcaf98d5143690a5d7e138f4875280ed6de581e66ed17f83371c268a073e4594814bcc88a33cbb4ec8819cc722ea15490312b85fed06e39274c4f73ac91c7f4d1b899729691cce616fb1a5feee1972456addcb51ac830e947fcc1b823468f0eefbaf195ac3b34f0baf96afc6fa77ee2e176081d6d91ce8c93c3d0f3547e48d059c9da447ba05ee3984703bebfd6d704b7f327ffaea7d0f63d0d3c6d65542fd4042926629451ee9a4dace812428b6494acbf45370ddd2308c01e9ab9bf3974b561d5064f6f315f1a39632024bc18f2738c3acb11a1c1d25919477b0acc4f3e8b865aa50a9c3e781535079a06a668aa262ed675bb8ff979b93b5c877044528a0a89aa0a13855b37d96d1c213f237c2739a26aeca46427c517ecf0bc778becda2afb0be236988ed5d162c87ecca8db123af41129f8dfb3893f66293c64dd09d7313190ae66af5a2bef053ed25594a97bda6aa2c7eff560c815b9fe28ce2b68e89988a88322c34ef0e7e4c0822b2018545379900553d18c71de88bed451ef814c739296586d238bef428945ecb9f1eda9c098ba2345daf59229659b1588f2374438e978f94cf03ece881ded34790416d0f746b0701f7096aa74f381a21725dba3702b32670a5db7693763e95e751ae0ef5cd875ac38a4427dd716dd1d61d6c0e234ff64f80dbf0f1c2632883ac74b9e9387ad58e5ca928b7880d9844b513b448447c31b94d04160cfa83b0381b4e59b23deafd1cca01639e405bc494fa63758246eab4d25f94a6c2dfed72be6127217d7f806b05b573070850307a8c594233851a7efdb55e27f1624f2a9ca2a0c3e803024b1cbce919e7ae7e0b730d357a6ca62cd15978940f7998524404cb5837ccc93bca22caeb5156aa36abd92c83e047addef10d2e8f78e8c94a50fc305f9fe35a7f45f76271bd794b2f111db2eae41c41a50ed2155a5740b45df8e3815774d6b8d193e5ad80c9efaaf6d6d0253f350c85becf39eb7056d75841f6a064acf8381383eceb218e16859ef72be7273321a2b4855b87bc6f14c734e2a9c90850c34a8a0a4279ac9be3186b086db5b302fb68176b4c1fee337456c42f972c7993f618fdedc0bf1658c2d59cf2c0c6ac31a61ac1260e0fd4a761ca3707e27611c14b4c6b6abe698c11009ddf5d1511ae47ea271079b6892d229a27d0822e0c7aa12a4cf7f7c28fe23d201eae2adb7f403c9c5a1762c2d8cc96898ce41fe529ab0ef8184e50063e6fc62e0a808e8602254c142c9e7f7e94e6ef2c767ac0e99810d09a44bfde8db46298bc0e25b4a333b4ef86cd7ce658ff661ab0d1789b603b8770a6b433851a91c8ff07a7a8a0767702f6887098ea34bf4a8309eaab9baadd16d45cdd9b1899b6a303a2dce23745cec9fc2ecd9735a66c77fdea1bfd4cdb2be7bfb407a4fd5d3405c3cb33b5316e16559f0c4bf0bc7d1a3ada78917217b289c4d75eb60e0396f03035fd8d553727c790189cfd8dabcee8a4ae6607925b9a27ff7ad7ede26b98f8acd2532cf3175693f3eede9989a0aeedbdb3ff14fec823017531aead4cd22733ab30dbce76cebcdac64424128d6eeff3cdc1825d7cdb7113e74db126e6d931544467c6979aa8d50ac803f36084ed7077f34acfcf3f77bb13d5ebb723fc5d3f45212d2dd6ef20ea757fb4c95").unwrap();
@@ -158,3 +373,274 @@ mod tests {
         assert_eq!(result.ok(), Some(()));
     }
 }
+
+#[cfg(test)]
+mod signer_tests {
+    use k256::ecdsa::SigningKey;
+    use super::*;
+    use crate::algorithm;
+    use crate::algorithm::SecretKey;
+    use crate::keymaster::SigningOption;
+
+    // abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about
+    pub(super) fn test_signer() -> (Signer, tempfile::TempDir) {
+        let keystore_dir = tempfile::tempdir().unwrap();
+        let keystore_path = keystore_dir.path().to_str().unwrap().to_string();
+        Software::new(keystore_path.clone())
+            .import_mnemonic(
+                0,
+                Zeroizing::new(
+                    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string(),
+                ),
+                &"".into(),
+                &"test_pass".into(),
+            )
+            .unwrap();
+        (Signer::new_with_software(keystore_path), keystore_dir)
+    }
+
+    #[test]
+    fn it_should_pass_test_sign_schnorr_taproot() {
+        let (fake_signer, _keystore_dir) = test_signer();
+        let path = "m/86'/0'/0'/0/0".to_string();
+
+        let data: Vec<u8> = hex::decode(
+            "af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd",
+        )
+            .unwrap();
+
+        let signing_option = SigningOption::Schnorr { tweak: Some(TaprootTweak { merkle_root: None }), aux_rand: None };
+        let signature = fake_signer
+            .sign_data(0, &"test_pass".into(), data.clone(), SigningAlgorithm::Secp256k1, path.clone(), Some(signing_option))
+            .unwrap();
+
+        let public_key = fake_signer
+            .get_public_key(0, &"test_pass".into(), SigningAlgorithm::Secp256k1, path, PublicKeyFormat::Compressed)
+            .unwrap();
+        let output_key = algorithm::schnorr::tweak_public_key(&public_key[1..], None).unwrap();
+        assert!(algorithm::schnorr::verify(&data, &output_key, &signature).is_ok());
+        assert!(Signer::verify(&public_key, &data, &signature, SigningAlgorithm::Secp256k1, Some(signing_option)).is_ok());
+    }
+
+    #[test]
+    fn it_should_pass_test_sign_256r1() {
+        use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+        let (fake_signer, _keystore_dir) = test_signer();
+        let path = "m/74'/784'/0'/0/0".to_string();
+
+        let data: Vec<u8> = hex::decode(
+            "af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd",
+        )
+            .unwrap();
+
+        let signing_option = SigningOption::Secp256R1 { encoding: SignatureEncoding::DER };
+        let signature = fake_signer
+            .sign_data(0, &"test_pass".into(), data.clone(), SigningAlgorithm::Secp256R1, path.clone(), Some(signing_option))
+            .unwrap();
+
+        let seed = hex::decode("5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4").unwrap();
+        let sk = algorithm::secp256r1::get_extended_private_key(path, &seed).unwrap().signing_key().unwrap();
+        let signature = Signature::from_der(&signature).unwrap();
+        assert!(VerifyingKey::from(&sk).verify(&data, &signature).is_ok());
+    }
+
+    #[test]
+    fn it_should_pass_test_sign_ed25519() {
+        let (fake_signer, _keystore_dir) = test_signer();
+        let path = "m/44'/501'/0'/0'".to_string();
+
+        let data: Vec<u8> = hex::decode(
+            "af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd",
+        )
+            .unwrap();
+
+        let signature = fake_signer
+            .sign_data(0, &"test_pass".into(), data.clone(), SigningAlgorithm::Ed25519, path.clone(), None)
+            .unwrap();
+
+        let seed = hex::decode("5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4").unwrap();
+        let public_key = algorithm::slip10_ed25519::get_public_key(path, &seed).unwrap();
+        let signature: [u8; 64] = signature.try_into().unwrap();
+        assert!(cryptoxide::ed25519::verify(&data, &public_key, &signature));
+    }
+
+    #[test]
+    fn it_should_pass_test_sign_sr25519() {
+        let (fake_signer, _keystore_dir) = test_signer();
+        let path = "//polkadot//0".to_string();
+
+        let data: Vec<u8> = hex::decode(
+            "af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd",
+        )
+            .unwrap();
+
+        let signature = fake_signer
+            .sign_data(0, &"test_pass".into(), data.clone(), SigningAlgorithm::SR25519, path.clone(), None)
+            .unwrap();
+
+        let entropy = hex::decode("00000000000000000000000000000000").unwrap();
+        let public_key = algorithm::sr25519::get_public_key(path, &entropy).unwrap();
+        assert!(algorithm::sr25519::verify(&data, &public_key, &signature).is_ok());
+    }
+
+    #[test]
+    fn it_should_get_public_keys() {
+        let (fake_signer, _keystore_dir) = test_signer();
+        let password = SecretPassword::from("test_pass");
+
+        let fingerprint = fake_signer.get_master_fingerprint(0, &password).unwrap();
+        assert_eq!(hex::encode(fingerprint), "73c5da0a");
+
+        let xpub = fake_signer
+            .get_public_key(0, &password, SigningAlgorithm::Secp256k1, "m/44'/0'/0'".to_string(), PublicKeyFormat::ExtendedPublicKey)
+            .unwrap();
+        assert_eq!(String::from_utf8(xpub).unwrap(), "xpub6BosfCnifzxcFwrSzQiqu2DBVTshkCXacvNsWGYJVVhhawA7d4R5WSWGFNbi8Aw6ZRc1brxMyWMzG3DSSSSoekkudhUd9yLb6qx39T9nMdj");
+
+        let path = "m/44'/60'/0'/0/0".to_string();
+        let compressed = fake_signer
+            .get_public_key(0, &password, SigningAlgorithm::Secp256k1, path.clone(), PublicKeyFormat::Compressed)
+            .unwrap();
+        let uncompressed = fake_signer
+            .get_public_key(0, &password, SigningAlgorithm::Secp256k1, path.clone(), PublicKeyFormat::Uncompressed)
+            .unwrap();
+        let sk_bytes = hex::decode("78707276413436797257796b4668334c6a4d486e3165716b374138574e4274374a7a4a714565425831524e7a32627839446974753670654b374d4a5752387466585571506a574e754c374c774c76706864676b5753684e705958694a42757669396167784a555769484748746f4e6b").unwrap();
+        let sk = <SigningKey as SecretKey>::from_secret(sk_bytes.as_slice()).unwrap();
+        assert_eq!(compressed, sk.verifying_key().to_bytes().to_vec());
+        assert_eq!(uncompressed.len(), 65);
+        assert_eq!(&uncompressed[1..33], &compressed[1..]);
+
+        let ed25519 = fake_signer
+            .get_public_key(0, &password, SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'".to_string(), PublicKeyFormat::Raw)
+            .unwrap();
+        assert_eq!(bs58::encode(ed25519).into_string(), "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+
+        let result = fake_signer
+            .get_public_key(0, &password, SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'".to_string(), PublicKeyFormat::Compressed);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_verify_signatures() {
+        let (fake_signer, _keystore_dir) = test_signer();
+        let password = SecretPassword::from("test_pass");
+        let data: Vec<u8> = hex::decode(
+            "af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd",
+        ).unwrap();
+        let cases = [
+            (SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/0", PublicKeyFormat::Compressed, None),
+            (SigningAlgorithm::Secp256R1, "m/74'/784'/0'/0/0", PublicKeyFormat::Uncompressed, Some(SigningOption::Secp256R1 { encoding: SignatureEncoding::DER })),
+            (SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'", PublicKeyFormat::Raw, None),
+            (SigningAlgorithm::SR25519, "//polkadot", PublicKeyFormat::Raw, None),
+        ];
+        for (algo, path, format, signing_option) in cases {
+            let signature = fake_signer
+                .sign_data(0, &password, data.clone(), algo, path.to_string(), signing_option)
+                .unwrap();
+            let public_key = fake_signer
+                .get_public_key(0, &password, algo, path.to_string(), format)
+                .unwrap();
+            assert!(Signer::verify(&public_key, &data, &signature, algo, signing_option).is_ok());
+            let mut tampered = data.clone();
+            tampered[0] ^= 1;
+            let result = Signer::verify(&public_key, &tampered, &signature, algo, signing_option);
+            assert!(matches!(result, Err(KSError::SignatureMismatch)));
+        }
+    }
+
+    #[test]
+    fn it_should_generate_mnemonic() {
+        let (software_signer, _keystore_dir) = test_signer();
+        let simulator = SeSimulator::new(vec![0x01; 32]);
+        let se_signer = Signer::new_with_se_transport(Box::new(simulator));
+        for signer in [software_signer, se_signer] {
+            for (words, length) in [(12, EntropyLength::Short(12)), (18, EntropyLength::Long(18)), (24, EntropyLength::Long(24))] {
+                let mnemonic = signer.generate_mnemonic(length, MnemonicLanguage::English).unwrap();
+                assert_eq!(mnemonic.split(' ').count(), words);
+                assert!(validate_mnemonic(&mnemonic, MnemonicLanguage::English).is_ok());
+            }
+            let mnemonic = signer.generate_mnemonic(EntropyLength::Long(24), MnemonicLanguage::Japanese).unwrap();
+            assert!(validate_mnemonic(&mnemonic, MnemonicLanguage::Japanese).is_ok());
+            assert!(signer.generate_mnemonic(EntropyLength::Short(15), MnemonicLanguage::English).is_err());
+        }
+    }
+
+    #[test]
+    fn it_should_sign_batch() {
+        let (fake_signer, _keystore_dir) = test_signer();
+        let data: Vec<u8> = hex::decode(
+            "af1dee894786c304604a039b041463c9ab8defb393403ea03cf2c85b1eb8cbfd",
+        ).unwrap();
+        let request = |algo, path: &str, option| SignRequest { data: data.clone(), algo, path: path.to_string(), option };
+        let requests = vec![
+            request(SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/0", None),
+            request(SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/x", None),
+            request(SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'", None),
+            request(SigningAlgorithm::RSA, "m/44'/472'", None),
+            request(SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/0", None),
+        ];
+        let results = fake_signer.sign_batch(0, &"test_pass".into(), requests).unwrap();
+        assert_eq!(results.len(), 5);
+        assert!(results[1].is_err());
+        assert!(matches!(results[3], Err(KSError::PolicyViolation(_))));
+        let expected = fake_signer
+            .sign_data(0, &"test_pass".into(), data.clone(), SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/0".to_string(), None)
+            .unwrap();
+        assert_eq!(results[0].as_ref().unwrap(), &expected);
+        assert_eq!(results[4].as_ref().unwrap(), &expected);
+        let public_key = fake_signer
+            .get_public_key(0, &"test_pass".into(), SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'".to_string(), PublicKeyFormat::Raw)
+            .unwrap();
+        assert!(Signer::verify(&public_key, &data, results[2].as_ref().unwrap(), SigningAlgorithm::Ed25519, None).is_ok());
+
+        assert!(fake_signer.sign_batch(0, &"wrong_pass".into(), vec![]).is_err());
+    }
+
+    #[test]
+    fn it_should_enforce_signing_policy() {
+        let (fake_signer, keystore_dir) = test_signer();
+        let password = SecretPassword::from("test_pass");
+        let data = vec![0x01; 32];
+        let sign = |signer: &Signer, algo, path: &str| signer.sign_data(0, &password, data.clone(), algo, path.to_string(), None);
+        assert!(matches!(sign(&fake_signer, SigningAlgorithm::Ed25519, "m/84'/0'/0'/0/0"), Err(KSError::PolicyViolation(_))));
+        assert!(matches!(sign(&fake_signer, SigningAlgorithm::Secp256k1, "m/44'/501'/0'/0'"), Err(KSError::PolicyViolation(_))));
+        // the policy runs before the keystore is opened
+        let result = fake_signer.sign_data(0, &"wrong_pass".into(), data.clone(), SigningAlgorithm::Ed25519, "m/84'/0'".to_string(), None);
+        assert!(matches!(result, Err(KSError::PolicyViolation(_))));
+
+        let policy = SigningPolicy::from_config("[solana]\npaths = m/44'/501'\ncurves = ed25519\nhardened = all\nmax_depth = 4\noptions = none").unwrap();
+        let solana_signer = Signer::new_with_software(keystore_dir.path().to_str().unwrap().to_string()).with_policy(policy);
+        assert!(sign(&solana_signer, SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'").is_ok());
+        assert!(matches!(sign(&solana_signer, SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/0"), Err(KSError::PolicyViolation(_))));
+    }
+
+    #[test]
+    fn it_should_write_audit_log() {
+        let (fake_signer, _keystore_dir) = test_signer();
+        let password = SecretPassword::from("test_pass");
+        let data = vec![0x01; 32];
+        assert!(fake_signer.verify_audit_log(0, &password).is_err());
+
+        let fake_signer = fake_signer.with_audit_log(AuditLog::new(Box::new(MemoryAuditStorage::new())).unwrap());
+        let path = "m/44'/60'/0'/0/0".to_string();
+        let signature = fake_signer
+            .sign_data(0, &password, data.clone(), SigningAlgorithm::Secp256k1, path.clone(), None)
+            .unwrap();
+        // rejected and failed requests are not signed, so not logged
+        assert!(fake_signer.sign_data(0, &password, data.clone(), SigningAlgorithm::Ed25519, path.clone(), None).is_err());
+        let requests = vec![
+            SignRequest { data: data.clone(), algo: SigningAlgorithm::Ed25519, path: "m/44'/501'/0'/0'".to_string(), option: None },
+            SignRequest { data: data.clone(), algo: SigningAlgorithm::RSA, path: "m/44'/472'".to_string(), option: None },
+        ];
+        let results = fake_signer.sign_batch(0, &password, requests).unwrap();
+
+        let records = fake_signer.audit_log().unwrap().records().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].path, path);
+        assert_eq!(records[0].payload_hash, cryptoxide::hashing::sha256(&data));
+        assert_eq!(records[0].signature_hash, cryptoxide::hashing::sha256(&signature));
+        assert_eq!(records[1].signature_hash, cryptoxide::hashing::sha256(results[0].as_ref().unwrap()));
+        assert_eq!(records[1].previous_hash, records[0].hash());
+        assert_eq!(fake_signer.verify_audit_log(0, &password).unwrap(), 2);
+        assert!(fake_signer.verify_audit_log(0, &"wrong_pass".into()).is_err());
+    }
+}
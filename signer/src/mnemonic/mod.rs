@@ -0,0 +1,131 @@
+use bip39::{Language, Mnemonic};
+use zeroize::Zeroizing;
+
+use crate::error::KSError;
+
+pub mod slip39;
+mod slip39_wordlist;
+
+// BIP39 word counts accepted when generating and validating mnemonics
+const WORD_COUNTS: [usize; 3] = [12, 18, 24];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MnemonicLanguage {
+    English,
+    SimplifiedChinese,
+    TraditionalChinese,
+    Czech,
+    French,
+    Italian,
+    Japanese,
+    Korean,
+    Portuguese,
+    Spanish,
+}
+
+impl From<MnemonicLanguage> for Language {
+    fn from(language: MnemonicLanguage) -> Self {
+        match language {
+            MnemonicLanguage::English => Language::English,
+            MnemonicLanguage::SimplifiedChinese => Language::SimplifiedChinese,
+            MnemonicLanguage::TraditionalChinese => Language::TraditionalChinese,
+            MnemonicLanguage::Czech => Language::Czech,
+            MnemonicLanguage::French => Language::French,
+            MnemonicLanguage::Italian => Language::Italian,
+            MnemonicLanguage::Japanese => Language::Japanese,
+            MnemonicLanguage::Korean => Language::Korean,
+            MnemonicLanguage::Portuguese => Language::Portuguese,
+            MnemonicLanguage::Spanish => Language::Spanish,
+        }
+    }
+}
+
+// 16, 24 or 32 bytes of entropy become 12, 18 or 24 words with the checksum appended
+pub fn entropy_to_mnemonic(entropy: &[u8], language: MnemonicLanguage) -> Result<Zeroizing<String>, KSError> {
+    let mnemonic = Mnemonic::from_entropy_in(language.into(), entropy)
+        .map_err(|e| KSError::MnemonicError(e.to_string()))?;
+    if !WORD_COUNTS.contains(&mnemonic.word_count()) {
+        return Err(KSError::MnemonicError(format!(
+            "{} words mnemonic is not supported",
+            mnemonic.word_count()
+        )));
+    }
+    Ok(Zeroizing::new(mnemonic.to_string()))
+}
+
+// checks the words belong to the wordlist and the checksum matches
+pub fn validate_mnemonic(mnemonic: &str, language: MnemonicLanguage) -> Result<(), KSError> {
+    mnemonic_to_entropy(mnemonic, language).map(|_| ())
+}
+
+pub fn mnemonic_to_entropy(mnemonic: &str, language: MnemonicLanguage) -> Result<Zeroizing<Vec<u8>>, KSError> {
+    let mnemonic = Zeroizing::new(mnemonic.to_string());
+    let mnemonic = Mnemonic::parse_in(language.into(), mnemonic.as_str())
+        .map_err(|e| KSError::MnemonicError(e.to_string()))?;
+    if !WORD_COUNTS.contains(&mnemonic.word_count()) {
+        return Err(KSError::MnemonicError(format!(
+            "{} words mnemonic is not supported",
+            mnemonic.word_count()
+        )));
+    }
+    Ok(Zeroizing::new(mnemonic.to_entropy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn it_should_convert_entropy_to_mnemonic() {
+        assert_eq!(entropy_to_mnemonic(&[0u8; 16], MnemonicLanguage::English).unwrap().as_str(), MNEMONIC);
+        let mnemonic = entropy_to_mnemonic(&[0x7f; 24], MnemonicLanguage::English).unwrap();
+        assert_eq!(mnemonic.split(' ').count(), 18);
+        let mnemonic = entropy_to_mnemonic(&[0xff; 32], MnemonicLanguage::English).unwrap();
+        assert_eq!(
+            mnemonic.as_str(),
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote"
+        );
+        // 15 and 21 words are valid BIP39 but not offered
+        assert!(entropy_to_mnemonic(&[0u8; 20], MnemonicLanguage::English).is_err());
+        assert!(entropy_to_mnemonic(&[0u8; 15], MnemonicLanguage::English).is_err());
+    }
+
+    #[test]
+    fn it_should_round_trip_every_language() {
+        let languages = [
+            MnemonicLanguage::English,
+            MnemonicLanguage::SimplifiedChinese,
+            MnemonicLanguage::TraditionalChinese,
+            MnemonicLanguage::Czech,
+            MnemonicLanguage::French,
+            MnemonicLanguage::Italian,
+            MnemonicLanguage::Japanese,
+            MnemonicLanguage::Korean,
+            MnemonicLanguage::Portuguese,
+            MnemonicLanguage::Spanish,
+        ];
+        let entropy: Vec<u8> = (0..32).collect();
+        for language in languages {
+            let mnemonic = entropy_to_mnemonic(&entropy, language).unwrap();
+            assert_eq!(mnemonic_to_entropy(&mnemonic, language).unwrap().as_slice(), entropy.as_slice());
+        }
+        let mnemonic = entropy_to_mnemonic(&[0u8; 16], MnemonicLanguage::French).unwrap();
+        assert!(mnemonic.starts_with("abaisser"));
+        assert!(validate_mnemonic(&mnemonic, MnemonicLanguage::English).is_err());
+    }
+
+    #[test]
+    fn it_should_reject_invalid_mnemonics() {
+        assert!(validate_mnemonic(MNEMONIC, MnemonicLanguage::English).is_ok());
+        // wrong checksum word
+        let mnemonic = MNEMONIC.replace("about", "abandon");
+        assert!(matches!(
+            validate_mnemonic(&mnemonic, MnemonicLanguage::English),
+            Err(KSError::MnemonicError(_))
+        ));
+        assert!(validate_mnemonic(&MNEMONIC.replace("about", "bitcoinz"), MnemonicLanguage::English).is_err());
+        assert!(validate_mnemonic("abandon abandon abandon", MnemonicLanguage::English).is_err());
+    }
+}
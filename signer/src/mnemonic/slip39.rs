@@ -0,0 +1,575 @@
+use std::collections::BTreeMap;
+
+use aes_gcm::aead::rand_core::{CryptoRng, RngCore};
+use aes_gcm::aead::OsRng;
+use cryptoxide::hmac::Hmac;
+use cryptoxide::mac::Mac;
+use cryptoxide::pbkdf2::pbkdf2;
+use cryptoxide::sha2::Sha256;
+use zeroize::Zeroizing;
+
+use super::slip39_wordlist::WORDLIST;
+use crate::error::KSError;
+
+const RADIX_BITS: usize = 10;
+const RADIX_MASK: u32 = (1 << RADIX_BITS) - 1;
+// identifier, extendable flag and iteration exponent, then the group and member parameters
+const HEADER_WORDS: usize = 4;
+const CHECKSUM_WORDS: usize = 3;
+const MIN_SECRET_LENGTH: usize = 16;
+const MIN_MNEMONIC_WORDS: usize =
+    HEADER_WORDS + (MIN_SECRET_LENGTH * 8).div_ceil(RADIX_BITS) + CHECKSUM_WORDS;
+const MAX_SHARE_COUNT: u8 = 16;
+const MAX_ITERATION_EXPONENT: u8 = 15;
+const DIGEST_LENGTH: usize = 4;
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const BASE_ITERATION_COUNT: u32 = 10000;
+const ROUND_COUNT: u8 = 4;
+const CHECKSUM_GENERATOR: [u32; 10] = [
+    0xE0E040, 0x1C1C080, 0x3838100, 0x7070200, 0xE0E0009, 0x1C0C2412, 0x38086C24, 0x3090FC48, 0x21B1F890, 0x3F3F120,
+];
+
+// GF(256) exp and log tables with generator 3 over the Rijndael polynomial
+const GF_TABLES: ([u8; 255], [u8; 256]) = gf_tables();
+
+const fn gf_tables() -> ([u8; 255], [u8; 256]) {
+    let mut exp = [0u8; 255];
+    let mut log = [0u8; 256];
+    let mut value: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = value as u8;
+        log[value as usize] = i as u8;
+        value ^= value << 1;
+        if value & 0x100 != 0 {
+            value ^= 0x11b;
+        }
+        i += 1;
+    }
+    (exp, log)
+}
+
+// member threshold and member count of one group
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GroupSpec {
+    pub threshold: u8,
+    pub count: u8,
+}
+
+// one decoded share mnemonic, indexes are zero based and thresholds and counts one based
+#[derive(Clone)]
+pub struct Share {
+    pub identifier: u16,
+    pub extendable: bool,
+    pub iteration_exponent: u8,
+    pub group_index: u8,
+    pub group_threshold: u8,
+    pub group_count: u8,
+    pub member_index: u8,
+    pub member_threshold: u8,
+    value: Zeroizing<Vec<u8>>,
+}
+
+impl Share {
+    // checks the words, the checksum, the padding and the parameters of a share mnemonic
+    pub fn parse(mnemonic: &str) -> Result<Self, KSError> {
+        let words = mnemonic
+            .split_whitespace()
+            .map(|word| {
+                WORDLIST
+                    .binary_search(&word.to_lowercase().as_str())
+                    .map(|index| index as u16)
+                    .map_err(|_| KSError::MnemonicError("share contains an unknown word".to_string()))
+            })
+            .collect::<Result<Vec<u16>, KSError>>()
+            .map(Zeroizing::new)?;
+        if words.len() < MIN_MNEMONIC_WORDS {
+            return Err(KSError::MnemonicError("share is too short".to_string()));
+        }
+        let id_exp = (words[0] as u32) << RADIX_BITS | words[1] as u32;
+        let extendable = id_exp >> 4 & 1 == 1;
+        if !verify_checksum(&words, extendable) {
+            return Err(KSError::MnemonicError("share has an invalid checksum".to_string()));
+        }
+        let params = (words[2] as u32) << RADIX_BITS | words[3] as u32;
+        let nibble = |shift: u32| (params >> shift & 0xf) as u8;
+        let share = Share {
+            identifier: (id_exp >> 5) as u16,
+            extendable,
+            iteration_exponent: (id_exp & 0xf) as u8,
+            group_index: nibble(16),
+            group_threshold: nibble(12) + 1,
+            group_count: nibble(8) + 1,
+            member_index: nibble(4),
+            member_threshold: nibble(0) + 1,
+            value: words_to_bytes(&words[HEADER_WORDS..words.len() - CHECKSUM_WORDS])?,
+        };
+        if share.group_threshold > share.group_count {
+            return Err(KSError::MnemonicError("group threshold exceeds the group count".to_string()));
+        }
+        if share.value.len() < MIN_SECRET_LENGTH {
+            return Err(KSError::MnemonicError("share value is too short".to_string()));
+        }
+        Ok(share)
+    }
+
+    pub fn to_mnemonic(&self) -> String {
+        let id_exp = (self.identifier as u32) << 5 | (self.extendable as u32) << 4 | self.iteration_exponent as u32;
+        let params = (self.group_index as u32) << 16
+            | (self.group_threshold as u32 - 1) << 12
+            | (self.group_count as u32 - 1) << 8
+            | (self.member_index as u32) << 4
+            | (self.member_threshold as u32 - 1);
+        let mut words = Zeroizing::new(vec![
+            (id_exp >> RADIX_BITS) as u16,
+            (id_exp & RADIX_MASK) as u16,
+            (params >> RADIX_BITS) as u16,
+            (params & RADIX_MASK) as u16,
+        ]);
+        words.extend(bytes_to_words(&self.value).iter());
+        let checksum = create_checksum(&words, self.extendable);
+        words.extend(checksum);
+        words.iter().map(|word| WORDLIST[*word as usize]).collect::<Vec<&str>>().join(" ")
+    }
+
+    // shares of one secret agree on everything but the group and member they belong to
+    fn is_compatible(&self, other: &Share) -> bool {
+        self.identifier == other.identifier
+            && self.extendable == other.extendable
+            && self.iteration_exponent == other.iteration_exponent
+            && self.group_threshold == other.group_threshold
+            && self.group_count == other.group_count
+            && self.value.len() == other.value.len()
+    }
+}
+
+pub fn validate_share(mnemonic: &str) -> Result<(), KSError> {
+    Share::parse(mnemonic).map(|_| ())
+}
+
+// splits the master secret in groups of share mnemonics, any group_threshold groups each with threshold members recover it
+pub fn generate_shares(
+    master_secret: &[u8],
+    passphrase: &str,
+    group_threshold: u8,
+    groups: &[GroupSpec],
+    iteration_exponent: u8,
+    extendable: bool,
+) -> Result<Vec<Vec<String>>, KSError> {
+    generate_shares_with_rng(master_secret, passphrase, group_threshold, groups, iteration_exponent, extendable, &mut OsRng)
+}
+
+fn generate_shares_with_rng<R: RngCore + CryptoRng>(
+    master_secret: &[u8],
+    passphrase: &str,
+    group_threshold: u8,
+    groups: &[GroupSpec],
+    iteration_exponent: u8,
+    extendable: bool,
+    rng: &mut R,
+) -> Result<Vec<Vec<String>>, KSError> {
+    if master_secret.len() < MIN_SECRET_LENGTH || !master_secret.len().is_multiple_of(2) {
+        return Err(KSError::MnemonicError(
+            "master secret should be an even number of bytes, at least 16".to_string(),
+        ));
+    }
+    check_passphrase(passphrase)?;
+    if iteration_exponent > MAX_ITERATION_EXPONENT {
+        return Err(KSError::MnemonicError("iteration exponent is too large".to_string()));
+    }
+    if groups.len() > MAX_SHARE_COUNT as usize || group_threshold == 0 || group_threshold as usize > groups.len() {
+        return Err(KSError::MnemonicError("invalid group threshold".to_string()));
+    }
+    for group in groups {
+        if group.threshold == 0 || group.threshold > group.count || group.count > MAX_SHARE_COUNT {
+            return Err(KSError::MnemonicError("invalid member threshold".to_string()));
+        }
+        // more than one share of a threshold 1 group would just be copies
+        if group.threshold == 1 && group.count > 1 {
+            return Err(KSError::MnemonicError("a member threshold of 1 allows a single share".to_string()));
+        }
+    }
+
+    let identifier = (rng.next_u32() & 0x7fff) as u16;
+    let encrypted = crypt(master_secret, passphrase, iteration_exponent, identifier, extendable, false);
+    let group_secrets = split_secret(group_threshold, groups.len() as u8, &encrypted, rng)?;
+    group_secrets
+        .iter()
+        .zip(groups)
+        .map(|((group_index, group_secret), group)| {
+            let members = split_secret(group.threshold, group.count, group_secret, rng)?;
+            Ok(members
+                .into_iter()
+                .map(|(member_index, value)| {
+                    Share {
+                        identifier,
+                        extendable,
+                        iteration_exponent,
+                        group_index: *group_index,
+                        group_threshold,
+                        group_count: groups.len() as u8,
+                        member_index,
+                        member_threshold: group.threshold,
+                        value,
+                    }
+                    .to_mnemonic()
+                })
+                .collect())
+        })
+        .collect()
+}
+
+// recovers the master secret from exactly group_threshold groups, each with exactly its member threshold of shares
+pub fn combine_shares(mnemonics: &[String], passphrase: &str) -> Result<Zeroizing<Vec<u8>>, KSError> {
+    check_passphrase(passphrase)?;
+    let shares = mnemonics
+        .iter()
+        .map(|mnemonic| Share::parse(mnemonic))
+        .collect::<Result<Vec<Share>, KSError>>()?;
+    let first = shares
+        .first()
+        .ok_or(KSError::MnemonicError("no share is provided".to_string()))?;
+    if shares.iter().any(|share| !first.is_compatible(share)) {
+        return Err(KSError::MnemonicError("shares do not belong to the same secret".to_string()));
+    }
+
+    let mut groups: BTreeMap<u8, Vec<&Share>> = BTreeMap::new();
+    shares.iter().for_each(|share| groups.entry(share.group_index).or_default().push(share));
+    if groups.len() != first.group_threshold as usize {
+        return Err(KSError::MnemonicError(format!(
+            "{} groups are required, {} provided",
+            first.group_threshold,
+            groups.len()
+        )));
+    }
+    let mut group_secrets = vec![];
+    for (group_index, members) in groups {
+        let threshold = members[0].member_threshold;
+        if members.iter().any(|member| member.member_threshold != threshold) {
+            return Err(KSError::MnemonicError("member thresholds of a group differ".to_string()));
+        }
+        let mut indexes: Vec<u8> = members.iter().map(|member| member.member_index).collect();
+        indexes.sort_unstable();
+        indexes.dedup();
+        if indexes.len() != members.len() || members.len() != threshold as usize {
+            return Err(KSError::MnemonicError(format!(
+                "group {} requires {} distinct shares",
+                group_index + 1,
+                threshold
+            )));
+        }
+        let values: Vec<(u8, &[u8])> = members.iter().map(|member| (member.member_index, member.value.as_slice())).collect();
+        group_secrets.push((group_index, recover_secret(threshold, &values)?));
+    }
+    let values: Vec<(u8, &[u8])> = group_secrets.iter().map(|(index, secret)| (*index, secret.as_slice())).collect();
+    let encrypted = recover_secret(first.group_threshold, &values)?;
+    Ok(crypt(&encrypted, passphrase, first.iteration_exponent, first.identifier, first.extendable, true))
+}
+
+// only printable ascii is allowed so the passphrase types the same everywhere
+fn check_passphrase(passphrase: &str) -> Result<(), KSError> {
+    match passphrase.bytes().all(|byte| (32..=126).contains(&byte)) {
+        true => Ok(()),
+        false => Err(KSError::MnemonicError("passphrase should be printable ascii".to_string())),
+    }
+}
+
+fn polymod(values: impl Iterator<Item = u32>) -> u32 {
+    values.fold(1, |checksum, value| {
+        let top = checksum >> 20;
+        let checksum = (checksum & 0xfffff) << RADIX_BITS ^ value;
+        CHECKSUM_GENERATOR
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| top >> i & 1 == 1)
+            .fold(checksum, |checksum, (_, generator)| checksum ^ generator)
+    })
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+    match extendable {
+        true => b"shamir_extendable",
+        false => b"shamir",
+    }
+}
+
+fn create_checksum(words: &[u16], extendable: bool) -> [u16; CHECKSUM_WORDS] {
+    let values = customization(extendable)
+        .iter()
+        .map(|byte| *byte as u32)
+        .chain(words.iter().map(|word| *word as u32))
+        .chain([0; CHECKSUM_WORDS]);
+    let checksum = polymod(values) ^ 1;
+    [2, 1, 0].map(|i| (checksum >> (i * RADIX_BITS) & RADIX_MASK) as u16)
+}
+
+fn verify_checksum(words: &[u16], extendable: bool) -> bool {
+    let values = customization(extendable)
+        .iter()
+        .map(|byte| *byte as u32)
+        .chain(words.iter().map(|word| *word as u32));
+    polymod(values) == 1
+}
+
+// the value is read as a big endian integer left padded with zero bits to whole words
+fn bytes_to_words(value: &[u8]) -> Zeroizing<Vec<u16>> {
+    let mut words = Zeroizing::new(vec![]);
+    let mut accumulator = 0u32;
+    let mut bits = (RADIX_BITS - value.len() * 8 % RADIX_BITS) % RADIX_BITS;
+    for byte in value {
+        accumulator = accumulator << 8 | *byte as u32;
+        bits += 8;
+        while bits >= RADIX_BITS {
+            bits -= RADIX_BITS;
+            words.push((accumulator >> bits & RADIX_MASK) as u16);
+        }
+        accumulator &= (1 << bits) - 1;
+    }
+    words
+}
+
+fn words_to_bytes(words: &[u16]) -> Result<Zeroizing<Vec<u8>>, KSError> {
+    let padding = words.len() * RADIX_BITS % 16;
+    if padding > 8 {
+        return Err(KSError::MnemonicError("share has an invalid length".to_string()));
+    }
+    if words.first().is_some_and(|word| *word as usize >> (RADIX_BITS - padding) != 0) {
+        return Err(KSError::MnemonicError("share has an invalid padding".to_string()));
+    }
+    let mut bytes = Zeroizing::new(vec![]);
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    for (i, word) in words.iter().enumerate() {
+        accumulator = accumulator << RADIX_BITS | *word as u32;
+        // the padding bits of the first word are zero and skipped
+        bits += if i == 0 { RADIX_BITS - padding } else { RADIX_BITS };
+        while bits >= 8 {
+            bits -= 8;
+            bytes.push((accumulator >> bits) as u8);
+        }
+        accumulator &= (1 << bits) - 1;
+    }
+    Ok(bytes)
+}
+
+// the Lagrange polynomial through the shares evaluated at x, bytewise over GF(256)
+fn interpolate(shares: &[(u8, &[u8])], x: u8) -> Zeroizing<Vec<u8>> {
+    if let Some((_, value)) = shares.iter().find(|(index, _)| *index == x) {
+        return Zeroizing::new(value.to_vec());
+    }
+    let (exp, log) = &GF_TABLES;
+    let log_product: usize = shares.iter().map(|(index, _)| log[(index ^ x) as usize] as usize).sum();
+    let mut result = Zeroizing::new(vec![0u8; shares[0].1.len()]);
+    for (index, value) in shares {
+        let log_denominator: usize = shares
+            .iter()
+            .filter(|(other, _)| other != index)
+            .map(|(other, _)| log[(index ^ other) as usize] as usize)
+            .sum();
+        let log_basis = (log_product + 255 * shares.len() - log[(index ^ x) as usize] as usize - log_denominator) % 255;
+        for (output, byte) in result.iter_mut().zip(value.iter()) {
+            if *byte != 0 {
+                *output ^= exp[(log[*byte as usize] as usize + log_basis) % 255];
+            }
+        }
+    }
+    result
+}
+
+fn create_digest(random: &[u8], secret: &[u8]) -> [u8; DIGEST_LENGTH] {
+    let mut hmac = Hmac::new(Sha256::new(), random);
+    hmac.input(secret);
+    let mut output = [0u8; 32];
+    hmac.raw_result(&mut output);
+    let mut digest = [0u8; DIGEST_LENGTH];
+    digest.copy_from_slice(&output[..DIGEST_LENGTH]);
+    digest
+}
+
+// a share or group value with its x coordinate
+type IndexedValue = (u8, Zeroizing<Vec<u8>>);
+
+// count shares of which any threshold recover the secret, the polynomial also passes through a digest of the secret
+fn split_secret<R: RngCore + CryptoRng>(
+    threshold: u8,
+    count: u8,
+    secret: &[u8],
+    rng: &mut R,
+) -> Result<Vec<IndexedValue>, KSError> {
+    if threshold == 1 {
+        return Ok((0..count).map(|index| (index, Zeroizing::new(secret.to_vec()))).collect());
+    }
+    let random_count = threshold - 2;
+    let mut shares: Vec<IndexedValue> = (0..random_count)
+        .map(|index| {
+            let mut value = Zeroizing::new(vec![0u8; secret.len()]);
+            rng.fill_bytes(&mut value);
+            (index, value)
+        })
+        .collect();
+    let mut random = Zeroizing::new(vec![0u8; secret.len() - DIGEST_LENGTH]);
+    rng.fill_bytes(&mut random);
+    let digest = Zeroizing::new([&create_digest(&random, secret)[..], &random].concat());
+    let interpolated: Vec<IndexedValue> = {
+        let base: Vec<(u8, &[u8])> = shares
+            .iter()
+            .map(|(index, value)| (*index, value.as_slice()))
+            .chain([(DIGEST_INDEX, digest.as_slice()), (SECRET_INDEX, secret)])
+            .collect();
+        (random_count..count).map(|index| (index, interpolate(&base, index))).collect()
+    };
+    shares.extend(interpolated);
+    Ok(shares)
+}
+
+fn recover_secret(threshold: u8, shares: &[(u8, &[u8])]) -> Result<Zeroizing<Vec<u8>>, KSError> {
+    if threshold == 1 {
+        return Ok(Zeroizing::new(shares[0].1.to_vec()));
+    }
+    let secret = interpolate(shares, SECRET_INDEX);
+    let digest = interpolate(shares, DIGEST_INDEX);
+    if create_digest(&digest[DIGEST_LENGTH..], &secret) != digest[..DIGEST_LENGTH] {
+        return Err(KSError::MnemonicError("shares do not recover a valid secret".to_string()));
+    }
+    Ok(secret)
+}
+
+// four round Feistel network keyed with pbkdf2 of the passphrase, decrypting runs the rounds backwards
+fn crypt(
+    secret: &[u8],
+    passphrase: &str,
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+    decrypt: bool,
+) -> Zeroizing<Vec<u8>> {
+    let salt = match extendable {
+        true => vec![],
+        false => [customization(false), &identifier.to_be_bytes()].concat(),
+    };
+    let iterations = (BASE_ITERATION_COUNT << iteration_exponent) / ROUND_COUNT as u32;
+    let half = secret.len() / 2;
+    let mut left = Zeroizing::new(secret[..half].to_vec());
+    let mut right = Zeroizing::new(secret[half..].to_vec());
+    for i in 0..ROUND_COUNT {
+        let round = if decrypt { ROUND_COUNT - 1 - i } else { i };
+        let password = Zeroizing::new([&[round], passphrase.as_bytes()].concat());
+        let mut hmac = Hmac::new(Sha256::new(), &password);
+        let mut output = Zeroizing::new(vec![0u8; right.len()]);
+        pbkdf2(&mut hmac, &[&salt, right.as_slice()].concat(), iterations, &mut output);
+        let next = Zeroizing::new(left.iter().zip(output.iter()).map(|(l, f)| l ^ f).collect::<Vec<u8>>());
+        left = std::mem::replace(&mut right, next);
+    }
+    Zeroizing::new([right.as_slice(), left.as_slice()].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    const SECRET: &str = "bb54aac4b89dc868ba37d9cc21b2cece";
+
+    fn mnemonics(shares: &[&str]) -> Vec<String> {
+        shares.iter().map(|share| share.to_string()).collect()
+    }
+
+    #[test]
+    fn it_should_recover_test_vectors() {
+        let shares = mnemonics(&["duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard"]);
+        assert_eq!(hex::encode(combine_shares(&shares, "TREZOR").unwrap().as_slice()), SECRET);
+
+        let shares = mnemonics(&[
+            "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+            "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+        ]);
+        let secret = combine_shares(&shares, "TREZOR").unwrap();
+        assert_eq!(hex::encode(secret.as_slice()), "b43ceb7e57a0ea8766221624d01b0864");
+        // a single share of a 2 of 3 group is not enough
+        assert!(combine_shares(&shares[..1], "TREZOR").is_err());
+        // every passphrase decrypts to some secret
+        assert_ne!(combine_shares(&shares, "").unwrap(), secret);
+    }
+
+    #[test]
+    fn it_should_encode_shares_like_the_vectors() {
+        let mnemonic = "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed";
+        let share = Share::parse(mnemonic).unwrap();
+        assert_eq!((share.identifier, share.iteration_exponent, share.extendable), (25653, 2, false));
+        assert_eq!((share.group_threshold, share.group_count), (1, 1));
+        assert_eq!((share.member_index, share.member_threshold), (2, 2));
+        assert_eq!(share.to_mnemonic(), mnemonic);
+    }
+
+    #[test]
+    fn it_should_split_in_groups() {
+        let secret = hex::decode(SECRET).unwrap();
+        let groups = [
+            GroupSpec { threshold: 1, count: 1 },
+            GroupSpec { threshold: 2, count: 3 },
+            GroupSpec { threshold: 3, count: 5 },
+        ];
+        let mut rng = ChaCha20Rng::from_seed([7u8; 32]);
+        let shares = generate_shares_with_rng(&secret, "TREZOR", 2, &groups, 0, false, &mut rng).unwrap();
+        assert_eq!(shares.iter().map(|group| group.len()).collect::<Vec<usize>>(), vec![1, 3, 5]);
+        assert!(shares.iter().flatten().all(|share| share.split(' ').count() == 20));
+
+        let combine = |picked: &[(usize, usize)]| {
+            let picked: Vec<String> = picked.iter().map(|(group, member)| shares[*group][*member].clone()).collect();
+            combine_shares(&picked, "TREZOR")
+        };
+        for picked in [
+            vec![(0, 0), (1, 0), (1, 2)],
+            vec![(1, 1), (1, 2), (2, 4), (2, 0), (2, 3)],
+            vec![(0, 0), (2, 1), (2, 2), (2, 3)],
+        ] {
+            assert_eq!(combine(&picked).unwrap().as_slice(), secret.as_slice());
+        }
+        // one group, a group short of its members, a repeated member and too many groups
+        assert!(combine(&[(1, 0), (1, 1)]).is_err());
+        assert!(combine(&[(0, 0), (2, 1), (2, 2)]).is_err());
+        assert!(combine(&[(0, 0), (1, 1), (1, 1)]).is_err());
+        assert!(combine(&[(0, 0), (1, 0), (1, 1), (2, 0), (2, 1), (2, 2)]).is_err());
+    }
+
+    #[test]
+    fn it_should_split_extendable_long_secrets() {
+        let secret: Vec<u8> = (0..32).collect();
+        let groups = [GroupSpec { threshold: 3, count: 5 }];
+        let shares = generate_shares(&secret, "", 1, &groups, 1, true).unwrap();
+        assert!(shares[0].iter().all(|share| share.split(' ').count() == 33));
+        let picked = vec![shares[0][4].clone(), shares[0][0].clone(), shares[0][2].clone()];
+        assert_eq!(combine_shares(&picked, "").unwrap().as_slice(), secret.as_slice());
+        assert!(Share::parse(&shares[0][0]).unwrap().extendable);
+        // shares of another split of the same secret don't mix
+        let other = generate_shares(&secret, "", 1, &groups, 1, true).unwrap();
+        let picked = vec![shares[0][0].clone(), shares[0][1].clone(), other[0][2].clone()];
+        assert!(combine_shares(&picked, "").is_err());
+    }
+
+    #[test]
+    fn it_should_reject_invalid_parameters() {
+        let secret = hex::decode(SECRET).unwrap();
+        let group = |threshold, count| GroupSpec { threshold, count };
+        assert!(generate_shares(&secret[..15], "", 1, &[group(1, 1)], 0, false).is_err());
+        assert!(generate_shares(&secret, "", 2, &[group(1, 1)], 0, false).is_err());
+        assert!(generate_shares(&secret, "", 1, &[group(1, 2)], 0, false).is_err());
+        assert!(generate_shares(&secret, "", 1, &[group(3, 2)], 0, false).is_err());
+        assert!(generate_shares(&secret, "", 1, &[group(2, 17)], 0, false).is_err());
+        assert!(generate_shares(&secret, "", 1, &[group(1, 1)], 16, false).is_err());
+        assert!(generate_shares(&secret, "pässword", 1, &[group(1, 1)], 0, false).is_err());
+    }
+
+    #[test]
+    fn it_should_validate_shares() {
+        let mnemonic = "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard";
+        assert!(validate_share(mnemonic).is_ok());
+        assert!(validate_share(&mnemonic.to_uppercase()).is_ok());
+        let error = validate_share(&mnemonic.replace("keyboard", "kidney")).unwrap_err();
+        assert_eq!(error.to_string(), "MnemonicError: share has an invalid checksum");
+        assert!(validate_share(&mnemonic.replace("fridge", "bridge")).is_err());
+        assert!(validate_share(&mnemonic.replacen("academic ", "", 1)).is_err());
+    }
+}
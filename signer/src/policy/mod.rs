@@ -0,0 +1,308 @@
+use crate::error::KSError;
+use crate::keymaster::{SigningAlgorithm, SigningOption};
+
+const DEFAULT_POLICY: &str = include_str!("default.policy");
+
+const CURVES: [(&str, SigningAlgorithm); 5] = [
+    ("secp256k1", SigningAlgorithm::Secp256k1),
+    ("secp256r1", SigningAlgorithm::Secp256R1),
+    ("ed25519", SigningAlgorithm::Ed25519),
+    ("sr25519", SigningAlgorithm::SR25519),
+    ("rsa", SigningAlgorithm::RSA),
+];
+
+const OPTIONS: [&str; 6] = ["none", "rsa", "ada", "secp256r1", "schnorr", "secp256k1"];
+
+fn curve_name(algo: SigningAlgorithm) -> &'static str {
+    CURVES.iter().find(|(_, curve)| *curve == algo).map(|(name, _)| *name).unwrap_or_default()
+}
+
+fn option_name(option: Option<SigningOption>) -> &'static str {
+    match option {
+        None => "none",
+        Some(SigningOption::RSA { .. }) => "rsa",
+        Some(SigningOption::ADA) => "ada",
+        Some(SigningOption::Secp256R1 { .. }) => "secp256r1",
+        Some(SigningOption::Schnorr { .. }) => "schnorr",
+        Some(SigningOption::Secp256k1 { .. }) => "secp256k1",
+    }
+}
+
+// a BIP32 path like m/44'/0'/0'/0/0, or a substrate path like //polkadot//0/1 whose "//" junctions are hard,
+// the empty path is the substrate root key
+#[derive(Debug, PartialEq, Eq)]
+struct PolicyPath {
+    substrate: bool,
+    levels: Vec<(String, bool)>,
+}
+
+impl PolicyPath {
+    fn parse(path: &str) -> Option<Self> {
+        if path.is_empty() || path.starts_with('/') {
+            // the password after "///" is not part of the derivation
+            let mut rest = path.split("///").next().unwrap_or_default();
+            let mut levels = vec![];
+            while !rest.is_empty() {
+                let hardened = rest.starts_with("//");
+                rest = &rest[if hardened { 2 } else { 1 }..];
+                let end = rest.find('/').unwrap_or(rest.len());
+                if end == 0 {
+                    return None;
+                }
+                levels.push((rest[..end].to_string(), hardened));
+                rest = &rest[end..];
+            }
+            return Some(Self { substrate: true, levels });
+        }
+        let path = path.to_lowercase();
+        let mut components = path.split('/');
+        if components.next() != Some("m") {
+            return None;
+        }
+        let levels = components
+            .map(|component| {
+                let (index, hardened) = match component.strip_suffix(['\'', 'h']) {
+                    Some(index) => (index, true),
+                    None => (component, false),
+                };
+                let index = index.parse::<u32>().ok().filter(|index| *index < 0x80000000)?;
+                Some((index.to_string(), hardened))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { substrate: false, levels })
+    }
+
+    fn starts_with(&self, prefix: &PolicyPath) -> bool {
+        self.substrate == prefix.substrate && self.levels.starts_with(&prefix.levels)
+    }
+}
+
+// line number, key and value of every entry of a section
+type Section<'a> = (String, Vec<(usize, &'a str, &'a str)>);
+
+struct ChainPolicy {
+    name: String,
+    paths: Vec<PolicyPath>,
+    curves: Vec<SigningAlgorithm>,
+    // None requires every level to be hardened
+    hardened: Option<usize>,
+    max_depth: usize,
+    options: Vec<&'static str>,
+}
+
+impl ChainPolicy {
+    fn check(&self, algo: SigningAlgorithm, path: &PolicyPath, option: Option<SigningOption>) -> Result<(), KSError> {
+        let violation = |reason: String| Err(KSError::PolicyViolation(format!("{}: {}", self.name, reason)));
+        if !self.curves.contains(&algo) {
+            return violation(format!("{} is not allowed", curve_name(algo)));
+        }
+        if path.levels.len() > self.max_depth {
+            return violation(format!("path is deeper than {} levels", self.max_depth));
+        }
+        let hardened = self.hardened.unwrap_or(path.levels.len());
+        if path.levels.len() < hardened || path.levels[..hardened].iter().any(|(_, hardened)| !hardened) {
+            return violation(format!("the first {} levels must be hardened", hardened));
+        }
+        if !self.options.contains(&option_name(option)) {
+            return violation(format!("signing option {} is not allowed", option_name(option)));
+        }
+        Ok(())
+    }
+}
+
+// allowed curves, path shapes and signing options per chain, checked before any key is fetched
+pub struct SigningPolicy {
+    chains: Vec<ChainPolicy>,
+}
+
+impl Default for SigningPolicy {
+    fn default() -> Self {
+        Self::from_config(DEFAULT_POLICY).expect("the default policy is valid")
+    }
+}
+
+impl SigningPolicy {
+    // ini style config, see default.policy for the format
+    pub fn from_config(config: &str) -> Result<Self, KSError> {
+        let mut sections: Vec<Section> = vec![];
+        for (number, line) in config.lines().enumerate().map(|(index, line)| (index + 1, line.trim())) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                sections.push((name.trim().to_string(), vec![]));
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid(number, "expected key = value"))?;
+            let (_, entries) = sections
+                .last_mut()
+                .ok_or_else(|| invalid(number, "entry outside of a section"))?;
+            entries.push((number, key.trim(), value.trim()));
+        }
+        let chains = sections
+            .into_iter()
+            .map(|(name, entries)| Self::parse_chain(name, entries))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { chains })
+    }
+
+    fn parse_chain(name: String, entries: Vec<(usize, &str, &str)>) -> Result<ChainPolicy, KSError> {
+        let mut paths = None;
+        let mut curves = None;
+        let mut hardened = None;
+        let mut max_depth = None;
+        let mut options = None;
+        for (number, key, value) in entries {
+            let list = value.split(',').map(str::trim).filter(|item| !item.is_empty());
+            match key {
+                "paths" => {
+                    let parsed = list
+                        .map(|path| PolicyPath::parse(path).ok_or_else(|| invalid(number, &format!("invalid path {}", path))))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    paths = Some(parsed);
+                }
+                "curves" => {
+                    let parsed = list
+                        .map(|curve| {
+                            CURVES
+                                .iter()
+                                .find(|(name, _)| *name == curve)
+                                .map(|(_, algo)| *algo)
+                                .ok_or_else(|| invalid(number, &format!("unknown curve {}", curve)))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    curves = Some(parsed);
+                }
+                "hardened" if value == "all" => hardened = Some(None),
+                "hardened" => {
+                    let levels = value.parse().map_err(|_| invalid(number, "hardened must be a number or all"))?;
+                    hardened = Some(Some(levels));
+                }
+                "max_depth" => {
+                    max_depth = Some(value.parse().map_err(|_| invalid(number, "max_depth must be a number"))?);
+                }
+                "options" => {
+                    let parsed = list
+                        .map(|option| {
+                            OPTIONS
+                                .iter()
+                                .find(|name| **name == option)
+                                .copied()
+                                .ok_or_else(|| invalid(number, &format!("unknown signing option {}", option)))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    options = Some(parsed);
+                }
+                _ => return Err(invalid(number, &format!("unknown key {}", key))),
+            }
+        }
+        let missing = |key: &str| KSError::InvalidPolicy(format!("[{}] is missing {}", name, key));
+        Ok(ChainPolicy {
+            paths: paths.ok_or_else(|| missing("paths"))?,
+            curves: curves.ok_or_else(|| missing("curves"))?,
+            hardened: hardened.ok_or_else(|| missing("hardened"))?,
+            max_depth: max_depth.ok_or_else(|| missing("max_depth"))?,
+            options: options.ok_or_else(|| missing("options"))?,
+            name,
+        })
+    }
+
+    // the first chain with a matching path prefix decides, paths of no chain are rejected
+    pub fn check(&self, algo: SigningAlgorithm, derivation_path: &str, option: Option<SigningOption>) -> Result<(), KSError> {
+        let path = PolicyPath::parse(derivation_path)
+            .ok_or_else(|| KSError::PolicyViolation(format!("invalid derivation path {}", derivation_path)))?;
+        let chain = self
+            .chains
+            .iter()
+            .find(|chain| chain.paths.iter().any(|prefix| path.starts_with(prefix)))
+            .ok_or_else(|| KSError::PolicyViolation(format!("no policy for {}", derivation_path)))?;
+        chain.check(algo, &path, option)
+    }
+}
+
+fn invalid(line: usize, reason: &str) -> KSError {
+    KSError::InvalidPolicy(format!("line {}: {}", line, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymaster::{SignatureEncoding, TaprootTweak};
+
+    fn violates(policy: &SigningPolicy, algo: SigningAlgorithm, path: &str, option: Option<SigningOption>) -> bool {
+        matches!(policy.check(algo, path, option), Err(KSError::PolicyViolation(_)))
+    }
+
+    #[test]
+    fn test_default_policy() {
+        let policy = SigningPolicy::default();
+        let schnorr = Some(SigningOption::Schnorr { tweak: Some(TaprootTweak { merkle_root: None }), aux_rand: None });
+        assert!(policy.check(SigningAlgorithm::Secp256k1, "m/84'/0'/0'/0/0", None).is_ok());
+        assert!(policy.check(SigningAlgorithm::Secp256k1, "m/86h/0h/0h/1/7", schnorr).is_ok());
+        assert!(policy.check(SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'", None).is_ok());
+        assert!(policy.check(SigningAlgorithm::Ed25519, "m/1852'/1815'/0'/0/0", Some(SigningOption::ADA)).is_ok());
+        assert!(policy.check(SigningAlgorithm::SR25519, "//polkadot//0/1///password", None).is_ok());
+        assert!(policy.check(SigningAlgorithm::RSA, "m/44'/472'", Some(SigningOption::RSA { salt_len: 32 })).is_ok());
+        let r1 = Some(SigningOption::Secp256R1 { encoding: SignatureEncoding::DER });
+        assert!(policy.check(SigningAlgorithm::Secp256R1, "m/74'/784'/0'/0/0", r1).is_ok());
+    }
+
+    #[test]
+    fn test_parse_substrate_root_path() {
+        let root = PolicyPath { substrate: true, levels: vec![] };
+        assert_eq!(PolicyPath::parse(""), Some(root));
+        assert_eq!(PolicyPath::parse("///password"), PolicyPath::parse(""));
+        assert!(!PolicyPath::parse("").unwrap().starts_with(&PolicyPath::parse("m").unwrap()));
+        assert!(violates(&SigningPolicy::default(), SigningAlgorithm::SR25519, "", None));
+    }
+
+    #[test]
+    fn test_default_policy_violations() {
+        let policy = SigningPolicy::default();
+        // wrong curve for the chain
+        assert!(violates(&policy, SigningAlgorithm::Ed25519, "m/84'/0'/0'/0/0", None));
+        assert!(violates(&policy, SigningAlgorithm::Secp256k1, "m/44'/501'/0'/0'", None));
+        // unknown chain and malformed paths
+        assert!(violates(&policy, SigningAlgorithm::Secp256k1, "m/44'/9999'/0'/0/0", None));
+        assert!(violates(&policy, SigningAlgorithm::Secp256k1, "m/44'/60'/0'/0/x", None));
+        assert!(violates(&policy, SigningAlgorithm::SR25519, "//westend", None));
+        // hardened levels and depth
+        assert!(violates(&policy, SigningAlgorithm::Secp256k1, "m/84'/0'/0/0/0", None));
+        assert!(violates(&policy, SigningAlgorithm::Secp256k1, "m/84'/0'/0'/0/0/0", None));
+        assert!(violates(&policy, SigningAlgorithm::Ed25519, "m/44'/501'/0'/0", None));
+        assert!(violates(&policy, SigningAlgorithm::SR25519, "/polkadot/0", None));
+        // signing options
+        assert!(violates(&policy, SigningAlgorithm::Ed25519, "m/1852'/1815'/0'/0/0", None));
+        assert!(violates(&policy, SigningAlgorithm::RSA, "m/44'/472'", None));
+        assert!(violates(&policy, SigningAlgorithm::Ed25519, "m/44'/501'/0'/0'", Some(SigningOption::ADA)));
+    }
+
+    #[test]
+    fn test_policy_config() {
+        let config = "
+            # ethereum only
+            [ethereum]
+            paths = m/44'/60'
+            curves = secp256k1, secp256r1
+            hardened = 2
+            max_depth = 3
+            options = none
+        ";
+        let policy = SigningPolicy::from_config(config).unwrap();
+        assert!(policy.check(SigningAlgorithm::Secp256R1, "m/44'/60'/0", None).is_ok());
+        assert!(violates(&policy, SigningAlgorithm::Secp256k1, "m/44'/60'/0/0", None));
+        assert!(violates(&policy, SigningAlgorithm::Secp256k1, "m/84'/0'/0'", None));
+
+        let invalid = |config: &str| matches!(SigningPolicy::from_config(config), Err(KSError::InvalidPolicy(_)));
+        assert!(invalid("paths = m/44'/60'"));
+        assert!(invalid("[ethereum]\npaths = m/44'/60'\ncurves = secp256k1\nhardened = 2\nmax_depth = 3"));
+        assert!(invalid("[ethereum]\npaths = m/44'/x'"));
+        assert!(invalid("[ethereum]\ncurves = p384"));
+        assert!(invalid("[ethereum]\noptions = taproot"));
+        assert!(invalid("[ethereum]\nhardened = some"));
+        assert!(invalid("[ethereum]\nmemo = 1"));
+        assert!(SigningPolicy::from_config("").unwrap().check(SigningAlgorithm::Secp256k1, "m/44'/60'", None).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+use zeroize::Zeroizing;
+
+// a password or passphrase, wiped on drop and redacted in Debug, there is no Clone so every copy is deliberate
+pub struct SecretPassword(Zeroizing<String>);
+
+impl SecretPassword {
+    pub fn new(password: String) -> Self {
+        Self(Zeroizing::new(password))
+    }
+
+    pub fn expose(&self) -> &str {
+        self.0.as_str()
+    }
+
+    // the SE takes the password as hex encoded bytes, None when it is not hex
+    pub(crate) fn decode_hex(&self) -> Option<SecretBytes> {
+        hex::decode(self.expose()).ok().map(SecretBytes::new)
+    }
+}
+
+impl From<String> for SecretPassword {
+    fn from(password: String) -> Self {
+        Self::new(password)
+    }
+}
+
+impl From<&str> for SecretPassword {
+    fn from(password: &str) -> Self {
+        Self::new(password.to_string())
+    }
+}
+
+impl fmt::Debug for SecretPassword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretPassword(..)")
+    }
+}
+
+// key material, tokens and other secret bytes, wiped on drop and redacted in Debug
+pub struct SecretBytes(Zeroizing<Vec<u8>>);
+
+impl SecretBytes {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(Zeroizing::new(bytes))
+    }
+
+    // an explicit copy, the source is left to its owner
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        Self::new(bytes.to_vec())
+    }
+
+    pub fn expose(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+
+    pub(crate) fn expose_mut(&mut self) -> &mut [u8] {
+        self.0.as_mut_slice()
+    }
+
+    pub(crate) fn into_zeroizing(self) -> Zeroizing<Vec<u8>> {
+        self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<Zeroizing<Vec<u8>>> for SecretBytes {
+    fn from(bytes: Zeroizing<Vec<u8>>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretBytes(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_redact_secrets() {
+        let password = SecretPassword::from("test_pass");
+        assert_eq!(format!("{:?}", password), "SecretPassword(..)");
+        assert_eq!(password.expose(), "test_pass");
+        let bytes = SecretBytes::new(vec![0x01, 0x02]);
+        assert_eq!(format!("{:?}", bytes), "SecretBytes(..)");
+        assert_eq!(bytes.expose(), &[0x01, 0x02]);
+    }
+
+    #[test]
+    fn it_should_decode_hex_password() {
+        let password = SecretPassword::from("f6cd");
+        assert_eq!(password.decode_hex().unwrap().expose(), &[0xf6, 0xcd]);
+        assert!(SecretPassword::from("test_pass").decode_hex().is_none());
+    }
+}
@@ -5,6 +5,14 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use core::error::Error;
 
+mod transaction;
+
+pub use crate::transaction::{overview_kind, Amount, Detail, Field, Overview, ParsedTransaction};
+
 pub trait Chain<E: Error> {
+    /// The chain specific JSON of the transaction.
     fn parse(data: &Vec<u8>) -> Result<String, E>;
+
+    /// The transaction in the model shared by all chains, `raw` holds the JSON of `parse`.
+    fn parse_transaction(data: &Vec<u8>) -> Result<ParsedTransaction, E>;
 }
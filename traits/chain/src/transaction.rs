@@ -0,0 +1,83 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// The chain independent view of a parsed transaction, what a wallet shows before signing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedTransaction {
+    pub overview: Overview,
+    /// one entry per action, instruction or message of the transaction, in order
+    pub detail: Vec<Detail>,
+    /// things the signer should look at twice, e.g. an instruction that could not be decoded
+    pub warnings: Vec<String>,
+    /// the chain specific JSON, the same as Chain::parse returns
+    pub raw: String,
+}
+
+/// Summary of the transaction, fields the chain does not carry are None.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Overview {
+    /// e.g. "Transfer", the kind of the only detail entry or "Batch" for several of them
+    pub kind: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub amount: Option<Amount>,
+    pub fee: Option<Amount>,
+    pub network: Option<String>,
+}
+
+/// An integer amount in the smallest unit of its asset, e.g. 1000000 lamports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Amount {
+    pub value: String,
+    pub unit: String,
+}
+
+impl Amount {
+    pub fn new(value: impl Into<String>, unit: impl Into<String>) -> Self {
+        Self { value: value.into(), unit: unit.into() }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Detail {
+    pub kind: String,
+    pub fields: Vec<Field>,
+}
+
+impl Detail {
+    pub fn new(kind: impl Into<String>) -> Self {
+        Self { kind: kind.into(), fields: Vec::new() }
+    }
+
+    pub fn with_field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push(Field { name: name.into(), value: value.into() });
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub value: String,
+}
+
+// "Batch" when a transaction carries several actions, otherwise the kind of the only one
+pub fn overview_kind(detail: &[Detail]) -> String {
+    match detail {
+        [only] => only.kind.clone(),
+        [] => String::from("Unknown"),
+        _ => String::from("Batch"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overview_kind() {
+        assert_eq!(overview_kind(&[]), "Unknown");
+        assert_eq!(overview_kind(&[Detail::new("Transfer")]), "Transfer");
+        assert_eq!(overview_kind(&[Detail::new("Transfer"), Detail::new("Stake")]), "Batch");
+    }
+}
@@ -0,0 +1,33 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::WasmError;
+
+#[wasm_bindgen(typescript_custom_section)]
+const APTOS_TYPES: &'static str = r#"
+export interface AptosTransaction {
+    raw_json: AptosRawTransaction;
+    formatted_json: AptosRawTransaction;
+}
+
+export interface AptosRawTransaction {
+    sender: string;
+    sequence_number: number;
+    payload: Record<string, unknown>;
+    max_gas_amount: number;
+    gas_unit_price: number;
+    expiration_timestamp_secs: number;
+    chain_id: number;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "AptosTransaction")]
+    pub type AptosTransaction;
+}
+
+/// Parses a BCS encoded aptos RawTransaction.
+#[wasm_bindgen(js_name = aptosParse)]
+pub fn aptos_parse(data: &[u8]) -> Result<AptosTransaction, WasmError> {
+    super::parse::<rcc_aptos::Aptos, _, _>(data)
+}
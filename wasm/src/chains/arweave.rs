@@ -0,0 +1,45 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::WasmError;
+
+#[wasm_bindgen(typescript_custom_section)]
+const ARWEAVE_TYPES: &'static str = r#"
+export type ArweaveTransaction =
+    | {
+        status: "success";
+        raw_json: ArweaveRawTransaction;
+        formatted_json: {
+            owner: string;
+            target: string;
+            quantity: number;
+            reward: number;
+            data_size: number;
+            signature_data: string;
+        };
+    }
+    | { status: "failed"; reason: string };
+
+export interface ArweaveRawTransaction {
+    format: number;
+    owner: string;
+    reward: string;
+    target: string;
+    last_tx: string;
+    quantity: string;
+    tags: { name: string; value: string }[];
+    data_size: string;
+    data_root: string;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "ArweaveTransaction")]
+    pub type ArweaveTransaction;
+}
+
+/// Parses the JSON of an arweave transaction, a malformed one is reported with status "failed".
+#[wasm_bindgen(js_name = arweaveParse)]
+pub fn arweave_parse(data: &[u8]) -> Result<ArweaveTransaction, WasmError> {
+    super::parse::<rcc_arweave::Arweave, _, _>(data)
+}
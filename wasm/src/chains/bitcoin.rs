@@ -0,0 +1,14 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::WasmError;
+
+/// Derives the address at `path` below an xpub, ypub or zpub, `scriptType` is one of
+/// "P2PKH", "P2SH-P2WPKH" or "P2WPKH".
+#[wasm_bindgen(js_name = bitcoinDeriveAddress)]
+pub fn bitcoin_derive_address(
+    xpub: String,
+    path: String,
+    #[wasm_bindgen(js_name = scriptType)] script_type: String,
+) -> Result<String, WasmError> {
+    Ok(rcc_bitcoin::derive_address(xpub, path, script_type)?)
+}
@@ -0,0 +1,37 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::WasmError;
+
+#[wasm_bindgen(typescript_custom_section)]
+const COSMOS_TYPES: &'static str = r#"
+export interface CosmosSignDoc {
+    body: {
+        msgs: { type: string; value: Record<string, unknown> }[];
+        memo: string;
+        timeout_height: number;
+    };
+    auth_info: {
+        signer_infos: Record<string, unknown>[];
+        fee: {
+            amount: { denom: string; amount: string }[];
+            gas: number;
+            payer: string;
+            granter: string;
+        };
+    };
+    chain_id: string;
+    account_number: number;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "CosmosSignDoc")]
+    pub type CosmosSignDoc;
+}
+
+/// Parses a protobuf encoded cosmos SignDoc.
+#[wasm_bindgen(js_name = cosmosParse)]
+pub fn cosmos_parse(data: &[u8]) -> Result<CosmosSignDoc, WasmError> {
+    super::parse::<rcc_cosmos::Cosmos, _, _>(data)
+}
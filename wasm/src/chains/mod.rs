@@ -0,0 +1,36 @@
+use rcc_trait_chain::Chain;
+use wasm_bindgen::JsCast;
+
+use crate::error::WasmError;
+
+#[cfg(feature = "aptos")]
+pub(crate) mod aptos;
+#[cfg(feature = "arweave")]
+pub(crate) mod arweave;
+#[cfg(feature = "bitcoin")]
+pub(crate) mod bitcoin;
+#[cfg(feature = "cosmos")]
+pub(crate) mod cosmos;
+#[cfg(feature = "near")]
+pub(crate) mod near;
+#[cfg(feature = "solana")]
+pub(crate) mod solana;
+
+// the JSON the chain crates produce as the JS value of its typescript type
+pub fn from_json<T: JsCast>(json: &str) -> Result<T, WasmError> {
+    js_sys::JSON::parse(json)
+        .map(JsCast::unchecked_into)
+        .map_err(|_| WasmError::new("SerializeFailed", "output is not valid JSON"))
+}
+
+// Chain::parse of any chain crate, the raw transaction in and its typed JSON out
+#[allow(dead_code)]
+pub(crate) fn parse<C, E, T>(data: &[u8]) -> Result<T, WasmError>
+where
+    C: Chain<E>,
+    E: std::error::Error + Into<WasmError>,
+    T: JsCast,
+{
+    let json = C::parse(&data.to_vec()).map_err(Into::into)?;
+    from_json(&json)
+}
@@ -0,0 +1,33 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::WasmError;
+
+#[wasm_bindgen(typescript_custom_section)]
+const NEAR_TYPES: &'static str = r#"
+export interface NearTransaction {
+    raw_json: NearRawTransaction;
+    formatted_json: NearRawTransaction;
+}
+
+export interface NearRawTransaction {
+    signer_id: string;
+    public_key: string;
+    nonce: number;
+    receiver_id: string;
+    block_hash: string;
+    hash: string;
+    actions: Record<string, unknown>[];
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "NearTransaction")]
+    pub type NearTransaction;
+}
+
+/// Parses a borsh encoded near Transaction.
+#[wasm_bindgen(js_name = nearParse)]
+pub fn near_parse(data: &[u8]) -> Result<NearTransaction, WasmError> {
+    super::parse::<rcc_near::Near, _, _>(data)
+}
@@ -0,0 +1,49 @@
+use rcc_solana::Sol;
+use wasm_bindgen::prelude::*;
+
+use crate::error::WasmError;
+
+#[wasm_bindgen(typescript_custom_section)]
+const SOLANA_TYPES: &'static str = r#"
+export interface SolanaMessage {
+    header: {
+        num_required_signatures: number;
+        num_readonly_signed_accounts: number;
+        num_readonly_unsigned_accounts: number;
+    };
+    accounts: string[];
+    block_hash: string;
+    instructions: SolanaInstruction[];
+}
+
+export interface SolanaInstruction {
+    raw: {
+        program_index: number;
+        program_account: string;
+        account_indexes: number[];
+        accounts: string;
+        data: string;
+    };
+    /** the decoded instruction, or the reason it could not be decoded */
+    readable: Record<string, unknown> | string;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "SolanaMessage")]
+    pub type SolanaMessage;
+}
+
+/// Parses a serialized solana Message.
+#[wasm_bindgen(js_name = solanaParseMessage)]
+pub fn solana_parse_message(message: &[u8]) -> Result<SolanaMessage, WasmError> {
+    let json = Sol::parse_message_to_json(&mut message.to_vec())?;
+    super::from_json(&json)
+}
+
+/// Whether `message` is a well formed solana Message.
+#[wasm_bindgen(js_name = solanaValidateMessage)]
+pub fn solana_validate_message(message: &[u8]) -> bool {
+    Sol::validate_message(&mut message.to_vec())
+}
@@ -0,0 +1,132 @@
+use js_sys::{Error, Reflect};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const RCC_ERROR: &'static str = r#"
+/** Thrown by every function on failure, `code` names the error variant of the chain crate. */
+export interface RccError extends Error {
+    name: "RccError";
+    code: RccErrorCode;
+}
+
+export type RccErrorCode =
+    | "SerializeFailed"
+    | "AptosParseFailed"
+    | "AptosSerializeFailed"
+    | "ArweaveBase64Decode"
+    | "ArweaveFromUtf8"
+    | "BitcoinAddressDerivationFailed"
+    | "BitcoinInvalidTransaction"
+    | "CosmosParseFailed"
+    | "CosmosSerializeFailed"
+    | "NearParseFailed"
+    | "NearSerializeFailed"
+    | "SolanaUnsupportedProgram"
+    | "SolanaInvalidData"
+    | "SolanaProgramError"
+    | "SolanaAccountNotFound"
+    // thrown by rcc-wasm-sui
+    | "SuiBcsDecodingError"
+    | "SuiInvalidTransaction"
+    | "SuiSignFailure"
+    | "SuiInvalidHDPath"
+    | "SuiKeystoreError"
+    | "SuiInvalidAddressError";
+"#;
+
+/// Failure of a call, thrown to JS as an RccError.
+#[derive(Debug)]
+pub struct WasmError {
+    code: &'static str,
+    message: String,
+}
+
+impl WasmError {
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl From<WasmError> for JsValue {
+    fn from(error: WasmError) -> Self {
+        let js_error = Error::new(&error.message);
+        js_error.set_name("RccError");
+        // setting a property on a fresh Error object does not fail
+        let _ = Reflect::set(&js_error, &"code".into(), &error.code.into());
+        js_error.into()
+    }
+}
+
+#[cfg(feature = "aptos")]
+impl From<rcc_aptos::AptosError> for WasmError {
+    fn from(error: rcc_aptos::AptosError) -> Self {
+        use rcc_aptos::AptosError;
+        let code = match error {
+            AptosError::ParseFailed(_) => "AptosParseFailed",
+            AptosError::SerializeFailed(_) => "AptosSerializeFailed",
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "arweave")]
+impl From<rcc_arweave::ArweaveError> for WasmError {
+    fn from(error: rcc_arweave::ArweaveError) -> Self {
+        use rcc_arweave::ArweaveError;
+        let code = match error {
+            ArweaveError::Base64Decode(_) => "ArweaveBase64Decode",
+            ArweaveError::FromUtf8(_) => "ArweaveFromUtf8",
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "bitcoin")]
+impl From<rcc_bitcoin::BitcoinError> for WasmError {
+    fn from(error: rcc_bitcoin::BitcoinError) -> Self {
+        use rcc_bitcoin::BitcoinError;
+        let code = match error {
+            BitcoinError::AddressDerivationFailed(_) => "BitcoinAddressDerivationFailed",
+            BitcoinError::InvalidTransaction(_) => "BitcoinInvalidTransaction",
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "cosmos")]
+impl From<rcc_cosmos::CosmosError> for WasmError {
+    fn from(error: rcc_cosmos::CosmosError) -> Self {
+        use rcc_cosmos::CosmosError;
+        let code = match error {
+            CosmosError::ParseFailed(_) => "CosmosParseFailed",
+            CosmosError::SerializeFailed(_) => "CosmosSerializeFailed",
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "near")]
+impl From<rcc_near::NearError> for WasmError {
+    fn from(error: rcc_near::NearError) -> Self {
+        use rcc_near::NearError;
+        let code = match error {
+            NearError::ParseFailed(_) => "NearParseFailed",
+            NearError::SerializeFailed(_) => "NearSerializeFailed",
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(feature = "solana")]
+impl From<rcc_solana::SolanaError> for WasmError {
+    fn from(error: rcc_solana::SolanaError) -> Self {
+        use rcc_solana::SolanaError;
+        let code = match error {
+            SolanaError::UnsupportedProgram(_) => "SolanaUnsupportedProgram",
+            SolanaError::InvalidData(_) => "SolanaInvalidData",
+            SolanaError::ProgramError(_) => "SolanaProgramError",
+            SolanaError::AccountNotFound(_) => "SolanaAccountNotFound",
+        };
+        Self::new(code, error.to_string())
+    }
+}
@@ -0,0 +1,24 @@
+// wasm-bindgen bindings of the chain parsers for browsers and Node, every parser returns the JSON of its
+// chain crate as a plain JS object typed in the generated .d.ts and throws an RccError on failure.
+mod chains;
+mod error;
+
+#[cfg(feature = "aptos")]
+pub use crate::chains::aptos::{aptos_parse, AptosTransaction};
+#[cfg(feature = "arweave")]
+pub use crate::chains::arweave::{arweave_parse, ArweaveTransaction};
+#[cfg(feature = "bitcoin")]
+pub use crate::chains::bitcoin::bitcoin_derive_address;
+#[cfg(feature = "cosmos")]
+pub use crate::chains::cosmos::{cosmos_parse, CosmosSignDoc};
+#[cfg(feature = "near")]
+pub use crate::chains::near::{near_parse, NearTransaction};
+#[cfg(feature = "solana")]
+pub use crate::chains::solana::{solana_parse_message, solana_validate_message, SolanaMessage};
+pub use crate::error::WasmError;
+
+// for the bindings of chains that cannot be built with this crate, see sui/
+#[doc(hidden)]
+pub mod ext {
+    pub use crate::chains::from_json;
+}
@@ -0,0 +1,68 @@
+// rcc-wasm plus the sui parser
+//
+// rcc_sui sets cargo-features in its manifest, so any crate depending on it, even optionally, needs a nightly
+// cargo. This module links rcc-wasm in and exports all of its functions next to the sui ones.
+pub use rcc_wasm::*;
+
+use rcc_sui::errors::SuiError;
+use rcc_wasm::ext::from_json;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const SUI_TYPES: &'static str = r#"
+export interface SuiTransactionData {
+    V1: {
+        sender: string;
+        gas_data: {
+            payment: [string, number, string][];
+            owner: string;
+            price: number;
+            budget: number;
+        };
+        kind: Record<string, unknown>;
+        expiration: unknown;
+    };
+}
+
+export interface SuiPersonalMessage {
+    message: number[];
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "SuiTransactionData")]
+    pub type SuiTransactionData;
+    #[wasm_bindgen(typescript_type = "SuiPersonalMessage")]
+    pub type SuiPersonalMessage;
+}
+
+fn sui_error(error: SuiError) -> WasmError {
+    let code = match error {
+        SuiError::BcsDecodingError(_) => "SuiBcsDecodingError",
+        SuiError::InvalidTransaction => "SuiInvalidTransaction",
+        SuiError::SignFailure(_) => "SuiSignFailure",
+        SuiError::InvalidHDPath(_) => "SuiInvalidHDPath",
+        SuiError::KeystoreError(_) => "SuiKeystoreError",
+        SuiError::InvalidAddressError(_) => "SuiInvalidAddressError",
+    };
+    WasmError::new(code, error.to_string())
+}
+
+fn serialize_error(error: serde_json::Error) -> WasmError {
+    WasmError::new("SerializeFailed", error.to_string())
+}
+
+/// Parses BCS encoded sui TransactionData.
+#[wasm_bindgen(js_name = suiParseTx)]
+pub fn sui_parse_tx(tx: &[u8]) -> Result<SuiTransactionData, WasmError> {
+    let tx = rcc_sui::parse_tx(tx.to_vec()).map_err(sui_error)?;
+    from_json(&serde_json::to_string(&tx).map_err(serialize_error)?)
+}
+
+/// Parses a BCS encoded sui personal message.
+#[wasm_bindgen(js_name = suiParseMsg)]
+pub fn sui_parse_msg(msg: &[u8]) -> Result<SuiPersonalMessage, WasmError> {
+    let msg = rcc_sui::parse_msg(msg.to_vec()).map_err(sui_error)?;
+    from_json(&serde_json::to_string(&msg).map_err(serialize_error)?)
+}
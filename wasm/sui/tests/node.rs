@@ -0,0 +1,26 @@
+// run in Node with `cargo test`, see ../.cargo/config.toml
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Array, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+
+fn get(value: &JsValue, path: &[&str]) -> JsValue {
+    path.iter().fold(value.clone(), |value, key| Reflect::get(&value, &JsValue::from_str(key)).unwrap())
+}
+
+fn error_code<T>(result: Result<T, rcc_wasm_sui::WasmError>) -> JsValue {
+    let error = JsValue::from(result.err().expect("the call should fail"));
+    assert!(error.is_instance_of::<js_sys::Error>());
+    assert_eq!(get(&error, &["name"]), "RccError");
+    get(&error, &["code"])
+}
+
+#[wasm_bindgen_test]
+fn it_should_parse_sui_personal_message() {
+    let msg = rcc_wasm_sui::sui_parse_msg(&hex::decode("0a48656c6c6f2c20537569").unwrap()).unwrap();
+    let message = get(&msg, &["message"]).dyn_into::<Array>().unwrap();
+    assert_eq!(message.length(), 10);
+    assert_eq!(message.get(0), 72);
+    assert_eq!(error_code(rcc_wasm_sui::sui_parse_tx(&[0])), "SuiBcsDecodingError");
+}
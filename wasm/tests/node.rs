@@ -0,0 +1,94 @@
+// run in Node with `cargo test`, see .cargo/config.toml
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Array, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+
+fn get(value: &JsValue, path: &[&str]) -> JsValue {
+    path.iter().fold(value.clone(), |value, key| Reflect::get(&value, &JsValue::from_str(key)).unwrap())
+}
+
+fn error_code<T>(result: Result<T, rcc_wasm::WasmError>) -> JsValue {
+    let error = JsValue::from(result.err().expect("the call should fail"));
+    assert!(error.is_instance_of::<js_sys::Error>());
+    assert_eq!(get(&error, &["name"]), "RccError");
+    get(&error, &["code"])
+}
+
+#[cfg(feature = "solana")]
+const SOLANA_MESSAGE: &str = "01000103c8d842a2f17fd7aab608ce2ea535a6e958dffa20caf669b347b911c4171965530f957620b228bae2b94c82ddd4c093983a67365555b737ec7ddc1117e61c72e0000000000000000000000000000000000000000000000000000000000000000010295cc2f1f39f3604718496ea00676d6a72ec66ad09d926e3ece34f565f18d201020200010c0200000000e1f50500000000";
+
+#[cfg(feature = "solana")]
+#[wasm_bindgen_test]
+fn it_should_parse_solana_message() {
+    let message = rcc_wasm::solana_parse_message(&hex::decode(SOLANA_MESSAGE).unwrap()).unwrap();
+    assert_eq!(get(&message, &["header", "num_required_signatures"]), 1);
+    assert_eq!(get(&message, &["block_hash"]), "26673efpV4o6Cv5ZnEfYp3M18nkqhg6tyXF2A2JzeoCd");
+    let instructions = get(&message, &["instructions"]).dyn_into::<Array>().unwrap();
+    assert_eq!(instructions.length(), 1);
+    assert_eq!(get(&instructions.get(0), &["readable", "method_name"]), "Transfer");
+    assert_eq!(get(&instructions.get(0), &["readable", "details", "amount"]), "100000000");
+}
+
+#[cfg(feature = "solana")]
+#[wasm_bindgen_test]
+fn it_should_validate_solana_message() {
+    assert!(rcc_wasm::solana_validate_message(&hex::decode(SOLANA_MESSAGE).unwrap()));
+    assert!(!rcc_wasm::solana_validate_message(b"Lorem ipsum dolor sit amet"));
+    assert_eq!(error_code(rcc_wasm::solana_parse_message(&[1])), "SolanaInvalidData");
+}
+
+#[cfg(feature = "bitcoin")]
+#[wasm_bindgen_test]
+fn it_should_derive_bitcoin_address() {
+    let address = rcc_wasm::bitcoin_derive_address(
+        "xpub6CPbc6auq3b8rEkXz6y78esTJ1SXLZwTJezUxoE8B7KZDfKo1qwJkHziR8MMat7P6RNf3aUPrUpMuKFQ8TRbfenCk6UvzCJXs1dHBdz2vcE".to_string(),
+        "m/0/0".to_string(),
+        "P2PKH".to_string(),
+    );
+    assert_eq!(address.unwrap(), "1Kw42PtjJV4VWYzDfaPekqy1V4kgXEpqz8");
+    let error = rcc_wasm::bitcoin_derive_address("xpub".to_string(), "m/0/0".to_string(), "P2PKH".to_string());
+    assert_eq!(error_code(error), "BitcoinAddressDerivationFailed");
+}
+
+#[cfg(feature = "aptos")]
+#[wasm_bindgen_test]
+fn it_should_parse_aptos_transaction() {
+    let data = hex::decode("8bbbb70ae8b90a8686b2a27f10e21e44f2fb64ffffcaa4bb0242e9f1ea698659010000000000000002000000000000000000000000000000000000000000000000000000000000000104636f696e087472616e73666572010700000000000000000000000000000000000000000000000000000000000000010a6170746f735f636f696e094170746f73436f696e000220834f4b75dcaacbd7c549a993cdd3140676e172d1fee0609bf6876c74aaa7116008400d0300000000009a0e0000000000006400000000000000b6b747630000000021").unwrap();
+    let tx = rcc_wasm::aptos_parse(&data).unwrap();
+    assert_eq!(get(&tx, &["formatted_json", "sender"]), "8bbbb70ae8b90a8686b2a27f10e21e44f2fb64ffffcaa4bb0242e9f1ea698659");
+    assert_eq!(get(&tx, &["formatted_json", "chain_id"]), 33);
+    assert_eq!(error_code(rcc_wasm::aptos_parse(&[0])), "AptosParseFailed");
+}
+
+#[cfg(feature = "arweave")]
+#[wasm_bindgen_test]
+fn it_should_parse_arweave_transaction() {
+    let data = hex::decode("7b22666f726d6174223a322c226964223a227964426e4544586b6c74697248465f38396e446141654a51737968366f6356464836773362476462344551222c226c6173745f7478223a224967497a6b54724e413153472d61594c5647514d316555456d5f622d374a713346385f4243555f6a3655763235727a38307a464767433451645f51617a73792d222c226f776e6572223a2273535757445652386a4262656f47656d79344d31567568554d5f304375735852586454617669343650555663503831344b6d33737a6d566c6b37316c5f2d7242596a584e79724d7232355f4c574d48454f495179667132446e2d323969426259716b36303853476a33594849324c6e52367a3364784c4256787754356174547535695a6e4d665a2d2d4e51696e413634694947725451336e473241333173356c44543538565173583741465f656156437757596f79434b76556a4b6564754e6d5553496a476c37736974722d746f6f54646362416873686b6135354c4371643662724f32345a7a42306969655777727741794e344c5a70636a783336644d61523971314c356e5935642d426e535a68566b322d5f4138532d50375766527275645453555a4d46386656396f3243643535542d6835776c48503558786835424f3435545a656d7746527a464b5833714945774b4e5a785f75434468546c4d6d4b7078744d616a3269302d677849315165587248597637364b66675a79325532584d573248344d7066725f574f324b4d325f623663575155656e50446e717a67525873713647586448554667562d7169314d5f69344d4345364144352d516c50515f516342615f5a666c646f77464c325041435f68796b457944665253374d77785f467734376737307356625745314450394d456678474339766d4f69446564726f4732457176543056704d2d78497a7a6870475057485f7a4e46634e53716a38735f7a537171746e58746c635474626b373649704b38744b6a4466494871314a485a675f5f776f6279664d30666941714836664a617455434c6757504754624a394d3436754a7738624649373270795f324d647278426b572d734e426f6132315f672d3646554e48346448574138674745776b61352d424d6e76614d5970414b417151322d47475977317045222c2274616773223a5b7b226e616d65223a22515842774c553568625755222c2276616c7565223a2251584a44623235755a574e30227d2c7b226e616d65223a22515842774c565a6c636e4e70623234222c2276616c7565223a224d4334304c6a49227d2c7b226e616d65223a225132397564475675644331556558426c222c2276616c7565223a226447563464433977624746706267227d5d2c22746172676574223a2267483034555f4d4476684b64456553564871466e6b78377869346473754b6a39344f3971526835304c5351222c227175616e74697479223a223130303030303030222c2264617461223a22222c22646174615f73697a65223a2230222c22646174615f726f6f74223a22222c22726577617264223a2231343130353037383534227d").unwrap();
+    let tx = rcc_wasm::arweave_parse(&data).unwrap();
+    assert_eq!(get(&tx, &["status"]), "success");
+    assert_eq!(get(&tx, &["formatted_json", "quantity"]), 10000000);
+    let tx = rcc_wasm::arweave_parse(b"{}").unwrap();
+    assert_eq!(get(&tx, &["status"]), "failed");
+}
+
+#[cfg(feature = "cosmos")]
+#[wasm_bindgen_test]
+fn it_should_parse_cosmos_sign_doc() {
+    let data = hex::decode("0a8f010a8c010a1c2f636f736d6f732e62616e6b2e763162657461312e4d736753656e64126c0a2d636f736d6f7331786573766b723664306a39366a357a64637735666d717861766a767576717832796779376d70122d636f736d6f7331786573766b723664306a39366a357a64637735666d717861766a767576717832796779376d701a0c0a057374616b65120331303012580a500a460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a21035bc6eee695a089c273b690d7123c84cf6dbcb91e613c8b60b79422a1ee68490612040a0208011803120410c09a0c1a04746573742001").unwrap();
+    let doc = rcc_wasm::cosmos_parse(&data).unwrap();
+    assert_eq!(get(&doc, &["chain_id"]), "test");
+    let msgs = get(&doc, &["body", "msgs"]).dyn_into::<Array>().unwrap();
+    assert_eq!(get(&msgs.get(0), &["type"]), "/cosmos.bank.v1beta1.MsgSend");
+    assert_eq!(error_code(rcc_wasm::cosmos_parse(&[0xff])), "CosmosParseFailed");
+}
+
+#[cfg(feature = "near")]
+#[wasm_bindgen_test]
+fn it_should_parse_near_transaction() {
+    let data = hex::decode("40000000353862633234353938303464326564383736343166626465343062306439363334316362663033313362376466346263346636306661326634326336303263330058bc2459804d2ed87641fbde40b0d96341cbf0313b7df4bc4f60fa2f42c602c389772d10bc5400001000000064656d6f303631372e746573746e65746ce5b0c72ea21d29c9cf8cde859d2ddd466a70e1f8f1069742876e259fb157440100000003000000ed95c28f055a2a000000000000").unwrap();
+    let tx = rcc_wasm::near_parse(&data).unwrap();
+    assert_eq!(get(&tx, &["formatted_json", "receiver_id"]), "demo0617.testnet");
+    assert_eq!(error_code(rcc_wasm::near_parse(&[0])), "NearParseFailed");
+}